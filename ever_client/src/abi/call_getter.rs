@@ -0,0 +1,131 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::abi::{encode_message, Abi, CallSet, Error, ParamsOfEncodeMessage, Signer};
+use crate::boc::internal::{deserialize_object_from_boc, serialize_object_to_base64};
+use crate::client::ClientContext;
+use crate::encoding::account_decode;
+use crate::error::ClientResult;
+use crate::processing::parsing::decode_output;
+use crate::tvm::call_tvm::call_tvm_msg_with_gas;
+use crate::tvm::types::ResolvedExecutionOptions;
+use crate::tvm::ExecutionOptions;
+use ever_block::{Account, Message};
+use serde_json::Value;
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfCallGetter {
+    /// Contract ABI.
+    pub abi: Abi,
+
+    /// Account BOC encoded in `base64`.
+    ///
+    /// If not specified, the account is fetched from the network by `address`.
+    pub account: Option<String>,
+
+    /// Contract address.
+    ///
+    /// Used to fetch the account if `account` is not specified.
+    pub address: Option<String>,
+
+    /// Getter name or function id encoded as string in hex (starting with 0x).
+    pub function_name: String,
+
+    /// Getter input parameters according to ABI.
+    pub input: Option<Value>,
+
+    /// Execution options.
+    pub execution_options: Option<ExecutionOptions>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfCallGetter {
+    /// Decoded getter output.
+    pub output: Option<Value>,
+
+    /// Gas used by the getter execution.
+    pub gas_used: u64,
+}
+
+async fn resolve_account(
+    context: &Arc<ClientContext>,
+    params: &ParamsOfCallGetter,
+) -> ClientResult<String> {
+    if let Some(account) = &params.account {
+        return Ok(account.clone());
+    }
+    let address = params
+        .address
+        .as_ref()
+        .ok_or_else(|| Error::required_account_missing_for_call_getter())?;
+    let address = account_decode(address)?;
+    let account =
+        crate::processing::fetching::fetch_account(context.clone(), &address, "boc").await?;
+    account["boc"]
+        .as_str()
+        .map(|boc| boc.to_string())
+        .ok_or_else(|| crate::tvm::Error::account_missing(&address))
+}
+
+/// Calls the getter of the ABI-compatible contract.
+///
+/// Builds an unsigned external message with the default header values,
+/// runs it on the account with TVM and decodes the getter output.
+/// Combines `encode_message`, `tvm.run_tvm` and output decoding in a single call.
+///
+/// The account is either provided as a BOC or fetched from the network by address.
+#[api_function]
+pub async fn call_getter(
+    context: Arc<ClientContext>,
+    params: ParamsOfCallGetter,
+) -> ClientResult<ResultOfCallGetter> {
+    let account_boc = resolve_account(&context, &params).await?;
+    let mut account =
+        deserialize_object_from_boc::<Account>(&context, &account_boc, "account")?.object;
+    let address = account
+        .get_addr()
+        .ok_or_else(|| crate::tvm::Error::invalid_account_boc("Account has no address"))?
+        .to_string();
+
+    let message = encode_message(
+        context.clone(),
+        ParamsOfEncodeMessage {
+            abi: params.abi.clone(),
+            address: Some(address),
+            call_set: Some(CallSet {
+                function_name: params.function_name.clone(),
+                header: None,
+                input: params.input.clone(),
+            }),
+            signer: Signer::None,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let message =
+        deserialize_object_from_boc::<Message>(&context, &message.message, "message")?.object;
+
+    let options =
+        ResolvedExecutionOptions::from_options(&context, params.execution_options).await?;
+    let (messages, gas_used) = call_tvm_msg_with_gas(&mut account, options, &message)?;
+    let messages = messages
+        .iter()
+        .map(|message| serialize_object_to_base64(message, "message"))
+        .collect::<ClientResult<Vec<_>>>()?;
+
+    Ok(ResultOfCallGetter {
+        output: decode_output(&context, &params.abi, messages)?.output,
+        gas_used,
+    })
+}
@@ -0,0 +1,266 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::abi::{Abi, Error, FunctionHeader};
+use crate::boc::internal::deserialize_object_from_boc;
+use crate::client::ClientContext;
+use crate::error::{format_time, ClientResult};
+use crate::utils::{convert_address, AddressStringFormat, ParamsOfConvertAddress};
+use ever_abi::token::Detokenizer;
+use ever_abi::{Token, TokenValue};
+use ever_block::{CommonMsgInfo, MsgAddress};
+use serde_json::Value;
+use std::sync::Arc;
+
+const TOKEN_DECIMALS: usize = 9;
+
+#[derive(Serialize, Deserialize, ApiType, Default)]
+pub struct ParamsOfDescribeMessage {
+    /// Contract ABI.
+    pub abi: Abi,
+
+    /// Message BOC encoded in `base64`.
+    ///
+    /// Usually it is an unsigned message produced by `encode_message` with `Signer::External`.
+    pub message: String,
+
+    /// Function name or function id if is known in advance.
+    pub function_name: Option<String>,
+
+    /// Format of the addresses in the description. Default is `Hex`.
+    pub address_format: Option<AddressStringFormat>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct DescribedParam {
+    /// Parameter name.
+    pub name: String,
+
+    /// Parameter ABI type.
+    pub param_type: String,
+
+    /// Parameter value.
+    ///
+    /// Addresses are converted to the requested format,
+    /// `token` values are formatted in tokens.
+    pub value: Value,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq)]
+pub struct ResultOfDescribeMessage {
+    /// Message is internal.
+    pub is_internal: bool,
+
+    /// Message deploys the contract.
+    pub is_deploy: bool,
+
+    /// Source address.
+    pub src: Option<String>,
+
+    /// Destination address.
+    pub dst: String,
+
+    /// Value in nanotokens attached to the internal message.
+    pub value: Option<String>,
+
+    /// Value attached to the internal message formatted in tokens, e.g. `1.5`.
+    pub value_tokens: Option<String>,
+
+    /// Called function name.
+    pub function_name: String,
+
+    /// Called function id encoded as `0x` prefixed hex.
+    pub function_id: String,
+
+    /// Function parameters.
+    pub params: Vec<DescribedParam>,
+
+    /// Function header.
+    pub header: Option<FunctionHeader>,
+
+    /// Human-readable message expiration time.
+    pub expire_at: Option<String>,
+
+    /// Message is already expired.
+    pub expired: bool,
+}
+
+pub(crate) fn format_tokens(nanotokens: u128) -> String {
+    let string = format!("{:0>width$}", nanotokens, width = TOKEN_DECIMALS + 1);
+    let (int, frac) = string.split_at(string.len() - TOKEN_DECIMALS);
+    let frac = frac.trim_end_matches('0');
+    if frac.is_empty() {
+        int.to_string()
+    } else {
+        format!("{}.{}", int, frac)
+    }
+}
+
+struct Describer {
+    context: Arc<ClientContext>,
+    address_format: AddressStringFormat,
+}
+
+impl Describer {
+    fn address(&self, address: String) -> ClientResult<String> {
+        Ok(convert_address(
+            self.context.clone(),
+            ParamsOfConvertAddress {
+                address,
+                output_format: self.address_format.clone(),
+            },
+        )?
+        .address)
+    }
+
+    fn value(&self, value: &TokenValue) -> ClientResult<Value> {
+        Ok(match value {
+            TokenValue::Address(address) | TokenValue::AddressStd(address) => match address {
+                MsgAddress::AddrNone => Value::Null,
+                MsgAddress::AddrStd(_) | MsgAddress::AddrVar(_) => {
+                    Value::String(self.address(address.to_string())?)
+                }
+                _ => Value::String(address.to_string()),
+            },
+            TokenValue::Token(grams) => Value::String(format_tokens(grams.as_u128())),
+            TokenValue::Tuple(tokens) => self.tokens(tokens)?,
+            TokenValue::Array(_, values) | TokenValue::FixedArray(_, values) => Value::Array(
+                values
+                    .iter()
+                    .map(|value| self.value(value))
+                    .collect::<ClientResult<_>>()?,
+            ),
+            TokenValue::Map(key_type, _, values) => {
+                let mut map = serde_json::Map::new();
+                for (key, value) in values {
+                    let key = match key_type {
+                        ever_abi::ParamType::Address | ever_abi::ParamType::AddressStd => {
+                            self.address(key.clone())?
+                        }
+                        _ => key.clone(),
+                    };
+                    map.insert(key, self.value(value)?);
+                }
+                Value::Object(map)
+            }
+            TokenValue::Optional(_, value) => match value {
+                Some(value) => self.value(value)?,
+                None => Value::Null,
+            },
+            TokenValue::Ref(value) => self.value(value)?,
+            _ => {
+                let token = Token {
+                    name: "value".to_string(),
+                    value: value.clone(),
+                };
+                Detokenizer::detokenize_to_json_value(&[token])
+                    .map_err(|err| Error::invalid_message_for_decode(err))?["value"]
+                    .take()
+            }
+        })
+    }
+
+    fn tokens(&self, tokens: &[Token]) -> ClientResult<Value> {
+        let mut result = serde_json::Map::new();
+        for token in tokens {
+            result.insert(token.name.clone(), self.value(&token.value)?);
+        }
+        Ok(Value::Object(result))
+    }
+}
+
+/// Describes message in a human-readable form.
+///
+/// Decodes function call from the message body and returns the destination,
+/// the attached value, the function parameters with formatted addresses and token
+/// amounts and the expiration time. Can be used to show the user what is going to be
+/// signed in the `Signer::External` flow.
+#[api_function]
+pub fn describe_message(
+    context: Arc<ClientContext>,
+    params: ParamsOfDescribeMessage,
+) -> ClientResult<ResultOfDescribeMessage> {
+    let abi = params.abi.abi()?;
+    let message: ever_block::Message =
+        deserialize_object_from_boc(&context, &params.message, "message")
+            .map_err(|err| Error::invalid_message_for_decode(err))?
+            .object;
+    let body = message
+        .body()
+        .ok_or_else(|| Error::invalid_message_for_decode("The message body is empty"))?;
+    let is_internal = message.is_internal();
+
+    let (function, tokens) = if let Some(name) = &params.function_name {
+        let function = abi
+            .function(name)
+            .map_err(|_| Error::invalid_function_name(name))?;
+        let tokens = function
+            .decode_input(body.clone(), is_internal, false)
+            .map_err(|err| Error::invalid_message_for_decode(err))?;
+        (function, tokens)
+    } else {
+        let decoded = abi
+            .decode_input(body.clone(), is_internal, false)
+            .map_err(|err| Error::invalid_message_for_decode(err))?;
+        let function = abi
+            .function(&decoded.function_name)
+            .map_err(|err| Error::invalid_message_for_decode(err))?;
+        (function, decoded.tokens)
+    };
+    let (header, _, _) =
+        ever_abi::Function::decode_header(abi.version(), body, abi.header(), is_internal)
+            .map_err(|err| Error::invalid_message_for_decode(err))?;
+    let header = FunctionHeader::from(&header)?;
+
+    let describer = Describer {
+        context: context.clone(),
+        address_format: params.address_format.unwrap_or_default(),
+    };
+    let mut described_params = Vec::new();
+    for (param, token) in function.inputs.iter().zip(tokens.iter()) {
+        described_params.push(DescribedParam {
+            name: token.name.clone(),
+            param_type: param.kind.type_signature(),
+            value: describer.value(&token.value)?,
+        });
+    }
+
+    let value = if let CommonMsgInfo::IntMsgInfo(header) = message.header() {
+        Some(header.value.grams.as_u128())
+    } else {
+        None
+    };
+    let dst = message
+        .dst()
+        .ok_or_else(|| Error::invalid_message_for_decode("Message has no destination address"))?;
+    let expire = header.as_ref().and_then(|header| header.expire);
+    let now = (context.env.now_ms() / 1000) as u32;
+
+    Ok(ResultOfDescribeMessage {
+        is_internal,
+        is_deploy: message.state_init().is_some(),
+        src: message
+            .src()
+            .map(|src| describer.address(src.to_string()))
+            .transpose()?,
+        dst: describer.address(dst.to_string())?,
+        value: value.map(|value| value.to_string()),
+        value_tokens: value.map(format_tokens),
+        function_name: function.name.clone(),
+        function_id: format!("0x{:08x}", function.get_input_id()),
+        params: described_params,
+        header,
+        expire_at: expire.map(format_time),
+        expired: expire.map(|expire| expire <= now).unwrap_or(false),
+    })
+}
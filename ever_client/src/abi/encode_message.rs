@@ -24,6 +24,8 @@ use super::types::extend_data_to_sign;
 pub struct DeploySet {
     /// Content of TVC file encoded in `base64`.
     /// For compatibility reason this field can contain an encoded  `StateInit`.
+    ///
+    /// TVC v2 contract package provides both the code and the initial data.
     pub tvc: Option<String>,
 
     /// Contract code BOC encoded with base64.
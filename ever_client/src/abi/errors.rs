@@ -19,6 +19,7 @@ pub enum ErrorCode {
     EncodeInitialDataFailed = 314,
     InvalidFunctionName = 315,
     PubKeyNotSupported = 316,
+    RequiredAccountMissingForCallGetter = 317,
 }
 
 pub struct Error;
@@ -46,6 +47,13 @@ impl Error {
         )
     }
 
+    pub fn required_account_missing_for_call_getter() -> ClientError {
+        error(
+            ErrorCode::RequiredAccountMissingForCallGetter,
+            "Either account BOC or address must be provided to call getter.".into(),
+        )
+    }
+
     pub fn missing_required_call_set_for_encode_message() -> ClientError {
         error(
             ErrorCode::RequiredCallSetMissingForEncodeMessage,
@@ -14,9 +14,11 @@
 #[cfg(test)]
 mod tests;
 
+pub(crate) mod call_getter;
 pub(crate) mod decode_boc;
 pub(crate) mod decode_data;
 pub(crate) mod decode_message;
+pub(crate) mod describe_message;
 pub(crate) mod encode_account;
 pub(crate) mod encode_boc;
 pub(crate) mod encode_message;
@@ -30,6 +32,7 @@ mod types;
 
 use serde::{Deserialize, Deserializer};
 
+pub use call_getter::{call_getter, ParamsOfCallGetter, ResultOfCallGetter};
 pub use decode_boc::{decode_boc, ParamsOfDecodeBoc, ResultOfDecodeBoc};
 pub use decode_data::{decode_account_data, ParamsOfDecodeAccountData, ResultOfDecodeAccountData};
 pub use decode_message::{
@@ -37,6 +40,9 @@ pub use decode_message::{
     ParamsOfDecodeMessage, ParamsOfDecodeMessageBody, DataLayout, ParamsOfGetSignatureData,
     ResultOfGetSignatureData
 };
+pub use describe_message::{
+    describe_message, DescribedParam, ParamsOfDescribeMessage, ResultOfDescribeMessage,
+};
 pub use encode_account::{
     encode_account, ParamsOfEncodeAccount, ResultOfEncodeAccount,
 };
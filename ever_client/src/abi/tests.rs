@@ -1662,3 +1662,144 @@ fn test_abi_v24_changes() {
         .unwrap_err();
     assert_eq!(error.code, ErrorCode::PubKeyNotSupported as u32);
 }
+
+#[test]
+fn describe_message() {
+    let client = TestClient::new();
+    let (abi, _) = TestClient::package(EVENTS_OLD, Some(2));
+    let public = "4c7c408ff1ddebb8d6405ee979c716a14fdd6cc08124107a61d3c25597099499";
+    let address = "0:05beb555e942fa744fd96f45a9ea9d0a8248208ca12421947c06e59bc997d309";
+    let expire: u32 = 1599458404;
+
+    let unsigned: ResultOfEncodeMessage = client
+        .request(
+            "abi.encode_message",
+            ParamsOfEncodeMessage {
+                address: Some(address.into()),
+                abi: abi.clone(),
+                call_set: Some(CallSet {
+                    function_name: "returnValue".into(),
+                    header: Some(FunctionHeader {
+                        pubkey: None,
+                        time: Some(1599458364291),
+                        expire: Some(expire),
+                    }),
+                    input: Some(json!({ "id": "0x10" })),
+                }),
+                signer: Signer::External {
+                    public_key: public.into(),
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let described: ResultOfDescribeMessage = client
+        .request(
+            "abi.describe_message",
+            ParamsOfDescribeMessage {
+                abi: abi.clone(),
+                message: unsigned.message.clone(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(!described.is_internal);
+    assert!(!described.is_deploy);
+    assert_eq!(described.dst, address);
+    assert_eq!(described.value, None);
+    assert_eq!(described.function_name, "returnValue");
+    assert_eq!(
+        described.params,
+        vec![DescribedParam {
+            name: "id".into(),
+            param_type: "uint256".into(),
+            value: json!(abi_uint(16, 256)),
+        }]
+    );
+    assert_eq!(described.header.unwrap().expire, Some(expire));
+    assert!(described.expire_at.is_some());
+    assert!(described.expired);
+
+    let error = client
+        .request::<_, ResultOfDescribeMessage>(
+            "abi.describe_message",
+            ParamsOfDescribeMessage {
+                abi,
+                message: unsigned.message,
+                function_name: Some("unknown".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidFunctionName as u32);
+
+    assert_eq!(super::describe_message::format_tokens(0), "0");
+    assert_eq!(super::describe_message::format_tokens(1_500_000_000), "1.5");
+    assert_eq!(super::describe_message::format_tokens(1), "0.000000001");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn call_getter() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(EVENTS, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let deploy_message: ResultOfEncodeMessage = client
+        .request_async(
+            "abi.encode_message",
+            ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: CallSet::some_with_function("constructor"),
+                signer: Signer::Keys { keys },
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let deployed: crate::tvm::ResultOfRunExecutor = client
+        .request_async(
+            "tvm.run_executor",
+            crate::tvm::ParamsOfRunExecutor {
+                message: deploy_message.message,
+                account: crate::tvm::AccountForExecutor::Uninit,
+                return_updated_account: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let result: ResultOfCallGetter = client
+        .request_async(
+            "abi.call_getter",
+            ParamsOfCallGetter {
+                abi: abi.clone(),
+                account: Some(deployed.account),
+                function_name: "returnValue".into(),
+                input: Some(json!({ "id": 7 })),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.output, Some(json!({ "value0": abi_uint(7, 256) })));
+    assert!(result.gas_used > 0);
+
+    let error = client
+        .request_async::<_, ResultOfCallGetter>(
+            "abi.call_getter",
+            ParamsOfCallGetter {
+                abi,
+                function_name: "returnValue".into(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.code,
+        ErrorCode::RequiredAccountMissingForCallGetter as u32
+    );
+}
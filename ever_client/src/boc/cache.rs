@@ -11,10 +11,11 @@
 * limitations under the License.
 */
 
-use super::Error;
+use super::persistent::PersistentBocs;
+use super::{BocConfig, Error};
 use crate::boc::internal::{
-    deserialize_cell_from_base64, deserialize_cell_from_boc, serialize_cell_to_base64,
-    DeserializedBoc,
+    decode_boc_base64, deserialize_cell_from_boc, deserialize_cell_from_bytes,
+    serialize_cell_to_base64, serialize_cell_to_bytes, DeserializedBoc,
 };
 use crate::client::ClientContext;
 use crate::error::ClientResult;
@@ -24,6 +25,7 @@ use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 #[allow(unused_imports)]
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 use ever_block::{Cell, UInt256};
 
@@ -88,6 +90,12 @@ pub enum BocCacheType {
     /// (least recently used) cache lifecycle. BOC resides there until it is replaced
     /// with other BOCs if it is not used
     Unpinned,
+    /// BOC is placed into a common BOC pool like `Unpinned` and is also saved into
+    /// the persistent local storage (the file system, not available for the web).
+    /// Such BOC survives the client's context destruction: the references to it are resolved
+    /// from the local storage by any context using the same local storage
+    /// if the BOC is missing in the memory cache.
+    Persistent,
 }
 
 impl Default for BocCacheType {
@@ -111,17 +119,31 @@ pub struct CachedBocs {
     cache_size: usize,
 }
 
+#[derive(Default)]
+struct CacheCounters {
+    memory_hits: AtomicU64,
+    memory_misses: AtomicU64,
+    storage_hits: AtomicU64,
+    storage_misses: AtomicU64,
+}
+
 pub struct Bocs {
     pinned: RwLock<HashMap<UInt256, PinnedBoc>>,
     cached: Mutex<CachedBocs>,
     max_cache_size: usize,
+    config: BocConfig,
+    pub(crate) persistent: PersistentBocs,
+    counters: CacheCounters,
 }
 
 impl Bocs {
-    pub(crate) fn new(max_cache_size: u32) -> Self {
-        let max_cache_size = (max_cache_size as usize)
+    pub(crate) fn new(config: &BocConfig, local_storage_path: Option<String>) -> Self {
+        let max_cache_size = (config.cache_max_size as usize)
             .checked_mul(1024) // kilobytes in config
             .unwrap_or(std::usize::MAX);
+        let max_local_cache_size = (config.local_cache_max_size as usize)
+            .checked_mul(1024)
+            .unwrap_or(std::usize::MAX);
         Bocs {
             pinned: RwLock::default(),
             cached: Mutex::new(CachedBocs {
@@ -129,6 +151,9 @@ impl Bocs {
                 cache_size: 0,
             }),
             max_cache_size,
+            config: config.clone(),
+            persistent: PersistentBocs::new(local_storage_path, max_local_cache_size),
+            counters: Default::default(),
         }
     }
 
@@ -222,8 +247,12 @@ impl Bocs {
             let cell = self.get(&hash).ok_or(Error::boc_ref_not_found(boc))?;
             Ok((DeserializedBoc::Cell(cell.clone()), cell))
         } else {
-            deserialize_cell_from_base64(boc, name)
-                .map(|(bytes, cell)| (DeserializedBoc::Bytes(bytes), cell))
+            self.config.check_base64_size(boc)?;
+            let bytes = decode_boc_base64(boc, name)?;
+            self.config.check_boc_bytes(&bytes)?;
+            let cell = deserialize_cell_from_bytes(&bytes, name)?;
+            self.config.check_boc_cell(&cell)?;
+            Ok((DeserializedBoc::Bytes(bytes), cell))
         }
     }
 
@@ -259,16 +288,47 @@ impl Bocs {
             .map(|entry| entry.cell.clone())
     }
 
+    /// Looks up the cell in the memory cache and then in the persistent local storage.
     pub(crate) fn get(&self, hash: &UInt256) -> Option<Cell> {
-        if let Some(cell) = self.get_pinned(&hash) {
-            return Some(cell);
-        }
+        let cell = self.get_pinned(&hash).or_else(|| self.get_cached(&hash));
+        let counter = if cell.is_some() {
+            &self.counters.memory_hits
+        } else {
+            &self.counters.memory_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cell.or_else(|| self.load_stored(hash))
+    }
 
-        if let Some(cell) = self.get_cached(&hash) {
-            return Some(cell);
+    /// Loads the cell from the persistent local storage into the memory cache.
+    fn load_stored(&self, hash: &UInt256) -> Option<Cell> {
+        if !self.persistent.enabled() {
+            return None;
         }
-
-        None
+        let bytes = match self.persistent.get(hash) {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => {
+                self.counters.storage_misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            Err(err) => {
+                log::warn!("Can not load BOC {:x} from the local storage: {}", hash, err);
+                return None;
+            }
+        };
+        let cell = match deserialize_cell_from_bytes(&bytes, "stored BOC") {
+            Ok(cell) if &cell.repr_hash() == hash => cell,
+            _ => {
+                log::warn!("Stored BOC {:x} is corrupted", hash);
+                self.counters.storage_misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+        self.counters.storage_hits.fetch_add(1, Ordering::Relaxed);
+        if let Err(err) = self.add_cached(hash.clone(), cell.clone(), bytes.len()) {
+            log::debug!("Stored BOC {:x} is not cached in memory: {}", hash, err.message);
+        }
+        Some(cell)
     }
 
     pub(crate) fn add(
@@ -281,7 +341,7 @@ impl Bocs {
         log::debug!("Bocs::add {:x}", hash);
         match cache_type {
             BocCacheType::Pinned { pin } => self.add_new_pinned(hash.clone(), pin, cell),
-            BocCacheType::Unpinned => {
+            BocCacheType::Unpinned | BocCacheType::Persistent => {
                 if let Some(_) = self.get_cached(&hash) {
                     return Ok(hash);
                 }
@@ -293,6 +353,28 @@ impl Bocs {
     }
 }
 
+/// Adds the cell into the cache. BOCs with `Persistent` cache type are also
+/// saved into the local storage before the function returns.
+pub(crate) fn add_to_cache(
+    context: &ClientContext,
+    cache_type: BocCacheType,
+    cell: Cell,
+    bytes: Option<Vec<u8>>,
+) -> ClientResult<UInt256> {
+    let persistent = matches!(cache_type, BocCacheType::Persistent);
+    let hash = context
+        .bocs
+        .add(cache_type, cell.clone(), bytes.as_ref().map(|bytes| bytes.len()))?;
+    if persistent && context.bocs.persistent.enabled() {
+        let bytes = match bytes {
+            Some(bytes) => bytes,
+            None => serialize_cell_to_bytes(&cell, "BOC")?,
+        };
+        context.bocs.persistent.put(&hash, &bytes)?;
+    }
+    Ok(hash)
+}
+
 fn parse_boc_ref(boc_ref: &str) -> ClientResult<UInt256> {
     if !boc_ref.starts_with("*") {
         return Err(Error::invalid_boc_ref(
@@ -327,16 +409,13 @@ pub fn cache_set(
     params: ParamsOfBocCacheSet,
 ) -> ClientResult<ResultOfBocCacheSet> {
     let (bytes, cell) = deserialize_cell_from_boc(&context, &params.boc, "BOC")?;
-    let size = match bytes {
-        DeserializedBoc::Bytes(bytes) => Some(bytes.len()),
+    let bytes = match bytes {
+        DeserializedBoc::Bytes(bytes) => Some(bytes),
         _ => None,
     };
-    context
-        .bocs
-        .add(params.cache_type, cell, size)
-        .map(|hash| ResultOfBocCacheSet {
-            boc_ref: format!("*{:x}", hash),
-        })
+    add_to_cache(&context, params.cache_type, cell, bytes).map(|hash| ResultOfBocCacheSet {
+        boc_ref: format!("*{:x}", hash),
+    })
 }
 
 #[derive(Serialize, Deserialize, Clone, ApiType, Default)]
@@ -368,6 +447,89 @@ pub fn cache_get(
     Ok(ResultOfBocCacheGet { boc })
 }
 
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfBocCacheLoad {
+    /// Reference to the cached BOC
+    pub boc_ref: String,
+    /// Cache type to put the loaded BOC into. Default is `Unpinned`.
+    pub cache_type: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ResultOfBocCacheLoad {
+    /// BOC is found in the memory cache or in the local storage
+    /// and is available by reference.
+    pub loaded: bool,
+}
+
+/// Load BOC from the persistent local storage into memory cache
+///
+/// BOCs saved with `Persistent` cache type are loaded from the local storage automatically
+/// when they are referenced. This function checks that the BOC is available and adds it
+/// with the specified cache type (e.g. pinned), so it is not evicted from the memory cache.
+#[api_function]
+pub async fn cache_load(
+    context: Arc<ClientContext>,
+    params: ParamsOfBocCacheLoad,
+) -> ClientResult<ResultOfBocCacheLoad> {
+    let hash = parse_boc_ref(&params.boc_ref)?;
+    let cache_type = params.cache_type.unwrap_or_default();
+
+    let cell = match context.bocs.get(&hash) {
+        Some(cell) => cell,
+        None => return Ok(ResultOfBocCacheLoad { loaded: false }),
+    };
+    context.bocs.add(cache_type, cell, None)?;
+
+    Ok(ResultOfBocCacheLoad { loaded: true })
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ResultOfBocCacheStats {
+    /// Number of pinned BOCs in memory
+    pub pinned_count: u32,
+    /// Number of unpinned BOCs in memory
+    pub cached_count: u32,
+    /// Size of unpinned BOCs in memory in bytes
+    pub cached_size: u64,
+    /// Number of BOC references resolved from memory
+    pub memory_hits: u64,
+    /// Number of BOC references not found in memory
+    pub memory_misses: u64,
+    /// Number of BOCs loaded from the local storage
+    pub storage_hits: u64,
+    /// Number of BOCs not found in the local storage
+    pub storage_misses: u64,
+    /// Number of BOCs in the local storage
+    pub stored_count: u32,
+    /// Size of BOCs in the local storage in bytes
+    pub stored_size: u64,
+}
+
+/// Get BOC cache statistics
+#[api_function]
+pub async fn cache_stats(context: Arc<ClientContext>) -> ClientResult<ResultOfBocCacheStats> {
+    let bocs = &context.bocs;
+    let pinned_count = bocs.pinned.read().unwrap().len() as u32;
+    let (cached_count, cached_size) = {
+        let lock = bocs.cached.lock().unwrap();
+        (lock.bocs.len() as u32, lock.cache_size as u64)
+    };
+    let (stored_count, stored_size) = bocs.persistent.stats()?;
+
+    Ok(ResultOfBocCacheStats {
+        pinned_count,
+        cached_count,
+        cached_size,
+        memory_hits: bocs.counters.memory_hits.load(Ordering::Relaxed),
+        memory_misses: bocs.counters.memory_misses.load(Ordering::Relaxed),
+        storage_hits: bocs.counters.storage_hits.load(Ordering::Relaxed),
+        storage_misses: bocs.counters.storage_misses.load(Ordering::Relaxed),
+        stored_count: stored_count as u32,
+        stored_size: stored_size as u64,
+    })
+}
+
 #[derive(Serialize, Deserialize, Clone, ApiType, Default)]
 pub struct ParamsOfBocCacheUnpin {
     /// Pinned name
@@ -0,0 +1,400 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::abi::AbiParam;
+use crate::boc::internal::{deserialize_cell_from_boc, serialize_cell_to_boc};
+use crate::boc::reader::query::{read_int, read_uint};
+use crate::boc::{BocCacheType, Error};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use ever_abi::contract::MAX_SUPPORTED_VERSION;
+use ever_abi::token::{Detokenizer, Tokenizer};
+use ever_abi::TokenValue;
+use ever_block::{BuilderData, Cell, HashmapE, HashmapType, SliceData};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{Num, One};
+use serde_json::Value;
+use std::convert::TryInto;
+use std::sync::Arc;
+
+/// Encoding of the dictionary values.
+#[derive(Serialize, Deserialize, Clone, ApiType, Debug)]
+#[serde(tag = "type")]
+pub enum DictValueType {
+    /// Value is a BOC of the cell with the value data and references.
+    Cell,
+    /// Value is an integer encoded as decimal string.
+    ///
+    /// Numbers and `0x` prefixed hexadecimal strings are also accepted for input.
+    Integer {
+        /// Integer size in bits.
+        size: u32,
+        /// Integer is signed. Default is `false`.
+        signed: Option<bool>,
+    },
+    /// Value is a JSON object encoded with ABI parameters.
+    Abi {
+        /// ABI parameters of the value.
+        params: Vec<AbiParam>,
+    },
+}
+
+impl Default for DictValueType {
+    fn default() -> Self {
+        DictValueType::Cell
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ParamsOfDictGet {
+    /// Dictionary root cell BOC encoded as base64 or BOC cache key.
+    ///
+    /// It is the cell referenced by the `HashmapE` value. Missing for an empty dictionary.
+    pub dict: Option<String>,
+    /// Key size in bits.
+    pub key_size: u32,
+    /// Key as an unsigned integer encoded as decimal or `0x` prefixed hexadecimal string.
+    pub key: String,
+    /// Value encoding. Default is `Cell`.
+    pub value_type: Option<DictValueType>,
+    /// Cache type to put the `Cell` values. The BOCs themselves are returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ResultOfDictGet {
+    /// Value of the entry. Missing if the key is not found.
+    pub value: Option<Value>,
+}
+
+/// Reads the dictionary entry.
+#[api_function]
+pub fn dict_get(
+    context: Arc<ClientContext>,
+    params: ParamsOfDictGet,
+) -> ClientResult<ResultOfDictGet> {
+    let key_size = params.key_size as usize;
+    let dict = load_dict(&context, &params.dict, key_size)?;
+    let value_type = params.value_type.unwrap_or_default();
+
+    let value = dict
+        .get(parse_dict_key(&params.key, key_size)?)
+        .map_err(|err| Error::invalid_boc(err))?
+        .map(|value| decode_value(&context, value, &value_type, params.boc_cache.clone()))
+        .transpose()?;
+
+    Ok(ResultOfDictGet { value })
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ParamsOfDictSet {
+    /// Dictionary root cell BOC encoded as base64 or BOC cache key.
+    ///
+    /// It is the cell referenced by the `HashmapE` value. Missing for an empty dictionary.
+    pub dict: Option<String>,
+    /// Key size in bits.
+    pub key_size: u32,
+    /// Key as an unsigned integer encoded as decimal or `0x` prefixed hexadecimal string.
+    pub key: String,
+    /// Value of the entry encoded according to `value_type`.
+    pub value: Value,
+    /// Value encoding. Default is `Cell`.
+    pub value_type: Option<DictValueType>,
+    /// Cache type to put the result. The BOC itself returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ResultOfDictUpdate {
+    /// Updated dictionary root cell BOC encoded as base64 or BOC cache key.
+    /// Missing if the dictionary became empty.
+    pub dict: Option<String>,
+}
+
+/// Inserts or replaces the dictionary entry.
+#[api_function]
+pub fn dict_set(
+    context: Arc<ClientContext>,
+    params: ParamsOfDictSet,
+) -> ClientResult<ResultOfDictUpdate> {
+    let key_size = params.key_size as usize;
+    let mut dict = load_dict(&context, &params.dict, key_size)?;
+    let value_type = params.value_type.unwrap_or_default();
+
+    let value = encode_value(&context, &params.value, &value_type)?;
+    dict.set_builder(parse_dict_key(&params.key, key_size)?, &value)
+        .map_err(|err| Error::invalid_dict_value(err))?;
+
+    Ok(ResultOfDictUpdate {
+        dict: serialize_dict(&context, &dict, params.boc_cache)?,
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ParamsOfDictRemove {
+    /// Dictionary root cell BOC encoded as base64 or BOC cache key.
+    ///
+    /// It is the cell referenced by the `HashmapE` value. Missing for an empty dictionary.
+    pub dict: Option<String>,
+    /// Key size in bits.
+    pub key_size: u32,
+    /// Key as an unsigned integer encoded as decimal or `0x` prefixed hexadecimal string.
+    pub key: String,
+    /// Cache type to put the result. The BOC itself returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+/// Removes the dictionary entry.
+///
+/// The dictionary is returned unchanged if the key is not found.
+#[api_function]
+pub fn dict_remove(
+    context: Arc<ClientContext>,
+    params: ParamsOfDictRemove,
+) -> ClientResult<ResultOfDictUpdate> {
+    let key_size = params.key_size as usize;
+    let mut dict = load_dict(&context, &params.dict, key_size)?;
+
+    dict.remove(parse_dict_key(&params.key, key_size)?)
+        .map_err(|err| Error::invalid_boc(err))?;
+
+    Ok(ResultOfDictUpdate {
+        dict: serialize_dict(&context, &dict, params.boc_cache)?,
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ParamsOfDictIterate {
+    /// Dictionary root cell BOC encoded as base64 or BOC cache key.
+    ///
+    /// It is the cell referenced by the `HashmapE` value. Missing for an empty dictionary.
+    pub dict: Option<String>,
+    /// Key size in bits.
+    pub key_size: u32,
+    /// Value encoding. Default is `Cell`.
+    pub value_type: Option<DictValueType>,
+    /// Cache type to put the `Cell` values. The BOCs themselves are returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug, PartialEq)]
+pub struct DictEntry {
+    /// Key as an unsigned integer encoded as decimal string.
+    pub key: String,
+    /// Value of the entry.
+    pub value: Value,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ResultOfDictIterate {
+    /// Dictionary entries in the ascending order of keys.
+    pub entries: Vec<DictEntry>,
+}
+
+/// Returns all of the dictionary entries.
+#[api_function]
+pub fn dict_iterate(
+    context: Arc<ClientContext>,
+    params: ParamsOfDictIterate,
+) -> ClientResult<ResultOfDictIterate> {
+    let key_size = params.key_size as usize;
+    let dict = load_dict(&context, &params.dict, key_size)?;
+    let value_type = params.value_type.unwrap_or_default();
+
+    let mut raw_entries = Vec::new();
+    dict.iterate_slices(|key, value| {
+        raw_entries.push((key, value));
+        Ok(true)
+    })
+    .map_err(|err| Error::invalid_boc(err))?;
+
+    let mut entries = Vec::with_capacity(raw_entries.len());
+    for (mut key, value) in raw_entries {
+        entries.push(DictEntry {
+            key: read_uint(&mut key, key_size)?.to_string(),
+            value: decode_value(&context, value, &value_type, params.boc_cache.clone())?,
+        });
+    }
+
+    Ok(ResultOfDictIterate { entries })
+}
+
+/// Parses unsigned integer key encoded as decimal or `0x` prefixed hexadecimal string.
+pub(crate) fn parse_dict_key(key: &str, key_size: usize) -> ClientResult<SliceData> {
+    let number = match key.strip_prefix("0x") {
+        Some(hex) => BigUint::from_str_radix(hex, 16),
+        None => BigUint::from_str_radix(key, 10),
+    }
+    .map_err(|err| Error::invalid_dict_key(format!("{}: {}", key, err)))?;
+    if number.bits() > key_size as u64 {
+        return Err(Error::invalid_dict_key(format!(
+            "{} does not fit into {} bits",
+            key, key_size
+        )));
+    }
+    Ok(SliceData::from_raw(uint_to_bytes(number, key_size), key_size))
+}
+
+/// Returns `size` bits of the number aligned to the left of the byte array.
+fn uint_to_bytes(number: BigUint, size: usize) -> Vec<u8> {
+    let len = (size + 7) / 8;
+    let padding = len * 8 - size;
+    let bytes = (number << padding).to_bytes_be();
+    let mut data = vec![0; len - bytes.len().min(len)];
+    data.extend_from_slice(&bytes);
+    data
+}
+
+fn check_integer_size(size: u32) -> ClientResult<usize> {
+    if size == 0 || size > 256 {
+        return Err(Error::invalid_dict_value(format!(
+            "integer size must be in 1..256 bits, got {}",
+            size
+        )));
+    }
+    Ok(size as usize)
+}
+
+fn load_dict(
+    context: &ClientContext,
+    dict: &Option<String>,
+    key_size: usize,
+) -> ClientResult<HashmapE> {
+    let root = match dict {
+        Some(boc) => Some(deserialize_cell_from_boc(context, boc, "dictionary")?.1),
+        None => None,
+    };
+    Ok(HashmapE::with_hashmap(key_size, root))
+}
+
+fn serialize_dict(
+    context: &ClientContext,
+    dict: &HashmapE,
+    boc_cache: Option<BocCacheType>,
+) -> ClientResult<Option<String>> {
+    dict.data()
+        .map(|root| serialize_cell_to_boc(context, root.clone(), "dictionary", boc_cache))
+        .transpose()
+}
+
+fn parse_integer(value: &Value) -> ClientResult<BigInt> {
+    if let Some(number) = value.as_i64() {
+        return Ok(BigInt::from(number));
+    }
+    if let Some(number) = value.as_u64() {
+        return Ok(BigInt::from(number));
+    }
+    let string = value
+        .as_str()
+        .ok_or_else(|| Error::invalid_dict_value(format!("integer expected, got {}", value)))?;
+    let (negative, string) = match string.strip_prefix('-') {
+        Some(string) => (true, string),
+        None => (false, string),
+    };
+    let number = match string.strip_prefix("0x") {
+        Some(hex) => BigInt::from_str_radix(hex, 16),
+        None => BigInt::from_str_radix(string, 10),
+    }
+    .map_err(|err| Error::invalid_dict_value(format!("{}: {}", value, err)))?;
+    Ok(if negative { -number } else { number })
+}
+
+fn encode_value(
+    context: &ClientContext,
+    value: &Value,
+    value_type: &DictValueType,
+) -> ClientResult<BuilderData> {
+    match value_type {
+        DictValueType::Cell => {
+            let boc = value.as_str().ok_or_else(|| {
+                Error::invalid_dict_value(format!("cell BOC expected, got {}", value))
+            })?;
+            let cell = deserialize_cell_from_boc(context, boc, "dictionary value")?.1;
+            BuilderData::from_cell(&cell).map_err(|err| Error::invalid_dict_value(err))
+        }
+        DictValueType::Integer { size, signed } => {
+            let size = check_integer_size(*size)?;
+            let number = parse_integer(value)?;
+            let fits = if signed.unwrap_or(false) {
+                let bound = BigInt::one() << (size - 1);
+                number >= -&bound && number < bound
+            } else {
+                number.sign() != Sign::Minus && number.bits() <= size as u64
+            };
+            if !fits {
+                return Err(Error::invalid_dict_value(format!(
+                    "integer {} does not fit into {} bits",
+                    number, size
+                )));
+            }
+            let unsigned = if number.sign() == Sign::Minus {
+                number + (BigInt::one() << size)
+            } else {
+                number
+            };
+            let data = uint_to_bytes(unsigned.to_biguint().unwrap_or_default(), size);
+            BuilderData::with_raw(data, size).map_err(|err| Error::invalid_dict_value(err))
+        }
+        DictValueType::Abi { params } => {
+            let mut abi_params = Vec::with_capacity(params.len());
+            for param in params.clone() {
+                abi_params.push(param.try_into()?)
+            }
+            let tokens = Tokenizer::tokenize_all_params(&abi_params, value)
+                .map_err(|err| Error::invalid_dict_value(err))?;
+            TokenValue::pack_values_into_chain(&tokens, Vec::new(), &MAX_SUPPORTED_VERSION)
+                .map_err(|err| Error::invalid_dict_value(err))
+        }
+    }
+}
+
+fn decode_value(
+    context: &ClientContext,
+    mut value: SliceData,
+    value_type: &DictValueType,
+    boc_cache: Option<BocCacheType>,
+) -> ClientResult<Value> {
+    match value_type {
+        DictValueType::Cell => {
+            let cell: Cell = value.into_cell();
+            Ok(Value::String(serialize_cell_to_boc(
+                context,
+                cell,
+                "dictionary value",
+                boc_cache,
+            )?))
+        }
+        DictValueType::Integer { size, signed } => {
+            let size = check_integer_size(*size)?;
+            let number = if signed.unwrap_or(false) {
+                read_int(&mut value, size).map(|number| number.to_string())
+            } else {
+                read_uint(&mut value, size).map(|number| number.to_string())
+            };
+            Ok(Value::String(
+                number.map_err(|err| Error::invalid_dict_value(err.message))?,
+            ))
+        }
+        DictValueType::Abi { params } => {
+            let mut abi_params = Vec::with_capacity(params.len());
+            for param in params.clone() {
+                abi_params.push(param.try_into()?)
+            }
+            let tokens =
+                TokenValue::decode_params(&abi_params, value, &MAX_SUPPORTED_VERSION, false)
+                    .map_err(|err| Error::invalid_dict_value(err))?;
+            Detokenizer::detokenize_to_json_value(&tokens)
+                .map_err(|err| Error::invalid_dict_value(err))
+        }
+    }
+}
@@ -0,0 +1,328 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::abi::{decode_account_data, Abi, ParamsOfDecodeAccountData};
+use crate::boc::internal::{deserialize_cell_from_boc, serialize_cell_to_base64};
+use crate::boc::{parse_account, Error, ParamsOfParse};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use ever_block::{Account, Cell, Deserializable, SliceData, UInt256};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Account fields containing whole BOCs which are compared by hashes instead.
+const SKIPPED_ACCOUNT_FIELDS: [&str; 4] = ["boc", "code", "data", "library"];
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfBocDiff {
+    /// Old BOC encoded as base64 or BOC cache key.
+    pub old_boc: String,
+    /// New BOC encoded as base64 or BOC cache key.
+    pub new_boc: String,
+    /// Contract ABI to decode the data fields.
+    ///
+    /// If both BOCs are accounts then the account data is decoded,
+    /// otherwise the BOCs themselves are decoded as the contract data.
+    pub abi: Option<Abi>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Debug, PartialEq)]
+pub enum CellDiffKind {
+    /// Cell subtree exists only in the new BOC.
+    Added,
+    /// Cell subtree exists only in the old BOC.
+    Removed,
+    /// Cell data or references count differs.
+    Changed,
+}
+
+impl Default for CellDiffKind {
+    fn default() -> Self {
+        CellDiffKind::Changed
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug, PartialEq)]
+pub struct CellDiff {
+    /// Path to the cell as a sequence of reference indexes from the root, e.g. `/0/1`.
+    /// Root cell path is `/`.
+    pub path: String,
+    /// Kind of the difference.
+    pub kind: CellDiffKind,
+    /// Hash of the old cell encoded with hex.
+    pub old_hash: Option<String>,
+    /// Hash of the new cell encoded with hex.
+    pub new_hash: Option<String>,
+    /// Data of the old cell encoded with hex. The last byte is padded with zero bits.
+    pub old_data: Option<String>,
+    /// Data of the new cell encoded with hex. The last byte is padded with zero bits.
+    pub new_data: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug, PartialEq)]
+pub struct FieldDiff {
+    /// Path to the field, e.g. `account/balance` or `data/owner`.
+    pub path: String,
+    /// Old field value. Missing if the field is added.
+    pub old_value: Option<Value>,
+    /// New field value. Missing if the field is removed.
+    pub new_value: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ResultOfBocDiff {
+    /// Different cells.
+    ///
+    /// The subtrees with the same hash are skipped, as well as the subtrees
+    /// which are present anywhere in the other BOC, e.g. moved to another path.
+    /// Added and removed subtrees are reported by their root cells only.
+    /// The cells which differ only in their references are not reported.
+    pub cells: Vec<CellDiff>,
+    /// Different fields of the accounts and of the decoded data.
+    ///
+    /// Is empty if BOCs are not accounts and ABI is not specified.
+    pub fields: Vec<FieldDiff>,
+}
+
+/// Compares two BOCs.
+///
+/// Walks both cell trees and reports added, removed and changed cells with paths.
+/// Each pair of the different subtrees is compared once, so the cost is bounded
+/// by the number of the unique cells.
+/// For accounts also compares the parsed account fields, and if ABI is specified,
+/// the decoded data fields.
+#[api_function]
+pub fn diff(context: Arc<ClientContext>, params: ParamsOfBocDiff) -> ClientResult<ResultOfBocDiff> {
+    let (_, old) = deserialize_cell_from_boc(&context, &params.old_boc, "old BOC")?;
+    let (_, new) = deserialize_cell_from_boc(&context, &params.new_boc, "new BOC")?;
+
+    let mut cells = CellsDiff::new(&old, &new);
+    cells.diff(&old, &new, String::new())?;
+    let cells = cells.result;
+
+    let mut fields = Vec::new();
+    let accounts = match (as_account(&old), as_account(&new)) {
+        (Some(old_account), Some(new_account)) => Some((old_account, new_account)),
+        _ => None,
+    };
+    let (old_data, new_data) = if let Some((old_account, new_account)) = accounts {
+        let old_parsed = parse_account_fields(&context, &old)?;
+        let new_parsed = parse_account_fields(&context, &new)?;
+        diff_values("account", Some(&old_parsed), Some(&new_parsed), &mut fields);
+        (old_account.get_data(), new_account.get_data())
+    } else {
+        (Some(old), Some(new))
+    };
+
+    if let Some(abi) = params.abi {
+        let old_data = decode_data(&context, &abi, old_data)?;
+        let new_data = decode_data(&context, &abi, new_data)?;
+        diff_values("data", old_data.as_ref(), new_data.as_ref(), &mut fields);
+    }
+
+    Ok(ResultOfBocDiff { cells, fields })
+}
+
+fn as_account(cell: &Cell) -> Option<Account> {
+    Account::construct_from_cell(cell.clone())
+        .ok()
+        .filter(|account| !account.is_none())
+}
+
+fn parse_account_fields(context: &Arc<ClientContext>, cell: &Cell) -> ClientResult<Value> {
+    let mut parsed = parse_account(
+        context.clone(),
+        ParamsOfParse {
+            boc: serialize_cell_to_base64(cell, "account")?,
+        },
+    )?
+    .parsed;
+    if let Some(parsed) = parsed.as_object_mut() {
+        for field in SKIPPED_ACCOUNT_FIELDS {
+            parsed.remove(field);
+        }
+    }
+    Ok(parsed)
+}
+
+fn decode_data(
+    context: &Arc<ClientContext>,
+    abi: &Abi,
+    data: Option<Cell>,
+) -> ClientResult<Option<Value>> {
+    let data = match data {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+    let result = decode_account_data(
+        context.clone(),
+        ParamsOfDecodeAccountData {
+            abi: abi.clone(),
+            data: serialize_cell_to_base64(&data, "contract data")?,
+            allow_partial: true,
+        },
+    )?;
+    Ok(Some(result.data))
+}
+
+fn cell_data(cell: &Cell) -> ClientResult<String> {
+    let mut slice = SliceData::load_cell(cell.clone()).map_err(|err| Error::invalid_boc(err))?;
+    let bytes = slice
+        .get_next_bits(cell.bit_length())
+        .map_err(|err| Error::invalid_boc(err))?;
+    Ok(hex::encode(bytes))
+}
+
+fn cell_path(path: &str) -> String {
+    if path.is_empty() {
+        "/".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+fn subtree_diff(cell: &Cell, path: &str, kind: CellDiffKind) -> ClientResult<CellDiff> {
+    let hash = Some(cell.repr_hash().as_hex_string());
+    let data = Some(cell_data(cell)?);
+    Ok(match kind {
+        CellDiffKind::Removed => CellDiff {
+            path: cell_path(path),
+            kind,
+            old_hash: hash,
+            old_data: data,
+            ..Default::default()
+        },
+        _ => CellDiff {
+            path: cell_path(path),
+            kind,
+            new_hash: hash,
+            new_data: data,
+            ..Default::default()
+        },
+    })
+}
+
+fn collect_hashes(root: &Cell) -> HashSet<UInt256> {
+    let mut hashes = HashSet::new();
+    let mut stack = vec![root.clone()];
+    while let Some(cell) = stack.pop() {
+        if hashes.insert(cell.repr_hash()) {
+            stack.extend(cell.clone_references());
+        }
+    }
+    hashes
+}
+
+/// Compares the cell trees skipping the subtrees shared by both of them.
+struct CellsDiff {
+    old_cells: HashSet<UInt256>,
+    new_cells: HashSet<UInt256>,
+    // the same pair of subtrees can be reached by many paths in a DAG
+    visited: HashSet<(UInt256, UInt256)>,
+    result: Vec<CellDiff>,
+}
+
+impl CellsDiff {
+    fn new(old: &Cell, new: &Cell) -> Self {
+        Self {
+            old_cells: collect_hashes(old),
+            new_cells: collect_hashes(new),
+            visited: HashSet::new(),
+            result: Vec::new(),
+        }
+    }
+
+    fn removed(&mut self, old: &Cell, path: &str) -> ClientResult<()> {
+        if !self.new_cells.contains(&old.repr_hash()) {
+            self.result.push(subtree_diff(old, path, CellDiffKind::Removed)?);
+        }
+        Ok(())
+    }
+
+    fn added(&mut self, new: &Cell, path: &str) -> ClientResult<()> {
+        if !self.old_cells.contains(&new.repr_hash()) {
+            self.result.push(subtree_diff(new, path, CellDiffKind::Added)?);
+        }
+        Ok(())
+    }
+
+    fn diff(&mut self, old: &Cell, new: &Cell, path: String) -> ClientResult<()> {
+        let (old_hash, new_hash) = (old.repr_hash(), new.repr_hash());
+        if old_hash == new_hash || !self.visited.insert((old_hash.clone(), new_hash.clone())) {
+            return Ok(());
+        }
+        // the subtree present in the other tree is not compared cell by cell,
+        // the other one is reported as a whole if it is not shared too
+        let old_shared = self.new_cells.contains(&old_hash);
+        let new_shared = self.old_cells.contains(&new_hash);
+        if old_shared || new_shared {
+            self.removed(old, &path)?;
+            return self.added(new, &path);
+        }
+
+        let old_data = cell_data(old)?;
+        let new_data = cell_data(new)?;
+        if old_data != new_data
+            || old.bit_length() != new.bit_length()
+            || old.references_count() != new.references_count()
+            || old.cell_type() != new.cell_type()
+        {
+            self.result.push(CellDiff {
+                path: cell_path(&path),
+                kind: CellDiffKind::Changed,
+                old_hash: Some(old_hash.as_hex_string()),
+                new_hash: Some(new_hash.as_hex_string()),
+                old_data: Some(old_data),
+                new_data: Some(new_data),
+            });
+        }
+
+        let old_refs = old.clone_references();
+        let new_refs = new.clone_references();
+        for i in 0..old_refs.len().max(new_refs.len()) {
+            let child_path = format!("{}/{}", path, i);
+            match (old_refs.get(i), new_refs.get(i)) {
+                (Some(old), Some(new)) => self.diff(old, new, child_path)?,
+                (Some(old), None) => self.removed(old, &child_path)?,
+                (None, Some(new)) => self.added(new, &child_path)?,
+                (None, None) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn diff_values(
+    path: &str,
+    old: Option<&Value>,
+    new: Option<&Value>,
+    result: &mut Vec<FieldDiff>,
+) {
+    match (old, new) {
+        (Some(Value::Object(old)), Some(Value::Object(new))) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                diff_values(&format!("{}/{}", path, key), old.get(key), new.get(key), result);
+            }
+        }
+        (old, new) if old != new => result.push(FieldDiff {
+            path: path.to_string(),
+            old_value: old.cloned(),
+            new_value: new.cloned(),
+        }),
+        _ => {}
+    }
+}
@@ -97,10 +97,21 @@ pub fn encode_boc(
     context: std::sync::Arc<ClientContext>,
     params: ParamsOfEncodeBoc,
 ) -> ClientResult<ResultOfEncodeBoc> {
+    let cell = build_cell(&context, &params.builder)?;
+    Ok(ResultOfEncodeBoc {
+        boc: serialize_cell_to_boc(&context, cell, "encoded cell", params.boc_cache)?,
+    })
+}
+
+/// Builds cell using builder operations.
+pub(crate) fn build_cell(
+    context: &std::sync::Arc<ClientContext>,
+    builder: &Vec<BuilderOp>,
+) -> ClientResult<Cell> {
     let mut stack = Vec::<Builder>::new();
-    let mut builder = Builder::new(&params.builder);
+    let mut builder = Builder::new(builder);
     loop {
-        match builder.build(&context)? {
+        match builder.build(context)? {
             BuildResult::Nested { nested, prev } => {
                 stack.push(prev);
                 builder = nested;
@@ -113,14 +124,7 @@ pub fn encode_boc(
                         .checked_append_reference(cell)
                         .map_err(|err| Error::serialization_error(err, "encoded cell"))?;
                 } else {
-                    return Ok(ResultOfEncodeBoc {
-                        boc: serialize_cell_to_boc(
-                            &context,
-                            cell,
-                            "encoded cell",
-                            params.boc_cache,
-                        )?,
-                    });
+                    return Ok(cell);
                 }
             }
         }
@@ -23,6 +23,16 @@ pub enum ErrorCode {
     InsufficientCacheSize = 205,
     BocRefNotFound = 206,
     InvalidBocRef = 207,
+    InvalidCellQuery = 208,
+    InvalidTlbSchema = 209,
+    InvalidTlbValue = 210,
+    InvalidMerkleProof = 211,
+    InvalidMerkleUpdate = 212,
+    MerkleProofTargetNotFound = 213,
+    InvalidDictKey = 214,
+    InvalidDictValue = 215,
+    BocLimitExceeded = 216,
+    InvalidLibrary = 217,
 }
 pub struct Error;
 
@@ -83,4 +93,79 @@ impl Error {
         error.data["boc_ref"] = boc_ref.into();
         error
     }
+
+    pub fn invalid_cell_query<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::InvalidCellQuery,
+            format!("Invalid cell query: {}", err),
+        )
+    }
+
+    pub fn invalid_tlb_schema<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::InvalidTlbSchema,
+            format!("Invalid TL-B schema: {}", err),
+        )
+    }
+
+    pub fn invalid_tlb_value<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::InvalidTlbValue,
+            format!("Value doesn't match TL-B type: {}", err),
+        )
+    }
+
+    pub fn invalid_merkle_proof<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::InvalidMerkleProof,
+            format!("Invalid Merkle proof: {}", err),
+        )
+    }
+
+    pub fn invalid_merkle_update<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::InvalidMerkleUpdate,
+            format!("Invalid Merkle update: {}", err),
+        )
+    }
+
+    pub fn merkle_proof_target_not_found<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::MerkleProofTargetNotFound,
+            format!("Merkle proof target not found: {}", err),
+        )
+    }
+
+    pub fn invalid_dict_key<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::InvalidDictKey,
+            format!("Invalid dictionary key: {}", err),
+        )
+    }
+
+    pub fn invalid_dict_value<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::InvalidDictValue,
+            format!("Invalid dictionary value: {}", err),
+        )
+    }
+
+    pub fn boc_limit_exceeded(
+        limit: &str,
+        max: impl Into<serde_json::Value>,
+        actual: impl Into<serde_json::Value>,
+    ) -> ClientError {
+        let mut error = error(
+            ErrorCode::BocLimitExceeded,
+            format!("BOC exceeds the `{}` limit", limit),
+        );
+        error.data["limit"] = limit.into();
+        error.data["max"] = max.into();
+        error.data["actual"] = actual.into();
+        error
+    }
+
+    pub fn invalid_library<E: Display>(err: E) -> ClientError {
+        error(ErrorCode::InvalidLibrary, format!("Invalid library: {}", err))
+    }
 }
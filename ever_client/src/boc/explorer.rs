@@ -0,0 +1,432 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::boc::internal::{deserialize_object_from_boc, serialize_cell_to_boc};
+use crate::boc::{BocCacheType, Error};
+use crate::client::ClientContext;
+use crate::encoding::account_decode;
+use crate::error::ClientResult;
+use ever_block::{
+    Block, Cell, Deserializable, DepthBalanceInfo, HashmapAugType, HashmapType, InMsg,
+    MsgAddressInt, OutMsg, ShardAccount, ShardAccounts, ShardStateUnsplit, SliceData, UInt256,
+};
+use std::sync::Arc;
+
+const DEFAULT_ACCOUNTS_LIMIT: u32 = 50;
+
+//------------------------------------------------------------------------ get_shardstate_account
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfGetShardstateAccount {
+    /// Shard state BOC encoded as base64 or BOC cache key.
+    pub shardstate: String,
+    /// Account address.
+    pub address: String,
+    /// Cache type to put the account BOC into.
+    /// The BOC itself returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ResultOfGetShardstateAccount {
+    /// Account BOC encoded as base64 or BOC cache key.
+    ///
+    /// Missing if the shard state has no such account.
+    pub account: Option<String>,
+    /// Hash of the last account transaction encoded with hex.
+    pub last_trans_hash: Option<String>,
+    /// Logical time of the last account transaction.
+    pub last_trans_lt: Option<String>,
+}
+
+/// Extracts the account from the shard state
+///
+/// Looks up the account in the shard state accounts dictionary by address
+/// without converting the shard state to JSON.
+#[api_function]
+pub fn get_shardstate_account(
+    context: Arc<ClientContext>,
+    params: ParamsOfGetShardstateAccount,
+) -> ClientResult<ResultOfGetShardstateAccount> {
+    let state = deserialize_object_from_boc::<ShardStateUnsplit>(
+        &context,
+        &params.shardstate,
+        "shardstate",
+    )?
+    .object;
+    let address = account_decode(&params.address)?;
+    if address.workchain_id() != state.shard().workchain_id() {
+        return Ok(ResultOfGetShardstateAccount::default());
+    }
+
+    let accounts = state
+        .read_accounts()
+        .map_err(|err| Error::invalid_boc(format!("can not read shard state accounts: {}", err)))?;
+    let account = accounts
+        .account(&address.address())
+        .map_err(|err| Error::invalid_boc(format!("can not read shard state account: {}", err)))?;
+    Ok(match account {
+        Some(account) => ResultOfGetShardstateAccount {
+            account: Some(serialize_cell_to_boc(
+                &context,
+                account.account_cell(),
+                "account",
+                params.boc_cache,
+            )?),
+            last_trans_hash: Some(account.last_trans_hash().as_hex_string()),
+            last_trans_lt: Some(format!("0x{:x}", account.last_trans_lt())),
+        },
+        None => ResultOfGetShardstateAccount::default(),
+    })
+}
+
+//---------------------------------------------------------------------- list_shardstate_accounts
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfListShardstateAccounts {
+    /// Shard state BOC encoded as base64 or BOC cache key.
+    pub shardstate: String,
+    /// Address of the last account of the previous page.
+    ///
+    /// The accounts are listed in the ascending order of their addresses,
+    /// starting from the first one if not specified.
+    pub after: Option<String>,
+    /// Maximum number of accounts to return. Default is 50.
+    pub limit: Option<u32>,
+    /// Return the account BOCs. Default is `false`.
+    pub include_boc: Option<bool>,
+    /// Cache type to put the account BOCs into.
+    /// The BOCs themselves returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug, PartialEq)]
+pub struct ShardstateAccount {
+    /// Account address.
+    pub address: String,
+    /// Account balance in nanotokens.
+    pub balance: String,
+    /// Hash of the last account transaction encoded with hex.
+    pub last_trans_hash: String,
+    /// Logical time of the last account transaction.
+    pub last_trans_lt: String,
+    /// Account BOC encoded as base64 or BOC cache key.
+    ///
+    /// Returned only if `include_boc` is `true`.
+    pub boc: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ResultOfListShardstateAccounts {
+    /// Accounts of the page.
+    pub accounts: Vec<ShardstateAccount>,
+    /// There are more accounts after the last returned one.
+    pub has_more: bool,
+}
+
+/// Lists the accounts of the shard state
+///
+/// Walks the shard state accounts dictionary in the ascending order of the addresses
+/// and returns a page of accounts with the balances and the last transactions
+/// without converting the shard state to JSON.
+#[api_function]
+pub fn list_shardstate_accounts(
+    context: Arc<ClientContext>,
+    params: ParamsOfListShardstateAccounts,
+) -> ClientResult<ResultOfListShardstateAccounts> {
+    let state = deserialize_object_from_boc::<ShardStateUnsplit>(
+        &context,
+        &params.shardstate,
+        "shardstate",
+    )?
+    .object;
+    let workchain_id = state.shard().workchain_id();
+    let after = params
+        .after
+        .as_ref()
+        .map(|address| {
+            account_decode(address)
+                .map(|address| UInt256::from_raw(address.address().get_bytestring(0), 256))
+        })
+        .transpose()?;
+    let limit = params.limit.unwrap_or(DEFAULT_ACCOUNTS_LIMIT) as usize;
+    let include_boc = params.include_boc.unwrap_or_default();
+
+    let accounts = state
+        .read_accounts()
+        .map_err(|err| Error::invalid_boc(format!("can not read shard state accounts: {}", err)))?;
+    let mut result = ResultOfListShardstateAccounts::default();
+    let mut next = next_shardstate_account(&accounts, after.as_ref())?;
+    while let Some((id, account, aug)) = next {
+        if result.accounts.len() == limit {
+            result.has_more = true;
+            break;
+        }
+        result.accounts.push(shardstate_account(
+            &context,
+            workchain_id,
+            id.clone(),
+            account,
+            aug,
+            include_boc,
+            &params.boc_cache,
+        )?);
+        next = next_shardstate_account(&accounts, Some(&id))?;
+    }
+    Ok(result)
+}
+
+/// Finds the account with the least address greater than `after`,
+/// or the first account if `after` is not specified.
+fn next_shardstate_account(
+    accounts: &ShardAccounts,
+    after: Option<&UInt256>,
+) -> ClientResult<Option<(UInt256, ShardAccount, DepthBalanceInfo)>> {
+    let read_error =
+        |err| Error::invalid_boc(format!("can not read shard state accounts: {}", err));
+    let key = after.cloned().unwrap_or_default();
+    let leaf = accounts
+        .find_leaf(
+            SliceData::from_raw(key.as_slice().to_vec(), 256),
+            true,
+            after.is_none(),
+            false,
+            &mut 0,
+        )
+        .map_err(read_error)?;
+    let (key, mut value) = match leaf {
+        Some(leaf) => leaf,
+        None => return Ok(None),
+    };
+    // the augmented dictionary leaf keeps the extra value before the value itself
+    let aug = DepthBalanceInfo::construct_from(&mut value).map_err(read_error)?;
+    let account = ShardAccount::construct_from(&mut value).map_err(read_error)?;
+    Ok(Some((UInt256::from_raw(key.data().to_vec(), 256), account, aug)))
+}
+
+fn shardstate_account(
+    context: &ClientContext,
+    workchain_id: i32,
+    id: UInt256,
+    account: ShardAccount,
+    aug: DepthBalanceInfo,
+    include_boc: bool,
+    boc_cache: &Option<BocCacheType>,
+) -> ClientResult<ShardstateAccount> {
+    let address = MsgAddressInt::with_standart(None, workchain_id as i8, id.into())
+        .map_err(|err| Error::invalid_boc(format!("invalid account address: {}", err)))?;
+    Ok(ShardstateAccount {
+        address: address.to_string(),
+        balance: aug.balance().grams.to_string(),
+        last_trans_hash: account.last_trans_hash().as_hex_string(),
+        last_trans_lt: format!("0x{:x}", account.last_trans_lt()),
+        boc: if include_boc {
+            Some(serialize_cell_to_boc(
+                context,
+                account.account_cell(),
+                "account",
+                boc_cache.clone(),
+            )?)
+        } else {
+            None
+        },
+    })
+}
+
+//---------------------------------------------------------------------------- get_block_messages
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfGetBlockMessages {
+    /// Block BOC encoded as base64 or BOC cache key.
+    pub block: String,
+    /// Return the message BOCs. Default is `false`.
+    pub include_boc: Option<bool>,
+    /// Cache type to put the message BOCs into.
+    /// The BOCs themselves returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Debug, PartialEq)]
+pub enum BlockMessageType {
+    /// Inbound external message or outbound external message.
+    External,
+    /// Inbound message delivered via instant hypercube routing.
+    Ihr,
+    /// Internal message processed in the same block it was created in.
+    Immediate,
+    /// Inbound internal message delivered to its destination shard.
+    Final,
+    /// Internal message routed through the shard.
+    Transit,
+    /// Inbound message discarded in its destination shard.
+    DiscardedFinal,
+    /// Transit inbound message discarded.
+    DiscardedTransit,
+    /// Outbound message created in the block and put into the queue.
+    New,
+    /// Outbound message removed from the queue when processed in the same block.
+    DequeueImmediate,
+    /// Outbound message removed from the queue.
+    Dequeue,
+    /// Outbound message removed from the queue, short form.
+    DequeueShort,
+    /// Transit outbound message put back into the queue.
+    TransitRequeued,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Debug, PartialEq)]
+pub struct BlockMessageDescr {
+    /// Message hash encoded with hex.
+    pub msg_id: String,
+    /// Descriptor type.
+    ///
+    /// For the inbound messages: `External`, `Ihr`, `Immediate`, `Final`, `Transit`,
+    /// `DiscardedFinal` or `DiscardedTransit`.
+    /// For the outbound messages: `External`, `Immediate`, `New`, `Transit`,
+    /// `DequeueImmediate`, `Dequeue`, `DequeueShort` or `TransitRequeued`.
+    pub msg_type: BlockMessageType,
+    /// Hash of the transaction processing or producing the message, encoded with hex.
+    pub transaction_id: Option<String>,
+    /// Message BOC encoded as base64 or BOC cache key.
+    ///
+    /// Returned only if `include_boc` is `true` and the descriptor contains the message.
+    pub boc: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ResultOfGetBlockMessages {
+    /// Inbound message descriptors.
+    pub in_msgs: Vec<BlockMessageDescr>,
+    /// Outbound message descriptors.
+    pub out_msgs: Vec<BlockMessageDescr>,
+}
+
+/// Extracts the message descriptors from the block
+///
+/// Reads the inbound and outbound message descriptors of the block
+/// without converting the block to JSON.
+#[api_function]
+pub fn get_block_messages(
+    context: Arc<ClientContext>,
+    params: ParamsOfGetBlockMessages,
+) -> ClientResult<ResultOfGetBlockMessages> {
+    let block = deserialize_object_from_boc::<Block>(&context, &params.block, "block")?.object;
+    let extra = block
+        .read_extra()
+        .map_err(|err| Error::invalid_boc(format!("can not read block extra: {}", err)))?;
+    let include_boc = params.include_boc.unwrap_or_default();
+    let boc = |cell: Option<Cell>| -> ClientResult<Option<String>> {
+        match cell {
+            Some(cell) if include_boc => Ok(Some(serialize_cell_to_boc(
+                &context,
+                cell,
+                "message",
+                params.boc_cache.clone(),
+            )?)),
+            _ => Ok(None),
+        }
+    };
+
+    let mut in_msgs = Vec::new();
+    let mut error = None;
+    extra
+        .read_in_msg_descr()
+        .and_then(|descr| {
+            descr.iterate_with_keys(|msg_id: UInt256, msg: InMsg| {
+                let msg_type = match in_msg_type(&msg) {
+                    Some(msg_type) => msg_type,
+                    None => return Ok(true),
+                };
+                match boc(msg.message_cell().ok()) {
+                    Ok(boc) => in_msgs.push(BlockMessageDescr {
+                        msg_id: msg_id.as_hex_string(),
+                        msg_type,
+                        transaction_id: msg
+                            .transaction_cell()
+                            .map(|cell| cell.repr_hash().as_hex_string()),
+                        boc,
+                    }),
+                    Err(err) => {
+                        error = Some(err);
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            })
+        })
+        .map_err(|err| {
+            Error::invalid_boc(format!("can not read block message descriptors: {}", err))
+        })?;
+
+    let mut out_msgs = Vec::new();
+    extra
+        .read_out_msg_descr()
+        .and_then(|descr| {
+            descr.iterate_with_keys(|msg_id: UInt256, msg: OutMsg| {
+                let msg_type = match out_msg_type(&msg) {
+                    Some(msg_type) => msg_type,
+                    None => return Ok(true),
+                };
+                match boc(msg.message_cell().ok().flatten()) {
+                    Ok(boc) => out_msgs.push(BlockMessageDescr {
+                        msg_id: msg_id.as_hex_string(),
+                        msg_type,
+                        transaction_id: msg
+                            .transaction_cell()
+                            .map(|cell| cell.repr_hash().as_hex_string()),
+                        boc,
+                    }),
+                    Err(err) => {
+                        error = Some(err);
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            })
+        })
+        .map_err(|err| {
+            Error::invalid_boc(format!("can not read block message descriptors: {}", err))
+        })?;
+
+    if let Some(err) = error {
+        return Err(err);
+    }
+    Ok(ResultOfGetBlockMessages { in_msgs, out_msgs })
+}
+
+fn in_msg_type(msg: &InMsg) -> Option<BlockMessageType> {
+    Some(match msg {
+        InMsg::None => return None,
+        InMsg::External(_) => BlockMessageType::External,
+        InMsg::IHR(_) => BlockMessageType::Ihr,
+        InMsg::Immediate(_) => BlockMessageType::Immediate,
+        InMsg::Final(_) => BlockMessageType::Final,
+        InMsg::Transit(_) => BlockMessageType::Transit,
+        InMsg::DiscardedFinal(_) => BlockMessageType::DiscardedFinal,
+        InMsg::DiscardedTransit(_) => BlockMessageType::DiscardedTransit,
+    })
+}
+
+fn out_msg_type(msg: &OutMsg) -> Option<BlockMessageType> {
+    Some(match msg {
+        OutMsg::None => return None,
+        OutMsg::External(_) => BlockMessageType::External,
+        OutMsg::Immediate(_) => BlockMessageType::Immediate,
+        OutMsg::New(_) => BlockMessageType::New,
+        OutMsg::Transit(_) => BlockMessageType::Transit,
+        OutMsg::DequeueImmediate(_) => BlockMessageType::DequeueImmediate,
+        OutMsg::Dequeue(_) => BlockMessageType::Dequeue,
+        OutMsg::DequeueShort(_) => BlockMessageType::DequeueShort,
+        OutMsg::TransitRequeued(_) => BlockMessageType::TransitRequeued,
+    })
+}
@@ -0,0 +1,198 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::boc::internal::{deserialize_cell_from_boc, DeserializedBoc};
+use crate::boc::ExoticCellType;
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use ever_block::{Cell, UInt256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+pub(crate) const BOC_GENERIC_TAG: u32 = 0xb5ee9c72;
+pub(crate) const BOC_INDEXED_TAG: u32 = 0x68ff65f3;
+pub(crate) const BOC_INDEXED_CRC32_TAG: u32 = 0xacc3a728;
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug, PartialEq)]
+pub struct BocHeader {
+    /// BOC contains the cells index.
+    pub has_index: bool,
+    /// BOC ends with the CRC32C checksum.
+    pub has_crc32c: bool,
+    /// Cells index contains the cache bits.
+    pub has_cache_bits: bool,
+    /// Size of the cell references in bytes.
+    pub ref_size: u32,
+    /// Size of the offsets in bytes.
+    pub offset_size: u32,
+    /// Number of cells declared in the header.
+    pub cells_count: u64,
+    /// Number of root cells declared in the header.
+    pub roots_count: u64,
+}
+
+impl BocHeader {
+    /// Reads the BOC header without deserializing the cells.
+    ///
+    /// Returns `None` if the header is malformed: such BOCs are rejected by the deserializer.
+    pub(crate) fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 6 {
+            return None;
+        }
+        let tag = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let (has_index, has_crc32c, has_cache_bits, ref_size) = match tag {
+            BOC_GENERIC_TAG => (
+                bytes[4] & 0x80 != 0,
+                bytes[4] & 0x40 != 0,
+                bytes[4] & 0x20 != 0,
+                bytes[4] & 0x07,
+            ),
+            BOC_INDEXED_TAG => (true, false, false, bytes[4]),
+            BOC_INDEXED_CRC32_TAG => (true, true, false, bytes[4]),
+            _ => return None,
+        };
+        let ref_size = ref_size as usize;
+        if ref_size == 0 || ref_size > 8 || bytes.len() < 6 + ref_size * 2 {
+            return None;
+        }
+        let read = |start: usize| {
+            bytes[start..start + ref_size]
+                .iter()
+                .fold(0u64, |value, byte| (value << 8) | *byte as u64)
+        };
+        Some(Self {
+            has_index,
+            has_crc32c,
+            has_cache_bits,
+            ref_size: ref_size as u32,
+            offset_size: bytes[5] as u32,
+            cells_count: read(6),
+            roots_count: read(6 + ref_size),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfInspectBoc {
+    /// BOC encoded as base64 or BOC cache key.
+    pub boc: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Debug, PartialEq)]
+pub struct ExoticCellsCount {
+    /// Exotic cell type.
+    pub cell_type: ExoticCellType,
+    /// Number of the unique cells of this type.
+    pub count: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug, PartialEq)]
+pub struct ResultOfInspectBoc {
+    /// Number of cells in the tree counting each occurrence of the repeated cells.
+    pub cells_count: u64,
+    /// Number of unique cells.
+    pub unique_cells_count: u32,
+    /// Total number of data bits in the unique cells.
+    pub total_bits: u64,
+    /// Total number of references in the unique cells.
+    pub total_refs: u32,
+    /// Exotic cells found in the tree.
+    pub exotic_cells: Vec<ExoticCellsCount>,
+    /// Root cell depth.
+    pub max_depth: u32,
+    /// Root cell level.
+    pub level: u32,
+    /// Root cell hashes for every level from 0 to `level`, encoded with hex.
+    ///
+    /// The last one is the representation hash.
+    pub hashes: Vec<String>,
+    /// Root cell depths for every level from 0 to `level`.
+    pub depths: Vec<u32>,
+    /// BOC size in bytes. Missing if the BOC is specified by the cache key.
+    pub boc_size: Option<u32>,
+    /// BOC header. Missing if the BOC is specified by the cache key.
+    pub header: Option<BocHeader>,
+}
+
+/// Inspects the BOC
+///
+/// Returns the cell statistics of the BOC tree: number of cells counting the repeated ones
+/// and the unique cells, total bits and references, exotic cells, and the root cell
+/// hashes and depths for every level. For the BOCs encoded as base64 also returns
+/// the size and the serialization flags from the header.
+#[api_function]
+pub fn inspect(
+    context: Arc<ClientContext>,
+    params: ParamsOfInspectBoc,
+) -> ClientResult<ResultOfInspectBoc> {
+    let (boc, cell) = deserialize_cell_from_boc(&context, &params.boc, "BOC")?;
+    let (boc_size, header) = match &boc {
+        DeserializedBoc::Bytes(bytes) => (Some(bytes.len() as u32), BocHeader::parse(bytes)),
+        DeserializedBoc::Cell(_) => (None, None),
+    };
+
+    let mut stats = TreeStats::default();
+    let cells_count = stats.visit(&cell);
+
+    let level = cell.level();
+    Ok(ResultOfInspectBoc {
+        cells_count,
+        unique_cells_count: stats.counts.len() as u32,
+        total_bits: stats.total_bits,
+        total_refs: stats.total_refs,
+        exotic_cells: stats
+            .exotic
+            .into_iter()
+            .map(|(cell_type, count)| ExoticCellsCount { cell_type, count })
+            .collect(),
+        max_depth: cell.repr_depth() as u32,
+        level: level as u32,
+        hashes: (0..=level as usize)
+            .map(|index| cell.hash(index).as_hex_string())
+            .collect(),
+        depths: (0..=level as usize)
+            .map(|index| cell.depth(index) as u32)
+            .collect(),
+        boc_size,
+        header,
+    })
+}
+
+#[derive(Default)]
+struct TreeStats {
+    counts: HashMap<UInt256, u64>,
+    total_bits: u64,
+    total_refs: u32,
+    exotic: BTreeMap<ExoticCellType, u32>,
+}
+
+impl TreeStats {
+    /// Returns the number of cells in the subtree counting the repeated cells.
+    fn visit(&mut self, cell: &Cell) -> u64 {
+        let hash = cell.repr_hash();
+        if let Some(count) = self.counts.get(&hash) {
+            return *count;
+        }
+        self.total_bits += cell.bit_length() as u64;
+        self.total_refs += cell.references_count() as u32;
+        if let Some(exotic) = ExoticCellType::from_cell_type(cell.cell_type()) {
+            *self.exotic.entry(exotic).or_default() += 1;
+        }
+        let mut count = 1u64;
+        for child in cell.clone_references() {
+            count = count.saturating_add(self.visit(&child));
+        }
+        self.counts.insert(hash, count);
+        count
+    }
+}
@@ -11,6 +11,7 @@
 * limitations under the License.
 */
 
+use crate::boc::cache::add_to_cache;
 use crate::boc::{BocCacheType, Error};
 use crate::error::ClientResult;
 use crate::ClientContext;
@@ -30,14 +31,23 @@ pub fn deserialize_cell_from_base64(
     b64: &str,
     name: &str,
 ) -> ClientResult<(Vec<u8>, ever_block::Cell)> {
-    let bytes = base64::decode(&b64)
-        .map_err(|err| Error::invalid_boc(format!("error decode {} BOC base64: {}", name, err)))?;
+    let bytes = decode_boc_base64(b64, name)?;
+    let cell = deserialize_cell_from_bytes(&bytes, name)?;
+    Ok((bytes, cell))
+}
 
-    let cell = ever_block::boc::read_single_root_boc(&bytes).map_err(|err| {
-        Error::invalid_boc(format!("{} BOC deserialization error: {}", name, err))
-    })?;
+pub(crate) fn decode_boc_base64(b64: &str, name: &str) -> ClientResult<Vec<u8>> {
+    base64::decode(&b64)
+        .map_err(|err| Error::invalid_boc(format!("error decode {} BOC base64: {}", name, err)))
+}
 
-    Ok((bytes, cell))
+pub(crate) fn deserialize_cell_from_bytes(
+    bytes: &[u8],
+    name: &str,
+) -> ClientResult<ever_block::Cell> {
+    ever_block::boc::read_single_root_boc(bytes).map_err(|err| {
+        Error::invalid_boc(format!("{} BOC deserialization error: {}", name, err))
+    })
 }
 
 pub fn deserialize_object_from_cell<S: Deserializable>(
@@ -156,10 +166,7 @@ pub fn serialize_cell_to_boc(
     context: &ClientContext, cell: ever_block::Cell, name: &str, boc_cache: Option<BocCacheType>,
 ) -> ClientResult<String> {
     if let Some(cache_type) = boc_cache {
-        context
-            .bocs
-            .add(cache_type, cell, None)
-            .map(|hash| format!("*{:x}", hash))
+        add_to_cache(context, cache_type, cell, None).map(|hash| format!("*{:x}", hash))
     } else {
         serialize_cell_to_base64(&cell, name)
     }
@@ -0,0 +1,400 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::boc::cache::add_to_cache;
+use crate::boc::internal::{deserialize_cell_from_boc, serialize_cell_to_boc};
+use crate::boc::{BocCacheType, Error};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use crate::net::{OrderBy, ParamsOfQueryCollection, SortDirection};
+use ever_block::{
+    Cell, CellType, Deserializable, HashmapE, HashmapType, Serializable, SimpleLib, SliceData,
+    UInt256,
+};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+const ACCOUNTS_PAGE_SIZE: u32 = 50;
+const MAX_ACCOUNTS_PAGES: usize = 20;
+const MISSING_LIBRARY_TTL_MS: u64 = 5 * 60 * 1000;
+
+/// Libraries not found in the network.
+///
+/// The lookup scans the zerostate and the masterchain accounts, so the missing libraries
+/// are not queried again until the TTL expires.
+#[derive(Default)]
+pub(crate) struct MissingLibraries {
+    expire_at: std::sync::Mutex<HashMap<UInt256, u64>>,
+}
+
+impl MissingLibraries {
+    fn contains(&self, hash: &UInt256, now: u64) -> bool {
+        let mut expire_at = self.expire_at.lock().unwrap();
+        match expire_at.get(hash) {
+            Some(time) if *time > now => true,
+            Some(_) => {
+                expire_at.remove(hash);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn insert<'a>(&self, hashes: impl Iterator<Item = &'a UInt256>, now: u64) {
+        let mut expire_at = self.expire_at.lock().unwrap();
+        expire_at.retain(|_, time| *time > now);
+        for hash in hashes {
+            expire_at.insert(hash.clone(), now + MISSING_LIBRARY_TTL_MS);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfResolveLibraries {
+    /// BOC encoded as base64 or BOC cache key.
+    pub boc: String,
+    /// Known libraries.
+    ///
+    /// Object with library hashes encoded as hex as keys and library cell BOCs
+    /// encoded as base64 or BOC cache keys as values.
+    pub libraries: Option<Value>,
+    /// Queries the libraries missing in `libraries` and in the BOC cache from the network.
+    ///
+    /// Default is `true`. Ignored if the client has no network configured.
+    pub query_network: Option<bool>,
+    /// Cache type to put the resolved libraries into. If it is not specified,
+    /// the libraries are returned in the base64 format.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ResultOfResolveLibraries {
+    /// Libraries referenced by the BOC, including the nested library references.
+    ///
+    /// Object with library hashes encoded as hex as keys and library cell BOCs
+    /// encoded as base64 or BOC cache keys as values.
+    pub libraries: Value,
+    /// Hashes of the referenced libraries which can not be resolved.
+    pub missing: Vec<String>,
+}
+
+/// Resolves the library references of the BOC
+///
+/// Finds all library reference cells in the BOC and in the resolved libraries,
+/// takes the libraries from the `libraries` map and the BOC cache, and queries
+/// the missing ones from the zerostate and the public libraries of the masterchain accounts.
+/// The libraries received from the network are saved into the BOC cache
+/// with the `Unpinned` cache type. Network errors are logged and the libraries
+/// are reported as missing. Libraries not found in the network are not queried again
+/// for 5 minutes.
+#[api_function]
+pub async fn resolve_libraries(
+    context: Arc<ClientContext>,
+    params: ParamsOfResolveLibraries,
+) -> ClientResult<ResultOfResolveLibraries> {
+    let (_, cell) = deserialize_cell_from_boc(&context, &params.boc, "BOC")?;
+    let resolved = resolve_library_cells(
+        &context,
+        &[cell],
+        params.libraries.as_ref(),
+        params.query_network.unwrap_or(true),
+    )
+    .await?;
+
+    let mut libraries = serde_json::Map::new();
+    for (hash, cell) in resolved.libraries {
+        libraries.insert(
+            hash.as_hex_string(),
+            serialize_cell_to_boc(&context, cell, "library", params.boc_cache.clone())?.into(),
+        );
+    }
+    Ok(ResultOfResolveLibraries {
+        libraries: libraries.into(),
+        missing: resolved
+            .missing
+            .iter()
+            .map(|hash| hash.as_hex_string())
+            .collect(),
+    })
+}
+
+#[derive(Default)]
+pub(crate) struct ResolvedLibraries {
+    pub libraries: BTreeMap<UInt256, Cell>,
+    pub missing: Vec<UInt256>,
+}
+
+impl ResolvedLibraries {
+    /// Builds the libraries dictionary in the format accepted by TVM.
+    pub fn to_dict(&self) -> ClientResult<HashmapE> {
+        let mut dict = HashmapE::with_bit_len(256);
+        for (hash, cell) in &self.libraries {
+            let value = SimpleLib::new(cell.clone(), true)
+                .write_to_new_cell()
+                .map_err(|err| Error::serialization_error(err, "library"))?;
+            dict.set_builder(SliceData::from_raw(hash.as_slice().to_vec(), 256), &value)
+                .map_err(|err| Error::serialization_error(err, "library"))?;
+        }
+        Ok(dict)
+    }
+}
+
+/// Resolves the libraries referenced by the cell trees.
+///
+/// References to the cells present in the trees themselves (e.g. to the account
+/// own libraries) are considered resolved and are not returned.
+pub(crate) async fn resolve_library_cells(
+    context: &Arc<ClientContext>,
+    roots: &[Cell],
+    provided: Option<&Value>,
+    query_network: bool,
+) -> ClientResult<ResolvedLibraries> {
+    let provided = parse_libraries(context, provided)?;
+    let query_network = query_network && context.get_server_link().is_ok();
+
+    let mut visited = HashSet::new();
+    let mut refs = Vec::new();
+    for root in roots {
+        collect_library_refs(root, &mut visited, &mut refs);
+    }
+    let mut seen: HashSet<UInt256> = visited.clone();
+    let mut pending: Vec<UInt256> = refs
+        .into_iter()
+        .filter(|hash| seen.insert(hash.clone()))
+        .collect();
+
+    let mut result = ResolvedLibraries::default();
+    while !pending.is_empty() {
+        let mut resolved = Vec::new();
+        let mut unresolved = HashSet::new();
+        for hash in pending {
+            match find_local_library(context, &provided, &hash) {
+                Some(cell) => resolved.push(cell),
+                None => {
+                    unresolved.insert(hash);
+                }
+            }
+        }
+        let now = context.env.now_ms();
+        let query: HashSet<UInt256> = unresolved
+            .iter()
+            .filter(|hash| !context.missing_libraries.contains(hash, now))
+            .cloned()
+            .collect();
+        if query_network && !query.is_empty() {
+            let (found, completed) = fetch_libraries(context, &query).await;
+            for cell in found {
+                unresolved.remove(&cell.repr_hash());
+                add_to_cache(context, BocCacheType::Unpinned, cell.clone(), None)?;
+                resolved.push(cell);
+            }
+            if completed {
+                context
+                    .missing_libraries
+                    .insert(query.iter().filter(|hash| unresolved.contains(hash)), now);
+            }
+        }
+        result.missing.extend(unresolved);
+
+        let mut refs = Vec::new();
+        for cell in resolved {
+            collect_library_refs(&cell, &mut visited, &mut refs);
+            result.libraries.insert(cell.repr_hash(), cell);
+        }
+        pending = refs
+            .into_iter()
+            .filter(|hash| seen.insert(hash.clone()))
+            .collect();
+    }
+    Ok(result)
+}
+
+fn parse_libraries(
+    context: &Arc<ClientContext>,
+    libraries: Option<&Value>,
+) -> ClientResult<BTreeMap<UInt256, Cell>> {
+    let mut result = BTreeMap::new();
+    let libraries = match libraries {
+        Some(Value::Null) | None => return Ok(result),
+        Some(Value::Object(libraries)) => libraries,
+        Some(_) => return Err(Error::invalid_library("libraries must be an object")),
+    };
+    for (hash, boc) in libraries {
+        let hash = UInt256::from_str(hash)
+            .map_err(|err| Error::invalid_library(format!("invalid library hash {}: {}", hash, err)))?;
+        let boc = boc
+            .as_str()
+            .ok_or_else(|| Error::invalid_library("library BOC must be a string"))?;
+        let (_, cell) = deserialize_cell_from_boc(context, boc, "library")?;
+        if cell.repr_hash() != hash {
+            return Err(Error::invalid_library(format!(
+                "library cell hash {:x} doesn't match the key {:x}",
+                cell.repr_hash(),
+                hash
+            )));
+        }
+        result.insert(hash, cell);
+    }
+    Ok(result)
+}
+
+fn collect_library_refs(cell: &Cell, visited: &mut HashSet<UInt256>, refs: &mut Vec<UInt256>) {
+    if !visited.insert(cell.repr_hash()) {
+        return;
+    }
+    if cell.cell_type() == CellType::LibraryReference {
+        // library reference cell contains the type byte followed by the library hash
+        if let Some(hash) = cell.data().get(1..33) {
+            refs.push(UInt256::from_slice(hash));
+        }
+        return;
+    }
+    for child in cell.clone_references() {
+        collect_library_refs(&child, visited, refs);
+    }
+}
+
+fn find_local_library(
+    context: &Arc<ClientContext>,
+    provided: &BTreeMap<UInt256, Cell>,
+    hash: &UInt256,
+) -> Option<Cell> {
+    provided
+        .get(hash)
+        .cloned()
+        .or_else(|| context.bocs.get(hash))
+}
+
+fn deserialize_library(context: &ClientContext, boc: &Value) -> Option<Cell> {
+    let boc = boc.as_str()?;
+    deserialize_cell_from_boc(context, boc, "library")
+        .ok()
+        .map(|(_, cell)| cell)
+}
+
+/// Queries the libraries published in the masterchain zerostate and by the masterchain accounts.
+///
+/// The libraries are optional for the execution, so the network errors are logged
+/// and the libraries found so far are returned. The flag is `false` if the lookup
+/// has been interrupted by an error.
+async fn fetch_libraries(
+    context: &Arc<ClientContext>,
+    hashes: &HashSet<UInt256>,
+) -> (Vec<Cell>, bool) {
+    let server_link = match context.get_server_link() {
+        Ok(server_link) => server_link,
+        Err(err) => {
+            log::warn!("Can not query libraries: {}", err);
+            return (Vec::new(), false);
+        }
+    };
+    let mut found = Vec::new();
+    let mut missing = hashes.clone();
+    let mut completed = true;
+
+    let zerostates = server_link
+        .query_collection(
+            ParamsOfQueryCollection {
+                collection: "zerostates".to_string(),
+                filter: Some(json!({ "id": { "eq": "zerostate:-1" } })),
+                result: "libraries { hash lib }".to_string(),
+                limit: Some(1),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap_or_else(|err| {
+            log::warn!("Can not query zerostate libraries: {}", err);
+            completed = false;
+            Value::Null
+        });
+    let libraries = zerostates[0]["libraries"].as_array().cloned().unwrap_or_default();
+    for library in libraries {
+        if let Some(cell) = deserialize_library(context, &library["lib"]) {
+            if missing.remove(&cell.repr_hash()) {
+                found.push(cell);
+            }
+        }
+    }
+
+    let mut last_id: Option<String> = None;
+    for _ in 0..MAX_ACCOUNTS_PAGES {
+        if missing.is_empty() {
+            break;
+        }
+        let mut filter = json!({
+            "workchain_id": { "eq": -1 },
+            "library": { "ne": null },
+        });
+        if let Some(last_id) = &last_id {
+            filter["id"] = json!({ "gt": last_id });
+        }
+        let accounts = server_link
+            .query_collection(
+                ParamsOfQueryCollection {
+                    collection: "accounts".to_string(),
+                    filter: Some(filter),
+                    result: "id library".to_string(),
+                    order: Some(vec![OrderBy {
+                        path: "id".to_string(),
+                        direction: SortDirection::ASC,
+                    }]),
+                    limit: Some(ACCOUNTS_PAGE_SIZE),
+                },
+                None,
+            )
+            .await;
+        let accounts = match accounts {
+            Ok(accounts) => accounts.as_array().cloned().unwrap_or_default(),
+            Err(err) => {
+                log::warn!("Can not query account libraries: {}", err);
+                completed = false;
+                break;
+            }
+        };
+        for account in &accounts {
+            let dict = match deserialize_library(context, &account["library"]) {
+                Some(root) => HashmapE::with_hashmap(256, Some(root)),
+                None => continue,
+            };
+            let hashes: Vec<UInt256> = missing.iter().cloned().collect();
+            for hash in hashes {
+                let key = SliceData::from_raw(hash.as_slice().to_vec(), 256);
+                // private libraries of other accounts are not available to the contract
+                let library = dict
+                    .get(key)
+                    .ok()
+                    .flatten()
+                    .and_then(|mut value| SimpleLib::construct_from(&mut value).ok())
+                    .filter(|library| library.is_public_library())
+                    .map(|library| library.root().clone());
+                if let Some(cell) = library.filter(|cell| cell.repr_hash() == hash) {
+                    missing.remove(&hash);
+                    found.push(cell);
+                }
+            }
+        }
+        if accounts.len() < ACCOUNTS_PAGE_SIZE as usize {
+            break;
+        }
+        last_id = accounts
+            .last()
+            .and_then(|account| account["id"].as_str())
+            .map(|id| id.to_string());
+    }
+    (found, completed)
+}
+
@@ -0,0 +1,115 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::inspect::BocHeader;
+use super::{BocConfig, Error};
+use crate::error::ClientResult;
+use ever_block::{Cell, CellType, UInt256};
+use std::collections::HashSet;
+
+/// Exotic cell type.
+#[derive(Serialize, Deserialize, Clone, Copy, ApiType, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExoticCellType {
+    /// Pruned branch cell replacing a subtree in Merkle proofs and updates.
+    PrunedBranch,
+    /// Library reference cell.
+    LibraryReference,
+    /// Merkle proof cell.
+    MerkleProof,
+    /// Merkle update cell.
+    MerkleUpdate,
+}
+
+impl ExoticCellType {
+    pub(crate) fn from_cell_type(cell_type: CellType) -> Option<Self> {
+        match cell_type {
+            CellType::PrunedBranch => Some(ExoticCellType::PrunedBranch),
+            CellType::LibraryReference => Some(ExoticCellType::LibraryReference),
+            CellType::MerkleProof => Some(ExoticCellType::MerkleProof),
+            CellType::MerkleUpdate => Some(ExoticCellType::MerkleUpdate),
+            _ => None,
+        }
+    }
+}
+
+impl BocConfig {
+    /// Checks the base64 encoded BOC size before decoding.
+    pub(crate) fn check_base64_size(&self, b64: &str) -> ClientResult<()> {
+        if let Some(max_boc_size) = self.max_boc_size {
+            // every 4 base64 chars encode 3 bytes, the last quad can contain up to 2 padding chars
+            let size = (b64.len() / 4 * 3).saturating_sub(2);
+            if size > max_boc_size as usize {
+                return Err(Error::boc_limit_exceeded("max_boc_size", max_boc_size, size));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the BOC size and the number of cells declared in the BOC header.
+    pub(crate) fn check_boc_bytes(&self, bytes: &[u8]) -> ClientResult<()> {
+        if let Some(max_boc_size) = self.max_boc_size {
+            if bytes.len() > max_boc_size as usize {
+                return Err(Error::boc_limit_exceeded("max_boc_size", max_boc_size, bytes.len()));
+            }
+        }
+        if let Some(max_cells) = self.max_cells {
+            if let Some(header) = BocHeader::parse(bytes) {
+                if header.cells_count > max_cells as u64 {
+                    return Err(Error::boc_limit_exceeded(
+                        "max_cells",
+                        max_cells,
+                        header.cells_count,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the depth and the exotic cells of the deserialized BOC.
+    pub(crate) fn check_boc_cell(&self, cell: &Cell) -> ClientResult<()> {
+        if let Some(max_depth) = self.max_depth {
+            let depth = cell.repr_depth();
+            if depth as u32 > max_depth {
+                return Err(Error::boc_limit_exceeded("max_depth", max_depth, depth));
+            }
+        }
+        if let Some(allowed) = &self.allowed_exotic_cells {
+            check_exotic_cells(cell, allowed, &mut HashSet::new())?;
+        }
+        Ok(())
+    }
+}
+
+fn check_exotic_cells(
+    cell: &Cell,
+    allowed: &[ExoticCellType],
+    visited: &mut HashSet<UInt256>,
+) -> ClientResult<()> {
+    if !visited.insert(cell.repr_hash()) {
+        return Ok(());
+    }
+    if let Some(exotic) = ExoticCellType::from_cell_type(cell.cell_type()) {
+        if !allowed.contains(&exotic) {
+            return Err(Error::boc_limit_exceeded(
+                "allowed_exotic_cells",
+                serde_json::to_value(allowed).unwrap_or_default(),
+                serde_json::to_value(exotic).unwrap_or_default(),
+            ));
+        }
+    }
+    for child in cell.clone_references() {
+        check_exotic_cells(&child, allowed, visited)?;
+    }
+    Ok(())
+}
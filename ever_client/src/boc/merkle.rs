@@ -0,0 +1,335 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::boc::dict::parse_dict_key;
+use crate::boc::internal::{
+    deserialize_cell_from_boc, deserialize_object_from_boc, serialize_cell_to_boc,
+    serialize_object_to_boc,
+};
+use crate::boc::{BocCacheType, Error};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use ever_block::{Cell, HashmapE, HashmapType, MerkleProof, MerkleUpdate, UInt256};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct MerkleProofDictEntries {
+    /// Hash of the dictionary root cell encoded with hex.
+    ///
+    /// It is the cell referenced by the `HashmapE` value, not the cell containing it.
+    pub dict_hash: String,
+    /// Dictionary key size in bits.
+    pub key_size: u32,
+    /// Keys of the entries to keep, as unsigned integers encoded as decimal
+    /// or `0x` prefixed hexadecimal strings.
+    pub keys: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ParamsOfCreateMerkleProof {
+    /// BOC encoded as base64 or BOC cache key.
+    pub boc: String,
+    /// Hashes of the cells to keep encoded with hex.
+    ///
+    /// The cells are kept with their subtrees.
+    pub cells: Option<Vec<String>>,
+    /// Dictionary entries to keep.
+    ///
+    /// The leaf cells of the entries are kept with their values.
+    pub dict_entries: Option<Vec<MerkleProofDictEntries>>,
+    /// Cache type to put the result. The BOC itself returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ResultOfCreateMerkleProof {
+    /// Merkle proof BOC encoded as base64 or BOC cache key.
+    pub proof: String,
+    /// Hash of the proven cell encoded with hex.
+    pub root_hash: String,
+}
+
+/// Creates Merkle proof of the cells of a BOC.
+///
+/// All of the cells except the specified cells, their subtrees and the paths
+/// from the root to them are replaced with the pruned branch cells.
+#[api_function]
+pub fn create_merkle_proof(
+    context: Arc<ClientContext>,
+    params: ParamsOfCreateMerkleProof,
+) -> ClientResult<ResultOfCreateMerkleProof> {
+    let (_, root) = deserialize_cell_from_boc(&context, &params.boc, "merkle proof root")?;
+
+    let mut targets = HashSet::new();
+    for hash in params.cells.unwrap_or_default() {
+        let hash = parse_hash(&hash)?;
+        if find_cell(&root, &hash).is_none() {
+            return Err(Error::merkle_proof_target_not_found(format!(
+                "cell {:x} is not found in the BOC",
+                hash
+            )));
+        }
+        targets.insert(hash);
+    }
+    for entries in params.dict_entries.unwrap_or_default() {
+        collect_dict_leaves(&root, &entries, &mut targets)?;
+    }
+
+    let mut keep = HashSet::new();
+    mark_paths(&root, &targets, &mut keep, &mut HashSet::new(), &mut HashMap::new());
+    keep.insert(root.repr_hash());
+
+    let proof = MerkleProof::create(&root, |hash| keep.contains(hash))
+        .map_err(|err| Error::invalid_merkle_proof(err))?;
+
+    Ok(ResultOfCreateMerkleProof {
+        proof: serialize_object_to_boc(&context, &proof, "merkle proof", params.boc_cache)?,
+        root_hash: proof.hash.as_hex_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ParamsOfVerifyMerkleProof {
+    /// Merkle proof BOC encoded as base64 or BOC cache key.
+    pub proof: String,
+    /// Expected hash of the proven cell encoded with hex.
+    pub root_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ResultOfVerifyMerkleProof {
+    /// Hash of the proven cell encoded with hex.
+    pub root_hash: String,
+    /// Depth of the proven cell.
+    pub depth: u32,
+}
+
+/// Verifies Merkle proof.
+///
+/// Checks that the hash and the depth stored in the proof match the hash and the depth
+/// of the proof contents and, if `root_hash` is specified, that the proof is created
+/// for the cell with this hash.
+#[api_function]
+pub fn verify_merkle_proof(
+    context: Arc<ClientContext>,
+    params: ParamsOfVerifyMerkleProof,
+) -> ClientResult<ResultOfVerifyMerkleProof> {
+    let proof = deserialize_object_from_boc::<MerkleProof>(&context, &params.proof, "merkle proof")
+        .map_err(|err| Error::invalid_merkle_proof(err.message))?
+        .object;
+
+    let virtual_root = proof.proof.clone().virtualize(1);
+    if virtual_root.repr_hash() != proof.hash {
+        return Err(Error::invalid_merkle_proof(format!(
+            "proof contents hash {:x} doesn't match the stored hash {:x}",
+            virtual_root.repr_hash(),
+            proof.hash
+        )));
+    }
+    if virtual_root.repr_depth() != proof.depth {
+        return Err(Error::invalid_merkle_proof(format!(
+            "proof contents depth {} doesn't match the stored depth {}",
+            virtual_root.repr_depth(),
+            proof.depth
+        )));
+    }
+    if let Some(root_hash) = params.root_hash {
+        let root_hash = parse_hash(&root_hash)?;
+        if root_hash != proof.hash {
+            return Err(Error::invalid_merkle_proof(format!(
+                "proof is created for the cell {:x}, expected {:x}",
+                proof.hash, root_hash
+            )));
+        }
+    }
+
+    Ok(ResultOfVerifyMerkleProof {
+        root_hash: proof.hash.as_hex_string(),
+        depth: proof.depth as u32,
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ParamsOfCreateMerkleUpdate {
+    /// Old state BOC encoded as base64 or BOC cache key.
+    pub old_boc: String,
+    /// New state BOC encoded as base64 or BOC cache key.
+    pub new_boc: String,
+    /// Cache type to put the result. The BOC itself returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ResultOfCreateMerkleUpdate {
+    /// Merkle update BOC encoded as base64 or BOC cache key.
+    pub update: String,
+    /// Hash of the old state encoded with hex.
+    pub old_hash: String,
+    /// Hash of the new state encoded with hex.
+    pub new_hash: String,
+}
+
+/// Creates Merkle update between two states.
+///
+/// The update contains the cells of the new state that are absent in the old state,
+/// the rest of the cells are pruned.
+#[api_function]
+pub fn create_merkle_update(
+    context: Arc<ClientContext>,
+    params: ParamsOfCreateMerkleUpdate,
+) -> ClientResult<ResultOfCreateMerkleUpdate> {
+    let (_, old) = deserialize_cell_from_boc(&context, &params.old_boc, "old state")?;
+    let (_, new) = deserialize_cell_from_boc(&context, &params.new_boc, "new state")?;
+
+    let update =
+        MerkleUpdate::create(&old, &new).map_err(|err| Error::invalid_merkle_update(err))?;
+
+    Ok(ResultOfCreateMerkleUpdate {
+        update: serialize_object_to_boc(&context, &update, "merkle update", params.boc_cache)?,
+        old_hash: update.old_hash.as_hex_string(),
+        new_hash: update.new_hash.as_hex_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ParamsOfApplyMerkleUpdate {
+    /// Merkle update BOC encoded as base64 or BOC cache key.
+    pub update: String,
+    /// Old state BOC encoded as base64 or BOC cache key.
+    pub old_boc: String,
+    /// Cache type to put the result. The BOC itself returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default, Debug)]
+pub struct ResultOfApplyMerkleUpdate {
+    /// New state BOC encoded as base64 or BOC cache key.
+    pub new_boc: String,
+    /// Hash of the new state encoded with hex.
+    pub new_hash: String,
+}
+
+/// Applies Merkle update to the old state and returns the new state.
+///
+/// Fails if the old state hash doesn't match the update.
+#[api_function]
+pub fn apply_merkle_update(
+    context: Arc<ClientContext>,
+    params: ParamsOfApplyMerkleUpdate,
+) -> ClientResult<ResultOfApplyMerkleUpdate> {
+    let update =
+        deserialize_object_from_boc::<MerkleUpdate>(&context, &params.update, "merkle update")
+            .map_err(|err| Error::invalid_merkle_update(err.message))?
+            .object;
+    let (_, old) = deserialize_cell_from_boc(&context, &params.old_boc, "old state")?;
+
+    let new = update
+        .apply_for(&old)
+        .map_err(|err| Error::invalid_merkle_update(err))?;
+    let new_hash = new.repr_hash().as_hex_string();
+
+    Ok(ResultOfApplyMerkleUpdate {
+        new_boc: serialize_cell_to_boc(&context, new, "new state", params.boc_cache)?,
+        new_hash,
+    })
+}
+
+fn parse_hash(hash: &str) -> ClientResult<UInt256> {
+    UInt256::from_str(hash)
+        .map_err(|err| Error::invalid_merkle_proof(format!("invalid cell hash {}: {}", hash, err)))
+}
+
+fn find_cell(cell: &Cell, hash: &UInt256) -> Option<Cell> {
+    fn find(cell: &Cell, hash: &UInt256, visited: &mut HashSet<UInt256>) -> Option<Cell> {
+        if cell.repr_hash() == *hash {
+            return Some(cell.clone());
+        }
+        if !visited.insert(cell.repr_hash()) {
+            return None;
+        }
+        cell.clone_references()
+            .iter()
+            .find_map(|child| find(child, hash, visited))
+    }
+    find(cell, hash, &mut HashSet::new())
+}
+
+fn collect_dict_leaves(
+    root: &Cell,
+    entries: &MerkleProofDictEntries,
+    targets: &mut HashSet<UInt256>,
+) -> ClientResult<()> {
+    let dict_hash = parse_hash(&entries.dict_hash)?;
+    let dict_root = find_cell(root, &dict_hash).ok_or_else(|| {
+        Error::merkle_proof_target_not_found(format!(
+            "dictionary {:x} is not found in the BOC",
+            dict_hash
+        ))
+    })?;
+    let key_size = entries.key_size as usize;
+    let dict = HashmapE::with_hashmap(key_size, Some(dict_root));
+    for key in &entries.keys {
+        let value = dict
+            .get(parse_dict_key(key, key_size)?)
+            .map_err(|err| Error::invalid_merkle_proof(err))?
+            .ok_or_else(|| {
+                Error::merkle_proof_target_not_found(format!(
+                    "key {} is not found in the dictionary {:x}",
+                    key, dict_hash
+                ))
+            })?;
+        targets.insert(value.cell().repr_hash());
+    }
+    Ok(())
+}
+
+/// Adds to `keep` the targets with their subtrees and all of the cells on the paths
+/// from `cell` to the targets. Returns `true` if `cell` is a target or leads to a target.
+fn mark_paths(
+    cell: &Cell,
+    targets: &HashSet<UInt256>,
+    keep: &mut HashSet<UInt256>,
+    expanded: &mut HashSet<UInt256>,
+    visited: &mut HashMap<UInt256, bool>,
+) -> bool {
+    let hash = cell.repr_hash();
+    if let Some(leads) = visited.get(&hash) {
+        return *leads;
+    }
+    let leads = if targets.contains(&hash) {
+        mark_subtree(cell, keep, expanded);
+        true
+    } else {
+        let mut leads = false;
+        for child in cell.clone_references() {
+            leads |= mark_paths(&child, targets, keep, expanded, visited);
+        }
+        leads
+    };
+    if leads {
+        keep.insert(hash.clone());
+    }
+    visited.insert(hash, leads);
+    leads
+}
+
+fn mark_subtree(cell: &Cell, keep: &mut HashSet<UInt256>, expanded: &mut HashSet<UInt256>) {
+    if expanded.insert(cell.repr_hash()) {
+        keep.insert(cell.repr_hash());
+        for child in cell.clone_references() {
+            mark_subtree(&child, keep, expanded);
+        }
+    }
+}
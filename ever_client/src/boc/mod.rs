@@ -16,11 +16,22 @@ use serde::{Deserialize, Deserializer};
 pub(crate) mod blockchain_config;
 pub(crate) mod cache;
 pub(crate) mod common;
+pub(crate) mod dict;
+pub(crate) mod diff;
 pub(crate) mod encode;
 mod errors;
+pub(crate) mod explorer;
+pub(crate) mod inspect;
 pub mod internal;
+pub(crate) mod libraries;
+pub(crate) mod limits;
+pub(crate) mod merkle;
 pub(crate) mod parse;
+pub(crate) mod persistent;
+pub(crate) mod reader;
+pub(crate) mod reserialize;
 pub(crate) mod state_init;
+pub(crate) mod tlb;
 
 pub(crate) mod encode_external_in_message;
 #[cfg(test)]
@@ -31,22 +42,48 @@ pub use blockchain_config::{
     get_blockchain_config, ParamsOfGetBlockchainConfig, ResultOfGetBlockchainConfig,
 };
 pub use cache::{
-    cache_get, cache_set, cache_unpin, BocCacheType, CachedBoc, ParamsOfBocCacheGet,
-    ParamsOfBocCacheSet, ParamsOfBocCacheUnpin, ResultOfBocCacheGet, ResultOfBocCacheSet,
+    cache_get, cache_load, cache_set, cache_stats, cache_unpin, BocCacheType, CachedBoc,
+    ParamsOfBocCacheGet, ParamsOfBocCacheLoad, ParamsOfBocCacheSet, ParamsOfBocCacheUnpin,
+    ResultOfBocCacheGet, ResultOfBocCacheLoad, ResultOfBocCacheSet, ResultOfBocCacheStats,
 };
 pub use common::{
     get_boc_depth, get_boc_hash, ParamsOfGetBocDepth, ParamsOfGetBocHash, ResultOfGetBocDepth,
     ResultOfGetBocHash,
 };
+pub use dict::{
+    dict_get, dict_iterate, dict_remove, dict_set, DictEntry, DictValueType, ParamsOfDictGet,
+    ParamsOfDictIterate, ParamsOfDictRemove, ParamsOfDictSet, ResultOfDictGet,
+    ResultOfDictIterate, ResultOfDictUpdate,
+};
+pub use diff::{
+    diff, CellDiff, CellDiffKind, FieldDiff, ParamsOfBocDiff, ResultOfBocDiff,
+};
 pub use encode::{encode_boc, BuilderOp, ParamsOfEncodeBoc, ResultOfEncodeBoc};
 pub use encode_external_in_message::{
     encode_external_in_message, ParamsOfEncodeExternalInMessage, ResultOfEncodeExternalInMessage,
 };
 pub use errors::{Error, ErrorCode};
+pub use explorer::{
+    get_block_messages, get_shardstate_account, list_shardstate_accounts, BlockMessageDescr,
+    BlockMessageType, ParamsOfGetBlockMessages, ParamsOfGetShardstateAccount,
+    ParamsOfListShardstateAccounts, ResultOfGetBlockMessages, ResultOfGetShardstateAccount,
+    ResultOfListShardstateAccounts, ShardstateAccount,
+};
+pub use inspect::{inspect, BocHeader, ExoticCellsCount, ParamsOfInspectBoc, ResultOfInspectBoc};
+pub use libraries::{resolve_libraries, ParamsOfResolveLibraries, ResultOfResolveLibraries};
+pub use limits::ExoticCellType;
+pub use merkle::{
+    apply_merkle_update, create_merkle_proof, create_merkle_update, verify_merkle_proof,
+    MerkleProofDictEntries, ParamsOfApplyMerkleUpdate, ParamsOfCreateMerkleProof,
+    ParamsOfCreateMerkleUpdate, ParamsOfVerifyMerkleProof, ResultOfApplyMerkleUpdate,
+    ResultOfCreateMerkleProof, ResultOfCreateMerkleUpdate, ResultOfVerifyMerkleProof,
+};
 pub use parse::{
     parse_account, parse_block, parse_message, parse_shardstate, parse_transaction, required_boc,
     source_boc, ParamsOfParse, ParamsOfParseShardstate, ResultOfParse,
 };
+pub use reader::{query_cell, ParamsOfQueryCell, ResultOfQueryCell};
+pub use reserialize::{reserialize, BocFormat, ParamsOfReserializeBoc, ResultOfReserializeBoc};
 pub use state_init::{
     decode_state_init, encode_state_init, get_code_from_tvc, get_code_salt, get_compiler_version,
     get_compiler_version_from_cell, set_code_salt, ParamsOfDecodeStateInit,
@@ -55,8 +92,14 @@ pub use state_init::{
     ResultOfEncodeStateInit, ResultOfGetCodeFromTvc, ResultOfGetCodeSalt,
     ResultOfGetCompilerVersion, ResultOfSetCodeSalt,
 };
+pub use tlb::{
+    encode_tlb, parse_tlb, ParamsOfEncodeTlb, ParamsOfParseTlb, ResultOfEncodeTlb, ResultOfParseTlb,
+};
 
-pub use tvc::{decode_tvc, Tvc, TvcV1};
+pub use tvc::{
+    decode_tvc, encode_tvc, ParamsOfDecodeTvc, ParamsOfEncodeTvc, ResultOfDecodeTvc, ResultOfEncodeTvc,
+    Tvc, TvcV1, TvcV2,
+};
 
 pub fn default_cache_max_size() -> u32 {
     10 * 1024 // * 1024 = 10 MB
@@ -66,6 +109,16 @@ fn deserialize_cache_max_size<'de, D: Deserializer<'de>>(deserializer: D) -> Res
     Ok(Option::deserialize(deserializer)?.unwrap_or(default_cache_max_size()))
 }
 
+pub fn default_local_cache_max_size() -> u32 {
+    100 * 1024 // * 1024 = 100 MB
+}
+
+fn deserialize_local_cache_max_size<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u32, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or(default_local_cache_max_size()))
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, ApiType)]
 pub struct BocConfig {
     /// Maximum BOC cache size in kilobytes. Default is 10 MB
@@ -74,12 +127,37 @@ pub struct BocConfig {
         deserialize_with = "deserialize_cache_max_size"
     )]
     pub cache_max_size: u32,
+    /// Maximum size of BOCs cached with `Persistent` cache type in the local storage
+    /// in kilobytes. Default is 100 MB. Zero disables the persistent cache.
+    ///
+    /// The local storage is shared by all of the contexts with the same `local_storage_path`.
+    /// Not available for the web.
+    #[serde(
+        default = "default_local_cache_max_size",
+        deserialize_with = "deserialize_local_cache_max_size"
+    )]
+    pub local_cache_max_size: u32,
+    /// Maximum size in bytes of the deserialized BOC. Not limited by default.
+    pub max_boc_size: Option<u32>,
+    /// Maximum number of cells in the deserialized BOC. Not limited by default.
+    ///
+    /// Checked against the BOC header before the cells are loaded.
+    pub max_cells: Option<u32>,
+    /// Maximum depth of the deserialized BOC root cell. Not limited by default.
+    pub max_depth: Option<u32>,
+    /// Exotic cell types allowed in the deserialized BOCs. All types are allowed by default.
+    pub allowed_exotic_cells: Option<Vec<ExoticCellType>>,
 }
 
 impl Default for BocConfig {
     fn default() -> Self {
         Self {
             cache_max_size: default_cache_max_size(),
+            local_cache_max_size: default_local_cache_max_size(),
+            max_boc_size: None,
+            max_cells: None,
+            max_depth: None,
+            allowed_exotic_cells: None,
         }
     }
 }
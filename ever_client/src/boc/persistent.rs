@@ -0,0 +1,184 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::client::Error;
+use crate::error::ClientResult;
+use ever_block::UInt256;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+#[cfg(not(feature = "wasm-base"))]
+const STORAGE_NAME: &str = "bocs";
+
+/// Stored BOC file found in the storage directory.
+struct StoredBoc {
+    path: PathBuf,
+    size: usize,
+    used: SystemTime,
+}
+
+/// Persistent tier of the BOC cache kept in the local storage directory.
+///
+/// Every BOC is saved into a separate file named by the BOC hash. The sizes and the usage
+/// times are taken from the file system, so the size limit is shared between the contexts
+/// and the processes using the same local storage. The file system is accessed synchronously,
+/// so the BOCs are available by reference right after they are saved and survive
+/// the immediate exit of the process.
+pub(crate) struct PersistentBocs {
+    path: Option<PathBuf>,
+    max_size: usize,
+    // serializes the eviction and the saving of the BOCs within the process
+    lock: Mutex<()>,
+}
+
+impl PersistentBocs {
+    #[cfg(not(feature = "wasm-base"))]
+    pub fn new(local_storage_path: Option<String>, max_size: usize) -> Self {
+        Self {
+            path: Some(crate::client::LocalStorage::calc_storage_path(
+                &local_storage_path,
+                STORAGE_NAME,
+            )),
+            max_size,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// The web local storage (IndexedDB) is asynchronous only, so the persistent tier
+    /// is not available there.
+    #[cfg(feature = "wasm-base")]
+    pub fn new(_local_storage_path: Option<String>, max_size: usize) -> Self {
+        Self {
+            path: None,
+            max_size,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_size > 0 && self.path.is_some()
+    }
+
+    fn boc_path(&self, hash: &UInt256) -> Option<PathBuf> {
+        self.path.as_ref().map(|path| path.join(hash.as_hex_string()))
+    }
+
+    /// Lists the stored BOCs skipping the files being written.
+    fn list(&self) -> ClientResult<Vec<StoredBoc>> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(Vec::new()),
+        };
+        let entries = match std::fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(Error::local_storage_error(err)),
+        };
+        let mut bocs = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|err| Error::local_storage_error(err))?;
+            let is_boc = entry
+                .file_name()
+                .to_str()
+                .map(|name| name.len() == 64 && hex::decode(name).is_ok())
+                .unwrap_or_default();
+            if !is_boc {
+                continue;
+            }
+            // the file can be removed by another process in the meantime
+            if let Ok(metadata) = entry.metadata() {
+                bocs.push(StoredBoc {
+                    path: entry.path(),
+                    size: metadata.len() as usize,
+                    used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                });
+            }
+        }
+        Ok(bocs)
+    }
+
+    /// Marks the BOC as recently used.
+    fn touch(path: &Path) {
+        let _ = std::fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+    }
+
+    /// Stores the BOC evicting the least recently used BOCs if the size limit is exceeded.
+    pub fn put(&self, hash: &UInt256, boc: &[u8]) -> ClientResult<()> {
+        let path = match self.boc_path(hash) {
+            Some(path) if self.enabled() && boc.len() <= self.max_size => path,
+            _ => return Ok(()),
+        };
+        let _lock = self.lock.lock().unwrap();
+        if path.exists() {
+            Self::touch(&path);
+            return Ok(());
+        }
+
+        let mut stored = self.list()?;
+        stored.sort_by_key(|boc| boc.used);
+        let mut total_size: usize = stored.iter().map(|boc| boc.size).sum();
+        for lru in stored {
+            if total_size + boc.len() <= self.max_size {
+                break;
+            }
+            match std::fs::remove_file(&lru.path) {
+                Ok(_) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(Error::local_storage_error(err)),
+            }
+            total_size -= lru.size;
+        }
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| Error::local_storage_error(err))?;
+        }
+        // the BOC is written into a temporary file and renamed, so the other processes
+        // never read the partially written BOC
+        let temp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+        std::fs::write(&temp_path, boc)
+            .and_then(|_| std::fs::rename(&temp_path, &path))
+            .map_err(|err| {
+                let _ = std::fs::remove_file(&temp_path);
+                Error::local_storage_error(err)
+            })
+    }
+
+    /// Loads the stored BOC.
+    pub fn get(&self, hash: &UInt256) -> ClientResult<Option<Vec<u8>>> {
+        let path = match self.boc_path(hash) {
+            Some(path) if self.enabled() => path,
+            _ => return Ok(None),
+        };
+        match std::fs::read(&path) {
+            Ok(boc) => {
+                Self::touch(&path);
+                Ok(Some(boc))
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(Error::local_storage_error(err)),
+        }
+    }
+
+    /// Returns the number and the total size of the stored BOCs.
+    pub fn stats(&self) -> ClientResult<(usize, usize)> {
+        if !self.enabled() {
+            return Ok((0, 0));
+        }
+        let stored = self.list()?;
+        Ok((stored.len(), stored.iter().map(|boc| boc.size).sum()))
+    }
+}
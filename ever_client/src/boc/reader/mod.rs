@@ -0,0 +1,77 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+pub(crate) mod parser;
+pub(crate) mod query;
+
+use crate::boc::internal::deserialize_cell_from_boc;
+use crate::boc::BocCacheType;
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use serde_json::Value;
+
+use parser::CellQuery;
+use query::CellReader;
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfQueryCell {
+    /// Cell BOC encoded as base64 or BOC cache key.
+    pub boc: String,
+    /// Cell query.
+    ///
+    /// Query is a sequence of fields that are read from the cell in order.
+    /// Each field has a form `[-][name:]type`:
+    /// - `-` prefix means that the field is read but not included into the result;
+    /// - `name` is a key of the field in the resulting object.
+    ///   If it is omitted then the field index is used.
+    ///
+    /// Supported types:
+    /// - `u<N>`, `i<N>` – unsigned or signed integer of `N` bits (1..256),
+    ///   returned as a decimal string. e.g. `u8`, `i32`, `u256`;
+    /// - `bool` – single bit;
+    /// - `bits<N>` – `N` bits (1..1023) returned as a hex string. e.g. `bits256`;
+    /// - `grams` or `coins` – variable length token amount, returned as a decimal string;
+    /// - `address` – `MsgAddress`, returned as a string or `null` for `addr_none`;
+    /// - `cell` – next cell reference, returned as a BOC;
+    /// - `ref(fields)` – next cell reference, decoded with nested `fields`;
+    /// - `dict(key_type[, fields])` – `HashmapE` with keys of `key_type`
+    ///   (`u<N>`, `i<N>` or `bits<N>`), returned as an object.
+    ///   Values are decoded with nested `fields` or returned as BOCs if `fields` are omitted.
+    ///   Plain `dict` means `dict(u256)`.
+    ///
+    /// Example: `seqno:u32 -flags:u8 owner:address balances:dict(u32, amount:coins meta:cell)`
+    pub query: String,
+    /// Cache type to put the nested cells into.
+    /// The BOCs themselves are returned if no cache type is provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ResultOfQueryCell {
+    /// Decoded cell fields.
+    pub result: Value,
+}
+
+/// Decodes cell without ABI using a simple query language.
+///
+/// Useful for inspecting non-ABI structures like the elector or config contract storage.
+#[api_function]
+pub fn query_cell(
+    context: std::sync::Arc<ClientContext>,
+    params: ParamsOfQueryCell,
+) -> ClientResult<ResultOfQueryCell> {
+    let query = CellQuery::parse(params.query)?;
+    let (_, cell) = deserialize_cell_from_boc(&context, &params.boc, "cell")?;
+    let result = CellReader::new(&context, params.boc_cache).query(&query, cell)?;
+    Ok(ResultOfQueryCell { result })
+}
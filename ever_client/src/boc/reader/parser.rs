@@ -11,19 +11,45 @@
 * limitations under the License.
 */
 
-use crate::error::{ClientResult, ClientError};
+use crate::boc::Error;
+use crate::error::{ClientError, ClientResult};
 
-#[derive(Debug)]
+pub(crate) const MAX_INT_SIZE: usize = 256;
+pub(crate) const MAX_BITS_SIZE: usize = 1023;
+pub(crate) const DEFAULT_DICT_KEY_SIZE: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum DictKeyReader {
+    Int(usize),
+    UInt(usize),
+    Bits(usize),
+}
+
+impl DictKeyReader {
+    pub fn bit_len(&self) -> usize {
+        match self {
+            Self::Int(size) | Self::UInt(size) | Self::Bits(size) => *size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) enum CellValueReader {
     IntWithSize(usize),
     UIntWithSize(usize),
+    Bool,
+    Bits(usize),
     Grams,
-    Dict(Vec<CellFieldReader>),
-    // Cell(Vec<CellFieldReader>),
+    Address,
+    Cell,
+    Ref(Vec<CellFieldReader>),
+    Dict {
+        key: DictKeyReader,
+        value: Vec<CellFieldReader>,
+    },
 }
 
-
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct CellFieldReader {
     pub value: CellValueReader,
     pub skip: bool,
@@ -38,6 +64,7 @@ pub(crate) struct CellQuery {
 enum Token {
     Minus,
     Colon,
+    Comma,
     Open,
     Close,
     Identifier(String),
@@ -52,6 +79,10 @@ impl Token {
         if let Self::Colon = self { Some(()) } else { None }
     }
 
+    fn is_comma(&self) -> Option<()> {
+        if let Self::Comma = self { Some(()) } else { None }
+    }
+
     fn is_open(&self) -> Option<()> {
         if let Self::Open = self { Some(()) } else { None }
     }
@@ -94,12 +125,11 @@ impl Parser {
     }
 
     fn tokenize_error(rest: &str) -> ClientError {
-        ClientError::cell_invalid_query(format!("invalid character (-> {})", rest))
+        Error::invalid_cell_query(format!("invalid character (-> {})", rest))
     }
 
     fn parse_error(&self, msg: &str) -> ClientError {
-        // TODO: error message must point to error position related to self.tokens[self.pos]
-        ClientError::cell_invalid_query(format!("{}", msg))
+        Error::invalid_cell_query(format!("{} at token #{}", msg, self.pos))
     }
 
     fn tokenize(source: String) -> ClientResult<Self> {
@@ -110,7 +140,9 @@ impl Parser {
             next = chars.next();
             if let Some(token) = match current {
                 space if space <= ' ' => None,
+                '-' => Some(Token::Minus),
                 ':' => Some(Token::Colon),
+                ',' => Some(Token::Comma),
                 '(' => Some(Token::Open),
                 ')' => Some(Token::Close),
                 first_ident if Self::is_first_ident_char(first_ident) => {
@@ -160,16 +192,21 @@ impl Parser {
         Ok(commands)
     }
 
-    fn parse_optional_parenthesis_enclosed_commands(&mut self) -> ClientResult<Vec<CellFieldReader>> {
+    fn expect_close(&mut self) -> ClientResult<()> {
+        if self.pass(Token::is_close) != None {
+            Ok(())
+        } else {
+            Err(self.parse_error(") expected"))
+        }
+    }
+
+    fn parse_parenthesis_enclosed_commands(&mut self) -> ClientResult<Vec<CellFieldReader>> {
         if self.pass(Token::is_open) != None {
             let commands = self.parse_commands()?;
-            if self.pass(Token::is_close) != None {
-                Ok(commands)
-            } else {
-                Err(self.parse_error(") expected"))
-            }
+            self.expect_close()?;
+            Ok(commands)
         } else {
-            Ok(Vec::new())
+            Err(self.parse_error("( expected"))
         }
     }
 
@@ -197,24 +234,73 @@ impl Parser {
         }
     }
 
+    /// Parses sized type names like `u32`, `i8` or `bits256`.
+    fn parse_size(&self, type_name: &str, prefix: &str, max: usize) -> ClientResult<Option<usize>> {
+        if !type_name.starts_with(prefix) {
+            return Ok(None);
+        }
+        let size = &type_name[prefix.len()..];
+        if size.is_empty() || !size.chars().all(Self::is_digit) {
+            return Ok(None);
+        }
+        match size.parse::<usize>() {
+            Ok(size) if size > 0 && size <= max => Ok(Some(size)),
+            _ => Err(self.parse_error(&format!(
+                "invalid size of type [{}]: must be in range 1..={}", type_name, max
+            ))),
+        }
+    }
+
+    fn parse_dict_key(&self, type_name: &str) -> ClientResult<DictKeyReader> {
+        if let Some(size) = self.parse_size(type_name, "u", MAX_INT_SIZE)? {
+            Ok(DictKeyReader::UInt(size))
+        } else if let Some(size) = self.parse_size(type_name, "i", MAX_INT_SIZE)? {
+            Ok(DictKeyReader::Int(size))
+        } else if let Some(size) = self.parse_size(type_name, "bits", MAX_BITS_SIZE)? {
+            Ok(DictKeyReader::Bits(size))
+        } else {
+            Err(self.parse_error(&format!("unsupported dict key type [{}]", type_name)))
+        }
+    }
+
+    fn parse_dict(&mut self) -> ClientResult<CellValueReader> {
+        if self.pass(Token::is_open) == None {
+            return Ok(CellValueReader::Dict {
+                key: DictKeyReader::UInt(DEFAULT_DICT_KEY_SIZE),
+                value: Vec::new(),
+            });
+        }
+        let key = if let Some(type_name) = self.pass(Token::identifier) {
+            self.parse_dict_key(&type_name)?
+        } else {
+            return Err(self.parse_error("dict key type expected"));
+        };
+        let value = if self.pass(Token::is_comma) != None {
+            self.parse_commands()?
+        } else {
+            Vec::new()
+        };
+        self.expect_close()?;
+        Ok(CellValueReader::Dict { key, value })
+    }
+
     fn parse_value_reader(&mut self, type_name: &str) -> ClientResult<CellValueReader> {
+        if let Some(size) = self.parse_size(type_name, "u", MAX_INT_SIZE)? {
+            return Ok(CellValueReader::UIntWithSize(size));
+        }
+        if let Some(size) = self.parse_size(type_name, "i", MAX_INT_SIZE)? {
+            return Ok(CellValueReader::IntWithSize(size));
+        }
+        if let Some(size) = self.parse_size(type_name, "bits", MAX_BITS_SIZE)? {
+            return Ok(CellValueReader::Bits(size));
+        }
         Ok(match type_name {
-            "u1" => CellValueReader::UIntWithSize(1),
-            "u8" => CellValueReader::UIntWithSize(8),
-            "u16" => CellValueReader::UIntWithSize(16),
-            "u32" => CellValueReader::UIntWithSize(32),
-            "u64" => CellValueReader::UIntWithSize(64),
-            "u128" => CellValueReader::UIntWithSize(128),
-            "u256" => CellValueReader::UIntWithSize(256),
-            "i1" => CellValueReader::IntWithSize(1),
-            "i8" => CellValueReader::IntWithSize(8),
-            "i16" => CellValueReader::IntWithSize(16),
-            "i32" => CellValueReader::IntWithSize(32),
-            "i64" => CellValueReader::IntWithSize(64),
-            "i128" => CellValueReader::IntWithSize(128),
-            "i256" => CellValueReader::IntWithSize(256),
-            "grams" => CellValueReader::Grams,
-            "dict" => CellValueReader::Dict(self.parse_optional_parenthesis_enclosed_commands()?),
+            "bool" => CellValueReader::Bool,
+            "grams" | "coins" => CellValueReader::Grams,
+            "address" => CellValueReader::Address,
+            "cell" => CellValueReader::Cell,
+            "ref" => CellValueReader::Ref(self.parse_parenthesis_enclosed_commands()?),
+            "dict" => self.parse_dict()?,
             _ => return Err(self.parse_error(&format!("unknown type [{}]", type_name)))
         })
     }
@@ -229,7 +315,7 @@ impl CellQuery {
                 commands
             })
         } else {
-            Err(parser.parse_error("unexpected"))
+            Err(parser.parse_error("unexpected token"))
         }
     }
 }
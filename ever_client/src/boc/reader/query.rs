@@ -11,54 +11,159 @@
 * limitations under the License.
 */
 
-use serde_json::Value;
-use crate::error::{ClientResult, ClientError};
-use super::parser::{CellQuery, CellFieldReader, CellValueReader};
-use ever_block::{Cell, SliceData, HashmapE, HashmapType};
+use super::parser::{CellFieldReader, CellQuery, CellValueReader, DictKeyReader};
+use crate::boc::internal::serialize_cell_to_boc;
+use crate::boc::{BocCacheType, Error};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
 use ever_block::types::Grams;
+use ever_block::{Cell, Deserializable, HashmapE, HashmapType, MsgAddress, SliceData};
+use num_bigint::{BigInt, BigUint};
+use num_traits::One;
+use serde_json::Value;
 
-fn read_value(slice: &mut SliceData, reader: &CellValueReader) -> ClientResult<Value> {
-    Ok(match reader {
-        CellValueReader::IntWithSize(size) => {
-            let n = slice.get_next_int(*size)
-                .map_err(|err| ClientError::cell_invalid_query(err))?;
-            Value::String(format!("{}", n))
-        }
-        CellValueReader::UIntWithSize(size) => {
-            let n = slice.get_next_int(*size)
-                .map_err(|err| ClientError::cell_invalid_query(err))?;
-            Value::String(format!("{}", n))
-        }
-        CellValueReader::Grams => {
-            let n = Grams::read_from_cell(slice)
-                .map_err(|err| ClientError::cell_invalid_query(err))?;
-            Value::String(format!("{}", n))
-        }
-        CellValueReader::Dict(_fields) => {
-            let mut dict = HashmapE::with_bit_len(256);
-            dict.read_hashmap_data(slice)
-                .map_err(|err| ClientError::cell_invalid_query(err))?;
-            let mut count = 0;
-            let result = dict.iterate(&mut |_key, _value| {
-                count += 1;
-                Ok(true)
-            });
-            result.map_err(|err| ClientError::cell_invalid_query(err))?;
-            Value::from(count)
-        }
-    })
+pub(crate) struct CellReader<'a> {
+    context: &'a ClientContext,
+    boc_cache: Option<BocCacheType>,
 }
 
-fn read(slice: &mut SliceData, commands: &Vec<CellFieldReader>) -> ClientResult<Value> {
-    let mut values = serde_json::Map::new();
-    for (index, command) in commands.iter().enumerate() {
-        let name = if command.name.is_empty() { format!("{}", index) } else { command.name.clone() };
-        values.insert(name, read_value(slice, &command.value)?);
+pub(crate) fn read_uint(slice: &mut SliceData, size: usize) -> ClientResult<BigUint> {
+    let bytes = slice
+        .get_next_bits(size)
+        .map_err(|err| Error::invalid_cell_query(err))?;
+    let padding = bytes.len() * 8 - size;
+    Ok(BigUint::from_bytes_be(&bytes) >> padding)
+}
+
+pub(crate) fn read_int(slice: &mut SliceData, size: usize) -> ClientResult<BigInt> {
+    let unsigned = read_uint(slice, size)?;
+    if unsigned >= (BigUint::one() << (size - 1)) {
+        Ok(BigInt::from(unsigned) - (BigInt::one() << size))
+    } else {
+        Ok(BigInt::from(unsigned))
     }
-    Ok(Value::Object(values))
 }
 
-pub(crate) fn query_cell(query: &CellQuery, cell: &Cell) -> ClientResult<Value> {
-    let mut slice = SliceData::from(cell);
-    read(&mut slice, &query.commands)
+impl<'a> CellReader<'a> {
+    pub fn new(context: &'a ClientContext, boc_cache: Option<BocCacheType>) -> Self {
+        Self { context, boc_cache }
+    }
+
+    fn read_cell_ref(&self, slice: &mut SliceData) -> ClientResult<Cell> {
+        slice
+            .checked_drain_reference()
+            .map_err(|err| Error::invalid_cell_query(err))
+    }
+
+    fn serialize_cell(&self, cell: Cell) -> ClientResult<Value> {
+        Ok(Value::String(serialize_cell_to_boc(
+            self.context,
+            cell,
+            "query_cell",
+            self.boc_cache.clone(),
+        )?))
+    }
+
+    fn read_dict_key(&self, key: &mut SliceData, reader: &DictKeyReader) -> ClientResult<String> {
+        Ok(match reader {
+            DictKeyReader::Int(size) => read_int(key, *size)?.to_string(),
+            DictKeyReader::UInt(size) => read_uint(key, *size)?.to_string(),
+            DictKeyReader::Bits(size) => hex::encode(
+                key.get_next_bits(*size)
+                    .map_err(|err| Error::invalid_cell_query(err))?,
+            ),
+        })
+    }
+
+    fn read_dict(
+        &self,
+        slice: &mut SliceData,
+        key: &DictKeyReader,
+        value: &Vec<CellFieldReader>,
+    ) -> ClientResult<Value> {
+        let mut dict = HashmapE::with_bit_len(key.bit_len());
+        dict.read_hashmap_data(slice)
+            .map_err(|err| Error::invalid_cell_query(err))?;
+        let mut entries = Vec::new();
+        dict.iterate_slices(|key, value| {
+            entries.push((key, value));
+            Ok(true)
+        })
+        .map_err(|err| Error::invalid_cell_query(err))?;
+
+        let mut result = serde_json::Map::new();
+        for (mut entry_key, mut entry_value) in entries {
+            let entry_key = self.read_dict_key(&mut entry_key, key)?;
+            let entry_value = if value.is_empty() {
+                self.serialize_cell(entry_value.into_cell())?
+            } else {
+                self.read(&mut entry_value, value)?
+            };
+            result.insert(entry_key, entry_value);
+        }
+        Ok(Value::Object(result))
+    }
+
+    fn read_value(&self, slice: &mut SliceData, reader: &CellValueReader) -> ClientResult<Value> {
+        Ok(match reader {
+            CellValueReader::IntWithSize(size) => {
+                Value::String(read_int(slice, *size)?.to_string())
+            }
+            CellValueReader::UIntWithSize(size) => {
+                Value::String(read_uint(slice, *size)?.to_string())
+            }
+            CellValueReader::Bool => Value::Bool(
+                slice
+                    .get_next_bit()
+                    .map_err(|err| Error::invalid_cell_query(err))?,
+            ),
+            CellValueReader::Bits(size) => Value::String(hex::encode(
+                slice
+                    .get_next_bits(*size)
+                    .map_err(|err| Error::invalid_cell_query(err))?,
+            )),
+            CellValueReader::Grams => {
+                let n = Grams::construct_from(slice)
+                    .map_err(|err| Error::invalid_cell_query(err))?;
+                Value::String(format!("{}", n))
+            }
+            CellValueReader::Address => {
+                match MsgAddress::construct_from(slice)
+                    .map_err(|err| Error::invalid_cell_query(err))?
+                {
+                    MsgAddress::AddrNone => Value::Null,
+                    address => Value::String(address.to_string()),
+                }
+            }
+            CellValueReader::Cell => {
+                let cell = self.read_cell_ref(slice)?;
+                self.serialize_cell(cell)?
+            }
+            CellValueReader::Ref(fields) => {
+                let cell = self.read_cell_ref(slice)?;
+                let mut nested =
+                    SliceData::load_cell(cell).map_err(|err| Error::invalid_cell_query(err))?;
+                self.read(&mut nested, fields)?
+            }
+            CellValueReader::Dict { key, value } => self.read_dict(slice, key, value)?,
+        })
+    }
+
+    pub fn read(&self, slice: &mut SliceData, commands: &Vec<CellFieldReader>) -> ClientResult<Value> {
+        let mut values = serde_json::Map::new();
+        for (index, command) in commands.iter().enumerate() {
+            let value = self.read_value(slice, &command.value)?;
+            if command.skip {
+                continue;
+            }
+            let name = if command.name.is_empty() { format!("{}", index) } else { command.name.clone() };
+            values.insert(name, value);
+        }
+        Ok(Value::Object(values))
+    }
+
+    pub fn query(&self, query: &CellQuery, cell: Cell) -> ClientResult<Value> {
+        let mut slice = SliceData::load_cell(cell).map_err(|err| Error::invalid_cell_query(err))?;
+        self.read(&mut slice, &query.commands)
+    }
 }
@@ -0,0 +1,190 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::boc::inspect::BocHeader;
+use crate::boc::internal::deserialize_cell_from_boc;
+use crate::boc::Error;
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use ever_block::boc::BocWriter;
+use ever_block::Cell;
+use std::sync::Arc;
+
+const CRC32C: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
+
+#[derive(Serialize, Deserialize, Clone, Copy, ApiType, Debug, PartialEq)]
+pub enum BocFormat {
+    Base64,
+    Hex,
+}
+
+impl Default for BocFormat {
+    fn default() -> Self {
+        BocFormat::Base64
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfReserializeBoc {
+    /// BOC encoded as base64 or BOC cache key.
+    pub boc: String,
+    /// Include the cells index. Default is `false`.
+    pub with_index: Option<bool>,
+    /// Append the CRC32C checksum. Default is `false`.
+    pub with_crc32c: Option<bool>,
+    /// Include the cache bits into the cells index. Default is `false`.
+    ///
+    /// The cache bit is set for the cells referenced more than once.
+    /// Requires `with_index`.
+    pub with_cache_bits: Option<bool>,
+    /// Output format. Default is `Base64`.
+    pub format: Option<BocFormat>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ResultOfReserializeBoc {
+    /// BOC encoded in the requested format.
+    pub boc: String,
+}
+
+/// Serializes the BOC again with the specified flags
+///
+/// Always uses the generic BOC format (`b5ee9c72` tag) written by `BocWriter`,
+/// the cache bits are set for the cells referenced more than once.
+#[api_function]
+pub fn reserialize(
+    context: Arc<ClientContext>,
+    params: ParamsOfReserializeBoc,
+) -> ClientResult<ResultOfReserializeBoc> {
+    let (_, cell) = deserialize_cell_from_boc(&context, &params.boc, "BOC")?;
+    let options = BocWriteOptions {
+        with_index: params.with_index.unwrap_or_default(),
+        with_crc32c: params.with_crc32c.unwrap_or_default(),
+        with_cache_bits: params.with_cache_bits.unwrap_or_default(),
+    };
+    if options.with_cache_bits && !options.with_index {
+        return Err(Error::serialization_error("cache bits require the cells index", "BOC"));
+    }
+    let bytes = write_boc(&cell, &options)?;
+    let boc = match params.format.unwrap_or_default() {
+        BocFormat::Base64 => base64::encode(&bytes),
+        BocFormat::Hex => hex::encode(&bytes),
+    };
+    Ok(ResultOfReserializeBoc { boc })
+}
+
+struct BocWriteOptions {
+    with_index: bool,
+    with_crc32c: bool,
+    with_cache_bits: bool,
+}
+
+fn bytes_to_fit(value: u64) -> usize {
+    let mut size = 1;
+    while size < 8 && value >> (size * 8) != 0 {
+        size += 1;
+    }
+    size
+}
+
+fn write_uint(output: &mut Vec<u8>, value: u64, size: usize) {
+    output.extend_from_slice(&value.to_be_bytes()[8 - size..]);
+}
+
+fn read_uint(bytes: &[u8], start: usize, size: usize) -> Option<u64> {
+    Some(
+        bytes
+            .get(start..start + size)?
+            .iter()
+            .fold(0u64, |value, byte| (value << 8) | *byte as u64),
+    )
+}
+
+fn write_boc(root: &Cell, options: &BocWriteOptions) -> ClientResult<Vec<u8>> {
+    let writer =
+        BocWriter::with_root(root).map_err(|err| Error::serialization_error(err, "BOC"))?;
+    let mut output = Vec::new();
+    // `BocWriter` doesn't support the cache bits, so they are added to its index afterwards
+    // and the checksum is calculated over the updated BOC
+    writer
+        .write_ex(
+            &mut output,
+            options.with_index,
+            options.with_crc32c && !options.with_cache_bits,
+            None,
+            None,
+        )
+        .map_err(|err| Error::serialization_error(err, "BOC"))?;
+    if !options.with_cache_bits {
+        return Ok(output);
+    }
+    let mut output = add_cache_bits(&output)
+        .ok_or_else(|| Error::serialization_error("unexpected BOC layout", "BOC"))?;
+    if options.with_crc32c {
+        let crc = CRC32C.checksum(&output);
+        output.extend_from_slice(&crc.to_le_bytes());
+    }
+    Ok(output)
+}
+
+/// Rewrites the cells index of the BOC written by `BocWriter` setting the cache bit
+/// for the cells referenced more than once.
+fn add_cache_bits(boc: &[u8]) -> Option<Vec<u8>> {
+    let header = BocHeader::parse(boc)?;
+    let ref_size = header.ref_size as usize;
+    let offset_size = header.offset_size as usize;
+    let cells_count = header.cells_count as usize;
+    // cells count, roots count and absent cells count
+    let mut pos = 6 + ref_size * 3;
+    let total_size = read_uint(boc, pos, offset_size)?;
+    pos += offset_size;
+    let roots = boc.get(pos..pos + header.roots_count as usize * ref_size)?;
+    pos += roots.len();
+    let mut offsets = Vec::with_capacity(cells_count);
+    for index in 0..cells_count {
+        offsets.push(read_uint(boc, pos + index * offset_size, offset_size)?);
+    }
+    pos += cells_count * offset_size;
+    let cells = boc.get(pos..pos + total_size as usize)?;
+
+    let mut parents = vec![0u32; cells_count];
+    let mut start = 0;
+    for end in &offsets {
+        let d1 = *cells.get(start)?;
+        let d2 = *cells.get(start + 1)?;
+        if d1 & 0x10 != 0 {
+            // the cells with the stored hashes are not expected
+            return None;
+        }
+        let refs_start = start + 2 + (d2 as usize + 1) / 2;
+        for i in 0..(d1 & 0x07) as usize {
+            let child = read_uint(cells, refs_start + i * ref_size, ref_size)? as usize;
+            *parents.get_mut(child)? += 1;
+        }
+        start = *end as usize;
+    }
+
+    let new_offset_size = bytes_to_fit(total_size << 1);
+    let mut output = Vec::with_capacity(boc.len() + cells_count);
+    output.extend_from_slice(&boc[..4]);
+    output.push(boc[4] | 0x20);
+    output.push(new_offset_size as u8);
+    output.extend_from_slice(&boc[6..6 + ref_size * 3]);
+    write_uint(&mut output, total_size, new_offset_size);
+    output.extend_from_slice(roots);
+    for (offset, parents) in offsets.into_iter().zip(parents) {
+        write_uint(&mut output, (offset << 1) | (parents > 1) as u64, new_offset_size);
+    }
+    output.extend_from_slice(cells);
+    Some(output)
+}
@@ -19,13 +19,14 @@ use crate::api_info::ApiModule;
 use crate::boc::internal::deserialize_object_from_boc_bin;
 use crate::boc::tvc::{ParamsOfDecodeTvc, ResultOfDecodeTvc};
 use crate::crypto::KeyPair;
+use crate::error::ClientResult;
 use crate::json_interface::modules::BocModule;
 use crate::tests::{TestClient, EVENTS_OLD};
 use internal::serialize_cell_to_base64;
 use pretty_assertions::assert_eq;
 use serde_json::Value;
 use std::str::FromStr;
-use ever_block::{MsgAddrStd, MsgAddressInt, Serializable};
+use ever_block::{Deserializable, HashmapType, MsgAddrStd, MsgAddressInt, Serializable};
 use ever_block::{AccountId, BuilderData, IBitstring};
 
 #[test]
@@ -980,3 +981,1116 @@ fn test_decode_tvc() {
         .unwrap();
     assert_eq!(expected, decoded);
 }
+
+#[test]
+fn query_cell() {
+    let client = TestClient::new();
+
+    let mut dict = ever_block::HashmapE::with_bit_len(32);
+    for (key, amount) in [(1u32, 100u64), (7u32, 700u64)] {
+        let mut value = BuilderData::new();
+        ever_block::Grams::from(amount).write_to(&mut value).unwrap();
+        value
+            .checked_append_reference(BuilderData::with_raw(vec![0xAB], 8).unwrap().into_cell().unwrap())
+            .unwrap();
+        dict.set_builder(
+            ever_block::SliceData::load_builder(key.write_to_new_cell().unwrap()).unwrap(),
+            &value,
+        )
+        .unwrap();
+    }
+
+    let owner = MsgAddressInt::from_str(
+        "0:2bb4a0e8391e7ea8877f4825064924bd41ce110fce97e939d3323999e1efbb13",
+    )
+    .unwrap();
+    let mut inner = BuilderData::new();
+    inner.append_i16(-2).unwrap();
+
+    let mut builder = BuilderData::new();
+    builder
+        .append_u32(42)
+        .unwrap()
+        .append_u8(0xFF)
+        .unwrap()
+        .append_bit_one()
+        .unwrap()
+        .append_u128(123456789123456789u128)
+        .unwrap()
+        .append_builder(&owner.write_to_new_cell().unwrap())
+        .unwrap();
+    dict.write_hashmap_data(&mut builder).unwrap();
+    builder
+        .checked_append_reference(inner.into_cell().unwrap())
+        .unwrap();
+    let boc = serialize_cell_to_base64(&builder.into_cell().unwrap(), "cell").unwrap();
+
+    let result: ResultOfQueryCell = client
+        .request(
+            "boc.query_cell",
+            ParamsOfQueryCell {
+                boc,
+                query: "seqno:u32 -flags:u8 active:bool u128 owner:address \
+                    balances:dict(u32, amount:coins meta:cell) ref(value:i16)"
+                    .to_string(),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+
+    let meta = serialize_cell_to_base64(
+        &BuilderData::with_raw(vec![0xAB], 8).unwrap().into_cell().unwrap(),
+        "meta",
+    )
+    .unwrap();
+    assert_eq!(
+        result.result,
+        json!({
+            "seqno": "42",
+            "active": true,
+            "3": "123456789123456789",
+            "owner": "0:2bb4a0e8391e7ea8877f4825064924bd41ce110fce97e939d3323999e1efbb13",
+            "balances": {
+                "1": { "amount": "100", "meta": meta },
+                "7": { "amount": "700", "meta": meta },
+            },
+            "6": { "value": "-2" },
+        })
+    );
+
+    let error = client
+        .request::<_, ResultOfQueryCell>(
+            "boc.query_cell",
+            ParamsOfQueryCell {
+                boc: String::from("te6ccgEBAQEAAgAAAA=="),
+                query: "value:u257".to_string(),
+                boc_cache: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidCellQuery as u32);
+}
+
+#[test]
+fn encode_and_parse_tlb() {
+    let client = TestClient::new();
+    let schema = r#"
+        // jetton-like messages
+        transfer#0f8a7ea5 query_id:uint64 amount:Coins destination:MsgAddress
+            response_destination:MsgAddress custom_payload:(Maybe ^Cell)
+            forward_ton_amount:(VarUInteger 16) forward_payload:(Either Cell ^Cell) = JettonMsg;
+        burn$0101 query_id:uint64 amount:Coins = JettonMsg;
+
+        _ balances:(HashmapE 32 Coins) flags:(## 3) sign:int8 extra:^[ a:Bool b:bits16 ]
+            msg:^JettonMsg = Storage;
+    "#;
+
+    let value = json!({
+        "@type": "_",
+        "balances": {
+            "1": "100",
+            "77": "123456789012",
+        },
+        "flags": "5",
+        "sign": "-3",
+        "extra": {
+            "a": true,
+            "b": "abcd",
+        },
+        "msg": {
+            "@type": "transfer",
+            "query_id": "42",
+            "amount": "1000000000",
+            "destination": "0:2bb4a0e8391e7ea8877f4825064924bd41ce110fce97e939d3323999e1efbb13",
+            "response_destination": null,
+            "custom_payload": null,
+            "forward_ton_amount": "0",
+            "forward_payload": {
+                "@type": "left",
+                "value": "te6ccgEBAQEAAgAAAA==",
+            },
+        },
+    });
+
+    let encoded: ResultOfEncodeTlb = client
+        .request(
+            "boc.encode_tlb",
+            ParamsOfEncodeTlb {
+                schema: schema.to_string(),
+                type_name: "Storage".to_string(),
+                value: value.clone(),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+
+    let parsed: ResultOfParseTlb = client
+        .request(
+            "boc.parse_tlb",
+            ParamsOfParseTlb {
+                boc: encoded.boc.clone(),
+                schema: schema.to_string(),
+                type_name: "Storage".to_string(),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(parsed.parsed, value);
+
+    let burn: ResultOfEncodeTlb = client
+        .request(
+            "boc.encode_tlb",
+            ParamsOfEncodeTlb {
+                schema: schema.to_string(),
+                type_name: "JettonMsg".to_string(),
+                value: json!({ "@type": "burn", "query_id": 1, "amount": 2 }),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+    let mut expected = BuilderData::new();
+    expected.append_bits(0b0101, 4).unwrap().append_u64(1).unwrap();
+    ever_block::Grams::from(2u64).write_to(&mut expected).unwrap();
+    assert_eq!(
+        burn.boc,
+        serialize_cell_to_base64(&expected.into_cell().unwrap(), "burn").unwrap()
+    );
+
+    let error = client
+        .request::<_, ResultOfEncodeTlb>(
+            "boc.encode_tlb",
+            ParamsOfEncodeTlb {
+                schema: schema.to_string(),
+                type_name: "JettonMsg".to_string(),
+                value: json!({ "query_id": 1, "amount": 2 }),
+                boc_cache: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidTlbValue as u32);
+
+    let error = client
+        .request::<_, ResultOfParseTlb>(
+            "boc.parse_tlb",
+            ParamsOfParseTlb {
+                boc: encoded.boc,
+                schema: "_ a:(Foo 8) = Storage;".to_string(),
+                type_name: "Storage".to_string(),
+                boc_cache: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidTlbSchema as u32);
+
+    let empty = serialize_cell_to_base64(&BuilderData::new().into_cell().unwrap(), "cell").unwrap();
+    for schema in [
+        "_ x:A = A;",
+        "_ a:Any b:B = A; _ x:[ y:Any z:A ] = B;",
+    ] {
+        let error = client
+            .request::<_, ResultOfParseTlb>(
+                "boc.parse_tlb",
+                ParamsOfParseTlb {
+                    boc: empty.clone(),
+                    schema: schema.to_string(),
+                    type_name: "A".to_string(),
+                    boc_cache: None,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidTlbSchema as u32);
+    }
+}
+
+#[test]
+fn test_merkle_proof() {
+    let client = TestClient::new();
+
+    let account_boc = base64::encode(include_bytes!("test_data/account.boc"));
+    let (account, account_hash) = deserialize_object_from_boc_bin::<ever_block::Account>(
+        include_bytes!("test_data/account.boc"),
+    )
+    .unwrap();
+    let code_hash = account.get_code().unwrap().repr_hash();
+
+    let proof: ResultOfCreateMerkleProof = client
+        .request(
+            "boc.create_merkle_proof",
+            ParamsOfCreateMerkleProof {
+                boc: account_boc.clone(),
+                cells: Some(vec![code_hash.as_hex_string()]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(proof.root_hash, account_hash.as_hex_string());
+
+    let verified: ResultOfVerifyMerkleProof = client
+        .request(
+            "boc.verify_merkle_proof",
+            ParamsOfVerifyMerkleProof {
+                proof: proof.proof.clone(),
+                root_hash: Some(account_hash.as_hex_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(verified.root_hash, account_hash.as_hex_string());
+
+    let parsed: ResultOfParse = client
+        .request(
+            "boc.parse_account",
+            ParamsOfParse { boc: proof.proof.clone() },
+        )
+        .unwrap();
+    assert!(parsed.parsed["code"].is_string());
+    assert!(parsed.parsed["data"].is_null());
+
+    let error = client
+        .request::<_, ResultOfVerifyMerkleProof>(
+            "boc.verify_merkle_proof",
+            ParamsOfVerifyMerkleProof {
+                proof: proof.proof,
+                root_hash: Some(code_hash.as_hex_string()),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidMerkleProof as u32);
+
+    let error = client
+        .request::<_, ResultOfCreateMerkleProof>(
+            "boc.create_merkle_proof",
+            ParamsOfCreateMerkleProof {
+                boc: account_boc,
+                cells: Some(vec![ever_block::UInt256::default().as_hex_string()]),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::MerkleProofTargetNotFound as u32);
+
+    let mut dict = ever_block::HashmapE::with_bit_len(32);
+    for key in 0..16u32 {
+        let mut value = BuilderData::new();
+        value.append_u64(key as u64 * 100).unwrap();
+        dict.set_builder(
+            ever_block::SliceData::load_builder(key.write_to_new_cell().unwrap()).unwrap(),
+            &value,
+        )
+        .unwrap();
+    }
+    let dict_hash = dict.data().unwrap().repr_hash();
+    let root = dict.serialize().unwrap();
+
+    let proof: ResultOfCreateMerkleProof = client
+        .request(
+            "boc.create_merkle_proof",
+            ParamsOfCreateMerkleProof {
+                boc: serialize_cell_to_base64(&root, "dict").unwrap(),
+                dict_entries: Some(vec![MerkleProofDictEntries {
+                    dict_hash: dict_hash.as_hex_string(),
+                    key_size: 32,
+                    keys: vec!["5".to_string(), "0xa".to_string()],
+                }]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(proof.root_hash, root.repr_hash().as_hex_string());
+
+    let proof = ever_block::MerkleProof::construct_from_base64(&proof.proof).unwrap();
+    let mut virtual_root =
+        ever_block::SliceData::load_cell(proof.proof.virtualize(1)).unwrap();
+    let mut proven = ever_block::HashmapE::with_bit_len(32);
+    proven.read_hashmap_data(&mut virtual_root).unwrap();
+    let get = |key: u32| {
+        proven
+            .get(ever_block::SliceData::load_builder(key.write_to_new_cell().unwrap()).unwrap())
+            .map(|value| value.map(|mut value| value.get_next_u64().unwrap()))
+    };
+    assert_eq!(get(5).unwrap(), Some(500));
+    assert_eq!(get(10).unwrap(), Some(1000));
+    assert!(get(6).is_err());
+}
+
+#[test]
+fn test_merkle_update() {
+    let client = TestClient::new();
+
+    let (mut account, old_hash) = deserialize_object_from_boc_bin::<ever_block::Account>(
+        include_bytes!("test_data/account.boc"),
+    )
+    .unwrap();
+    account.set_balance(ever_block::CurrencyCollection::with_grams(1000));
+    let new_cell = account.serialize().unwrap();
+    let old_boc = base64::encode(include_bytes!("test_data/account.boc"));
+
+    let update: ResultOfCreateMerkleUpdate = client
+        .request(
+            "boc.create_merkle_update",
+            ParamsOfCreateMerkleUpdate {
+                old_boc: old_boc.clone(),
+                new_boc: serialize_cell_to_base64(&new_cell, "account").unwrap(),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(update.old_hash, old_hash.as_hex_string());
+    assert_eq!(update.new_hash, new_cell.repr_hash().as_hex_string());
+
+    let applied: ResultOfApplyMerkleUpdate = client
+        .request(
+            "boc.apply_merkle_update",
+            ParamsOfApplyMerkleUpdate {
+                update: update.update.clone(),
+                old_boc,
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(applied.new_hash, new_cell.repr_hash().as_hex_string());
+
+    let error = client
+        .request::<_, ResultOfApplyMerkleUpdate>(
+            "boc.apply_merkle_update",
+            ParamsOfApplyMerkleUpdate {
+                update: update.update,
+                old_boc: serialize_cell_to_base64(&new_cell, "account").unwrap(),
+                boc_cache: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidMerkleUpdate as u32);
+}
+
+#[test]
+fn test_dict() {
+    let client = TestClient::new();
+
+    let set = |dict: Option<String>, key: &str, value: Value, value_type: DictValueType| {
+        client
+            .request::<_, ResultOfDictUpdate>(
+                "boc.dict_set",
+                ParamsOfDictSet {
+                    dict,
+                    key_size: 32,
+                    key: key.to_string(),
+                    value,
+                    value_type: Some(value_type),
+                    boc_cache: None,
+                },
+            )
+            .unwrap()
+            .dict
+    };
+    let uint64 = || DictValueType::Integer { size: 64, signed: None };
+
+    let dict = set(None, "1", json!(100), uint64());
+    let dict = set(dict, "0x10", json!("0x200"), uint64());
+    let dict = set(dict, "3", json!("300"), uint64());
+
+    let result: ResultOfDictGet = client
+        .request(
+            "boc.dict_get",
+            ParamsOfDictGet {
+                dict: dict.clone(),
+                key_size: 32,
+                key: "16".to_string(),
+                value_type: Some(uint64()),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(result.value, Some(json!("512")));
+
+    let result: ResultOfDictGet = client
+        .request(
+            "boc.dict_get",
+            ParamsOfDictGet {
+                dict: dict.clone(),
+                key_size: 32,
+                key: "2".to_string(),
+                value_type: Some(uint64()),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(result.value, None);
+
+    let dict = client
+        .request::<_, ResultOfDictUpdate>(
+            "boc.dict_remove",
+            ParamsOfDictRemove {
+                dict,
+                key_size: 32,
+                key: "3".to_string(),
+                boc_cache: None,
+            },
+        )
+        .unwrap()
+        .dict;
+
+    let result: ResultOfDictIterate = client
+        .request(
+            "boc.dict_iterate",
+            ParamsOfDictIterate {
+                dict: dict.clone(),
+                key_size: 32,
+                value_type: Some(uint64()),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        result.entries,
+        vec![
+            DictEntry { key: "1".to_string(), value: json!("100") },
+            DictEntry { key: "16".to_string(), value: json!("512") },
+        ]
+    );
+
+    let abi = DictValueType::Abi {
+        params: vec![
+            crate::abi::AbiParam {
+                name: "owner".to_owned(),
+                param_type: "address".to_owned(),
+                ..Default::default()
+            },
+            crate::abi::AbiParam {
+                name: "amount".to_owned(),
+                param_type: "uint128".to_owned(),
+                ..Default::default()
+            },
+        ],
+    };
+    let owner = "0:1111111111111111111111111111111111111111111111111111111111111111";
+    let abi_dict = set(None, "7", json!({ "owner": owner, "amount": "42" }), abi.clone());
+    let result: ResultOfDictGet = client
+        .request(
+            "boc.dict_get",
+            ParamsOfDictGet {
+                dict: abi_dict,
+                key_size: 32,
+                key: "7".to_string(),
+                value_type: Some(abi),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(result.value, Some(json!({ "owner": owner, "amount": "42" })));
+
+    let error = client
+        .request::<_, ResultOfDictUpdate>(
+            "boc.dict_set",
+            ParamsOfDictSet {
+                dict: None,
+                key_size: 8,
+                key: "256".to_string(),
+                value: json!(1),
+                value_type: Some(uint64()),
+                boc_cache: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidDictKey as u32);
+
+    let error = client
+        .request::<_, ResultOfDictUpdate>(
+            "boc.dict_set",
+            ParamsOfDictSet {
+                dict,
+                key_size: 32,
+                key: "1".to_string(),
+                value: json!(-1),
+                value_type: Some(uint64()),
+                boc_cache: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidDictValue as u32);
+}
+
+#[test]
+fn test_boc_diff() {
+    let client = TestClient::new();
+
+    let cell = |data: u8, refs: Vec<ever_block::Cell>| {
+        let mut builder = BuilderData::with_raw(vec![data], 8).unwrap();
+        for child in refs {
+            builder.checked_append_reference(child).unwrap();
+        }
+        builder.into_cell().unwrap()
+    };
+    let shared = cell(0x11, vec![]);
+    let old = cell(0x01, vec![shared.clone(), cell(0xAA, vec![]), cell(0xCC, vec![])]);
+    let new = cell(0x01, vec![shared, cell(0xAB, vec![])]);
+
+    let result: ResultOfBocDiff = client
+        .request(
+            "boc.diff",
+            ParamsOfBocDiff {
+                old_boc: serialize_cell_to_base64(&old, "old").unwrap(),
+                new_boc: serialize_cell_to_base64(&new, "new").unwrap(),
+                abi: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        result
+            .cells
+            .iter()
+            .map(|diff| (diff.path.as_str(), diff.kind.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("/", CellDiffKind::Changed),
+            ("/1", CellDiffKind::Changed),
+            ("/2", CellDiffKind::Removed),
+        ]
+    );
+    assert_eq!(result.cells[1].old_data.as_deref(), Some("aa"));
+    assert_eq!(result.cells[1].new_data.as_deref(), Some("ab"));
+    assert!(result.fields.is_empty());
+
+    // swapped subtrees are shared, only the new one and the root are reported
+    let (first, second) = (cell(0x21, vec![cell(0x22, vec![])]), cell(0x31, vec![]));
+    let old = cell(0x01, vec![first.clone(), second.clone()]);
+    let new = cell(0x02, vec![second, first, cell(0x41, vec![])]);
+    let result: ResultOfBocDiff = client
+        .request(
+            "boc.diff",
+            ParamsOfBocDiff {
+                old_boc: serialize_cell_to_base64(&old, "old").unwrap(),
+                new_boc: serialize_cell_to_base64(&new, "new").unwrap(),
+                abi: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        result
+            .cells
+            .iter()
+            .map(|diff| (diff.path.as_str(), diff.kind.clone()))
+            .collect::<Vec<_>>(),
+        vec![("/", CellDiffKind::Changed), ("/2", CellDiffKind::Added)]
+    );
+
+    let old_boc = include_bytes!("test_data/account.boc");
+    let (mut account, _) = deserialize_object_from_boc_bin::<ever_block::Account>(old_boc).unwrap();
+    account.set_balance(ever_block::CurrencyCollection::with_grams(1000));
+
+    let result: ResultOfBocDiff = client
+        .request(
+            "boc.diff",
+            ParamsOfBocDiff {
+                old_boc: base64::encode(old_boc),
+                new_boc: serialize_cell_to_base64(&account.serialize().unwrap(), "account")
+                    .unwrap(),
+                abi: None,
+            },
+        )
+        .unwrap();
+    assert!(!result.cells.is_empty());
+    let balance = result
+        .fields
+        .iter()
+        .find(|field| field.path == "account/balance")
+        .unwrap();
+    assert_eq!(balance.new_value, Some(json!("0x3e8")));
+    assert!(result.fields.iter().all(|field| field.path != "account/code_hash"));
+}
+
+#[test]
+fn test_boc_limits() {
+    let account = base64::encode(include_bytes!("test_data/account.boc"));
+    let get_hash = |client: &TestClient, boc: &str| {
+        client.request::<_, ResultOfGetBocHash>(
+            "boc.get_boc_hash",
+            ParamsOfGetBocHash { boc: boc.to_string() },
+        )
+    };
+    let assert_limit = |result: ClientResult<ResultOfGetBocHash>, limit: &str| {
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::BocLimitExceeded as u32);
+        assert_eq!(error.data["limit"], limit);
+    };
+
+    let client = TestClient::new_with_config(json!({ "boc": { "max_boc_size": 16 } }));
+    assert_limit(get_hash(&client, &account), "max_boc_size");
+
+    let client = TestClient::new_with_config(json!({ "boc": { "max_cells": 1 } }));
+    assert_limit(get_hash(&client, &account), "max_cells");
+
+    let client = TestClient::new_with_config(json!({ "boc": { "max_depth": 0 } }));
+    assert_limit(get_hash(&client, &account), "max_depth");
+
+    let (parsed, _) =
+        deserialize_object_from_boc_bin::<ever_block::Account>(include_bytes!("test_data/account.boc"))
+            .unwrap();
+    let code = parsed.get_code().unwrap().repr_hash();
+    let proof = ever_block::MerkleProof::create(&parsed.serialize().unwrap(), |hash| *hash != code)
+        .unwrap();
+    let proof = base64::encode(&proof.write_to_bytes().unwrap());
+
+    let client = TestClient::new_with_config(json!({
+        "boc": { "allowed_exotic_cells": ["MerkleProof"] }
+    }));
+    get_hash(&client, &account).unwrap();
+    assert_limit(get_hash(&client, &proof), "allowed_exotic_cells");
+
+    let client = TestClient::new_with_config(json!({
+        "boc": {
+            "max_boc_size": 1024 * 1024,
+            "max_cells": 10000,
+            "max_depth": 1000,
+            "allowed_exotic_cells": ["MerkleProof", "PrunedBranch"]
+        }
+    }));
+    get_hash(&client, &account).unwrap();
+    get_hash(&client, &proof).unwrap();
+}
+
+fn persistent_cache_client(storage_path: &str, local_cache_max_size: usize) -> TestClient {
+    TestClient::new_with_config(json!({
+        "local_storage_path": storage_path,
+        "boc": { "local_cache_max_size": local_cache_max_size },
+    }))
+}
+
+async fn load_cached_boc(client: &TestClient, boc_ref: &str) -> bool {
+    client
+        .request_async::<_, ResultOfBocCacheLoad>(
+            "boc.cache_load",
+            ParamsOfBocCacheLoad { boc_ref: boc_ref.to_string(), cache_type: None },
+        )
+        .await
+        .unwrap()
+        .loaded
+}
+
+async fn set_persistent_boc(client: &TestClient, boc: &str) -> String {
+    client
+        .request_async::<_, ResultOfBocCacheSet>(
+            "boc.cache_set",
+            ParamsOfBocCacheSet { boc: boc.to_string(), cache_type: BocCacheType::Persistent },
+        )
+        .await
+        .unwrap()
+        .boc_ref
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_persistent_cache() {
+    let storage_path = std::env::temp_dir()
+        .join(format!("tonclient-bocs-{}", rand::random::<u32>()))
+        .to_string_lossy()
+        .to_string();
+
+    let boc1 = TestClient::tvc(crate::tests::TEST_DEBOT, None).unwrap();
+    let boc2 = TestClient::tvc(crate::tests::SUBSCRIBE, None).unwrap();
+
+    let client = persistent_cache_client(&storage_path, 1024);
+    let ref1 = set_persistent_boc(&client, &boc1).await;
+    drop(client);
+
+    // the reference is resolved from the local storage without `cache_load`
+    let client = persistent_cache_client(&storage_path, 1024);
+    let cached: ResultOfBocCacheGet = client
+        .request_async("boc.cache_get", ParamsOfBocCacheGet { boc_ref: ref1.clone() })
+        .await
+        .unwrap();
+    assert_eq!(cached.boc, Some(boc1.clone()));
+
+    let client = persistent_cache_client(&storage_path, 1024);
+    assert!(load_cached_boc(&client, &ref1).await);
+    let cached: ResultOfBocCacheGet = client
+        .request_async("boc.cache_get", ParamsOfBocCacheGet { boc_ref: ref1.clone() })
+        .await
+        .unwrap();
+    assert_eq!(cached.boc, Some(boc1.clone()));
+
+    let unknown = format!("*{}", ever_block::UInt256::default().as_hex_string());
+    assert!(!load_cached_boc(&client, &unknown).await);
+
+    let stats: ResultOfBocCacheStats =
+        client.request_async("boc.cache_stats", ()).await.unwrap();
+    assert_eq!(stats.memory_hits, 1);
+    assert_eq!(stats.memory_misses, 2);
+    assert_eq!(stats.storage_hits, 1);
+    assert_eq!(stats.storage_misses, 1);
+    assert_eq!(stats.cached_count, 1);
+    assert_eq!(stats.stored_count, 1);
+    assert_eq!(stats.stored_size, base64::decode(&boc1).unwrap().len() as u64);
+
+    // only one of the BOCs fits into the local storage
+    let max_size = std::cmp::max(
+        base64::decode(&boc1).unwrap().len(),
+        base64::decode(&boc2).unwrap().len(),
+    ) / 1024 + 1;
+    let client = persistent_cache_client(&storage_path, max_size);
+    set_persistent_boc(&client, &boc2).await;
+    assert!(!load_cached_boc(&persistent_cache_client(&storage_path, max_size), &ref1).await);
+
+    let _ = std::fs::remove_dir_all(&storage_path);
+}
+
+pub(crate) fn library_reference_cell(hash: &ever_block::UInt256) -> ever_block::Cell {
+    // library reference cell contains the cell type byte followed by the library hash
+    let mut data = vec![2u8];
+    data.extend_from_slice(hash.as_slice());
+    let mut builder = BuilderData::with_raw(data, 264).unwrap();
+    builder.set_type(ever_block::CellType::LibraryReference);
+    builder.into_cell().unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_resolve_libraries() {
+    let client = TestClient::new();
+
+    let nested = BuilderData::with_raw(vec![0xAB], 8).unwrap().into_cell().unwrap();
+    let mut builder = BuilderData::with_raw(vec![0x01], 8).unwrap();
+    builder.checked_append_reference(library_reference_cell(&nested.repr_hash())).unwrap();
+    let library = builder.into_cell().unwrap();
+    let unknown = ever_block::UInt256::from([0x11; 32]);
+
+    let mut builder = BuilderData::new();
+    builder.checked_append_reference(library_reference_cell(&library.repr_hash())).unwrap();
+    builder.checked_append_reference(library_reference_cell(&unknown)).unwrap();
+    let boc = serialize_cell_to_base64(&builder.into_cell().unwrap(), "cell").unwrap();
+
+    let libraries = json!({
+        library.repr_hash().as_hex_string(): serialize_cell_to_base64(&library, "library").unwrap(),
+        nested.repr_hash().as_hex_string(): serialize_cell_to_base64(&nested, "library").unwrap(),
+    });
+    let result: ResultOfResolveLibraries = client
+        .request_async(
+            "boc.resolve_libraries",
+            ParamsOfResolveLibraries {
+                boc: boc.clone(),
+                libraries: Some(libraries.clone()),
+                query_network: Some(false),
+                boc_cache: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.libraries, libraries);
+    assert_eq!(result.missing, vec![unknown.as_hex_string()]);
+
+    let error = client
+        .request_async::<_, ResultOfResolveLibraries>(
+            "boc.resolve_libraries",
+            ParamsOfResolveLibraries {
+                boc,
+                libraries: Some(json!({
+                    unknown.as_hex_string(): serialize_cell_to_base64(&nested, "library").unwrap(),
+                })),
+                query_network: Some(false),
+                boc_cache: None,
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidLibrary as u32);
+}
+
+#[test]
+fn test_encode_tvc() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(EVENTS_OLD, Some(2));
+    let state_init: ResultOfDecodeStateInit = client
+        .request(
+            "boc.decode_state_init",
+            ParamsOfDecodeStateInit { state_init: tvc.clone().unwrap(), ..Default::default() },
+        )
+        .unwrap();
+
+    let tvc_v2: ResultOfEncodeTvc = client
+        .request(
+            "boc.encode_tvc",
+            ParamsOfEncodeTvc {
+                code: state_init.code.clone().unwrap(),
+                data: state_init.data.clone(),
+                abi: Some(abi.clone()),
+                description: Some("Events".to_string()),
+                source_metadata: Some("Events.sol".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let decoded: ResultOfDecodeTvc = decode_tvc(
+        client.context().clone(),
+        ParamsOfDecodeTvc { tvc: tvc_v2.tvc.clone() },
+    )
+    .unwrap();
+    assert_eq!(
+        decoded.tvc,
+        Tvc::V2(TvcV2 {
+            code: state_init.code.unwrap(),
+            data: state_init.data,
+            abi: Some(abi.json_string().unwrap()),
+            compiler_version: state_init.compiler_version,
+            description: Some("Events".to_string()),
+            source_metadata: Some("Events.sol".to_string()),
+        })
+    );
+
+    let keys = client.generate_sign_keys();
+    let encode_deploy = |tvc: Option<String>| {
+        client
+            .request::<_, ResultOfEncodeMessage>(
+                "abi.encode_message",
+                ParamsOfEncodeMessage {
+                    abi: abi.clone(),
+                    deploy_set: DeploySet::some_with_tvc(tvc),
+                    call_set: Some(CallSet {
+                        function_name: "constructor".into(),
+                        header: Some(FunctionHeader {
+                            pubkey: Some(keys.public.clone()),
+                            time: Some(1599458364291),
+                            expire: Some(1599458404),
+                        }),
+                        input: None,
+                    }),
+                    signer: Signer::Keys { keys: keys.clone() },
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+    };
+    let expected = encode_deploy(tvc);
+    let encoded = encode_deploy(Some(tvc_v2.tvc));
+    assert_eq!(encoded.address, expected.address);
+    assert_eq!(encoded.message, expected.message);
+}
+
+#[test]
+fn test_inspect_and_reserialize() {
+    let client = TestClient::new();
+    let inspect = |boc: &str| -> ResultOfInspectBoc {
+        client
+            .request("boc.inspect", ParamsOfInspectBoc { boc: boc.to_string() })
+            .unwrap()
+    };
+
+    let child = BuilderData::with_raw(vec![0xAB, 0xC0], 10).unwrap().into_cell().unwrap();
+    let mut builder = BuilderData::with_raw(vec![0x01], 8).unwrap();
+    builder.checked_append_reference(child.clone()).unwrap();
+    builder.checked_append_reference(child).unwrap();
+    let cell = builder.into_cell().unwrap();
+    let boc = serialize_cell_to_base64(&cell, "cell").unwrap();
+
+    let result = inspect(&boc);
+    assert_eq!(result.cells_count, 3);
+    assert_eq!(result.unique_cells_count, 2);
+    assert_eq!(result.total_bits, 18);
+    assert_eq!(result.total_refs, 2);
+    assert_eq!(result.exotic_cells, vec![]);
+    assert_eq!(result.max_depth, 1);
+    assert_eq!(result.level, 0);
+    assert_eq!(result.hashes, vec![cell.repr_hash().as_hex_string()]);
+    assert_eq!(result.depths, vec![1]);
+    assert_eq!(result.boc_size, Some(base64::decode(&boc).unwrap().len() as u32));
+    assert_eq!(result.header.unwrap().cells_count, 2);
+
+    let account = base64::encode(include_bytes!("test_data/account.boc"));
+    let original = inspect(&account);
+    for (with_index, with_crc32c, with_cache_bits) in [
+        (false, false, false),
+        (true, false, false),
+        (false, true, false),
+        (true, true, true),
+    ] {
+        let reserialized: ResultOfReserializeBoc = client
+            .request(
+                "boc.reserialize",
+                ParamsOfReserializeBoc {
+                    boc: account.clone(),
+                    with_index: Some(with_index),
+                    with_crc32c: Some(with_crc32c),
+                    with_cache_bits: Some(with_cache_bits),
+                    format: None,
+                },
+            )
+            .unwrap();
+        let result = inspect(&reserialized.boc);
+        assert_eq!(result.hashes, original.hashes);
+        assert_eq!(result.unique_cells_count, original.unique_cells_count);
+        let header = result.header.unwrap();
+        assert_eq!(header.has_index, with_index);
+        assert_eq!(header.has_crc32c, with_crc32c);
+        assert_eq!(header.has_cache_bits, with_cache_bits);
+        assert_eq!(header.cells_count, original.unique_cells_count as u64);
+        assert_eq!(header.roots_count, 1);
+
+        let hex: ResultOfReserializeBoc = client
+            .request(
+                "boc.reserialize",
+                ParamsOfReserializeBoc {
+                    boc: account.clone(),
+                    with_index: Some(with_index),
+                    with_crc32c: Some(with_crc32c),
+                    with_cache_bits: Some(with_cache_bits),
+                    format: Some(BocFormat::Hex),
+                },
+            )
+            .unwrap();
+        assert_eq!(base64::encode(&hex::decode(&hex.boc).unwrap()), reserialized.boc);
+    }
+
+    let error = client
+        .request::<_, ResultOfReserializeBoc>(
+            "boc.reserialize",
+            ParamsOfReserializeBoc {
+                boc: account,
+                with_cache_bits: Some(true),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::SerializationError as u32);
+
+    let (parsed, _) =
+        deserialize_object_from_boc_bin::<ever_block::Account>(include_bytes!("test_data/account.boc"))
+            .unwrap();
+    let code = parsed.get_code().unwrap().repr_hash();
+    let proof = ever_block::MerkleProof::create(&parsed.serialize().unwrap(), |hash| *hash != code)
+        .unwrap();
+    let result = inspect(&base64::encode(&proof.write_to_bytes().unwrap()));
+    assert_eq!(result.exotic_cells[0].cell_type, ExoticCellType::PrunedBranch);
+    assert_eq!(
+        result.exotic_cells[1],
+        ExoticCellsCount { cell_type: ExoticCellType::MerkleProof, count: 1 }
+    );
+}
+
+#[test]
+fn test_shardstate_accounts() {
+    let client = TestClient::new();
+    let shardstate = base64::encode(&include_bytes!("test_data/zerostate.boc"));
+
+    let all: ResultOfListShardstateAccounts = client
+        .request(
+            "boc.list_shardstate_accounts",
+            ParamsOfListShardstateAccounts {
+                shardstate: shardstate.clone(),
+                limit: Some(u32::MAX),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(all.accounts.len() > 2);
+    assert!(!all.has_more);
+    assert!(all.accounts.iter().all(|account| account.boc.is_none()));
+    assert!(all.accounts.windows(2).all(|pair| pair[0].address < pair[1].address));
+
+    let mut paged = Vec::new();
+    let mut after = None;
+    loop {
+        let page: ResultOfListShardstateAccounts = client
+            .request(
+                "boc.list_shardstate_accounts",
+                ParamsOfListShardstateAccounts {
+                    shardstate: shardstate.clone(),
+                    after: after.clone(),
+                    limit: Some(2),
+                    include_boc: Some(true),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(page.accounts.len() <= 2);
+        after = page.accounts.last().map(|account| account.address.clone());
+        paged.extend(page.accounts);
+        if !page.has_more {
+            break;
+        }
+    }
+    assert_eq!(paged.len(), all.accounts.len());
+
+    for (account, item) in all.accounts.iter().zip(paged.iter()) {
+        assert_eq!(account.address, item.address);
+        assert_eq!(account.balance, item.balance);
+
+        let found: ResultOfGetShardstateAccount = client
+            .request(
+                "boc.get_shardstate_account",
+                ParamsOfGetShardstateAccount {
+                    shardstate: shardstate.clone(),
+                    address: account.address.clone(),
+                    boc_cache: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(found.account, item.boc);
+        assert_eq!(found.last_trans_hash.as_ref(), Some(&account.last_trans_hash));
+        assert_eq!(found.last_trans_lt.as_ref(), Some(&account.last_trans_lt));
+
+        let parsed: ResultOfParse = client
+            .request(
+                "boc.parse_account",
+                ParamsOfParse { boc: found.account.unwrap() },
+            )
+            .unwrap();
+        assert_eq!(parsed.parsed["id"], account.address);
+        let balance = u64::from_str(&account.balance).unwrap();
+        assert_eq!(parsed.parsed["balance"], format!("0x{:x}", balance));
+    }
+
+    let missing: ResultOfGetShardstateAccount = client
+        .request(
+            "boc.get_shardstate_account",
+            ParamsOfGetShardstateAccount {
+                shardstate,
+                address: format!("-1:{}", "1".repeat(64)),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+    assert!(missing.account.is_none());
+}
+
+#[test]
+fn test_get_block_messages() {
+    let client = TestClient::new();
+    let block = base64::encode(&include_bytes!("test_data/block.boc"));
+
+    let parsed: ResultOfParse = client
+        .request("boc.parse_block", ParamsOfParse { boc: block.clone() })
+        .unwrap();
+    let messages: ResultOfGetBlockMessages = client
+        .request(
+            "boc.get_block_messages",
+            ParamsOfGetBlockMessages {
+                block,
+                include_boc: Some(true),
+                boc_cache: None,
+            },
+        )
+        .unwrap();
+
+    let ids = |descr: &Value| -> Vec<String> {
+        descr
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|msg| msg["msg_id"].as_str().map(|id| id.to_string()))
+            .collect()
+    };
+    let msg_ids = |msgs: &[BlockMessageDescr]| -> Vec<String> {
+        msgs.iter().map(|msg| msg.msg_id.clone()).collect()
+    };
+    assert_eq!(msg_ids(&messages.in_msgs), ids(&parsed.parsed["in_msg_descr"]));
+    assert_eq!(msg_ids(&messages.out_msgs), ids(&parsed.parsed["out_msg_descr"]));
+    assert!(!messages.in_msgs.is_empty() || !messages.out_msgs.is_empty());
+
+    for msg in messages.in_msgs.iter().chain(messages.out_msgs.iter()) {
+        if let Some(boc) = &msg.boc {
+            let hash = internal::deserialize_cell_from_base64(boc, "message")
+                .unwrap()
+                .1
+                .repr_hash();
+            assert_eq!(hash.as_hex_string(), msg.msg_id);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::schema::{var_len_bits, TlbField, TlbSchema, TlbTag, TlbType};
+use crate::boc::internal::serialize_cell_to_boc;
+use crate::boc::reader::query::{read_int, read_uint};
+use crate::boc::{BocCacheType, Error};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use ever_block::{Cell, Deserializable, HashmapE, HashmapType, MsgAddress, SliceData};
+use serde_json::Value;
+
+pub(crate) struct TlbDecoder<'a> {
+    schema: &'a TlbSchema,
+    context: &'a ClientContext,
+    boc_cache: Option<BocCacheType>,
+}
+
+fn value_error<E: std::fmt::Display>(err: E) -> crate::error::ClientError {
+    Error::invalid_tlb_value(err)
+}
+
+impl<'a> TlbDecoder<'a> {
+    pub fn new(
+        schema: &'a TlbSchema,
+        context: &'a ClientContext,
+        boc_cache: Option<BocCacheType>,
+    ) -> Self {
+        Self {
+            schema,
+            context,
+            boc_cache,
+        }
+    }
+
+    pub fn decode(&self, cell: Cell, type_name: &str) -> ClientResult<Value> {
+        let mut slice = SliceData::load_cell(cell).map_err(value_error)?;
+        self.decode_named(&mut slice, type_name)
+    }
+
+    fn serialize_cell(&self, cell: Cell) -> ClientResult<Value> {
+        Ok(Value::String(serialize_cell_to_boc(
+            self.context,
+            cell,
+            "TL-B value",
+            self.boc_cache.clone(),
+        )?))
+    }
+
+    fn tag_matches(slice: &SliceData, tag: &TlbTag) -> bool {
+        if tag.bits == 0 {
+            return true;
+        }
+        let mut probe = slice.clone();
+        probe.get_next_bits(tag.bits).ok().as_ref() == Some(&tag.value)
+    }
+
+    fn decode_named(&self, slice: &mut SliceData, type_name: &str) -> ClientResult<Value> {
+        let constructor = self
+            .schema
+            .constructors(type_name)?
+            .iter()
+            .find(|constructor| Self::tag_matches(slice, &constructor.tag))
+            .ok_or_else(|| {
+                Error::invalid_tlb_value(format!(
+                    "no constructor of type [{}] matches the data",
+                    type_name
+                ))
+            })?;
+        if constructor.tag.bits > 0 {
+            slice
+                .get_next_bits(constructor.tag.bits)
+                .map_err(value_error)?;
+        }
+        let mut result = self.decode_fields(slice, &constructor.fields)?;
+        result["@type"] = Value::String(constructor.name.clone());
+        Ok(result)
+    }
+
+    fn decode_fields(&self, slice: &mut SliceData, fields: &Vec<TlbField>) -> ClientResult<Value> {
+        let mut values = serde_json::Map::new();
+        for field in fields {
+            let value = self.decode_type(slice, &field.field_type).map_err(|mut err| {
+                err.data["field"] = field.name.clone().into();
+                err
+            })?;
+            values.insert(field.name.clone(), value);
+        }
+        Ok(Value::Object(values))
+    }
+
+    fn decode_dict(
+        &self,
+        slice: &mut SliceData,
+        key_size: usize,
+        value_type: &TlbType,
+    ) -> ClientResult<Value> {
+        let mut dict = HashmapE::with_bit_len(key_size);
+        dict.read_hashmap_data(slice).map_err(value_error)?;
+        let mut entries = Vec::new();
+        dict.iterate_slices(|key, value| {
+            entries.push((key, value));
+            Ok(true)
+        })
+        .map_err(value_error)?;
+
+        let mut result = serde_json::Map::new();
+        for (mut key, mut value) in entries {
+            result.insert(
+                read_uint(&mut key, key_size)?.to_string(),
+                self.decode_type(&mut value, value_type)?,
+            );
+        }
+        Ok(Value::Object(result))
+    }
+
+    fn decode_type(&self, slice: &mut SliceData, tlb_type: &TlbType) -> ClientResult<Value> {
+        Ok(match tlb_type {
+            TlbType::UInt(size) => Value::String(read_uint(slice, *size)?.to_string()),
+            TlbType::Int(size) => Value::String(read_int(slice, *size)?.to_string()),
+            TlbType::Bits(size) => Value::String(hex::encode(
+                slice.get_next_bits(*size).map_err(value_error)?,
+            )),
+            TlbType::Bool => Value::Bool(slice.get_next_bit().map_err(value_error)?),
+            TlbType::VarUInt(n) => {
+                let len = read_uint(slice, var_len_bits(*n))?.to_string();
+                let len = len.parse::<usize>().map_err(value_error)?;
+                if len == 0 {
+                    Value::String("0".to_string())
+                } else {
+                    Value::String(read_uint(slice, len * 8)?.to_string())
+                }
+            }
+            TlbType::VarInt(n) => {
+                let len = read_uint(slice, var_len_bits(*n))?.to_string();
+                let len = len.parse::<usize>().map_err(value_error)?;
+                if len == 0 {
+                    Value::String("0".to_string())
+                } else {
+                    Value::String(read_int(slice, len * 8)?.to_string())
+                }
+            }
+            TlbType::Address => match MsgAddress::construct_from(slice).map_err(value_error)? {
+                MsgAddress::AddrNone => Value::Null,
+                address => Value::String(address.to_string()),
+            },
+            TlbType::Any => {
+                let rest = std::mem::take(slice);
+                self.serialize_cell(rest.into_cell())?
+            }
+            TlbType::Ref(inner) => {
+                let cell = slice.checked_drain_reference().map_err(value_error)?;
+                if let TlbType::Any = inner.as_ref() {
+                    self.serialize_cell(cell)?
+                } else {
+                    let mut nested = SliceData::load_cell(cell).map_err(value_error)?;
+                    self.decode_type(&mut nested, inner)?
+                }
+            }
+            TlbType::Maybe(inner) => {
+                if slice.get_next_bit().map_err(value_error)? {
+                    self.decode_type(slice, inner)?
+                } else {
+                    Value::Null
+                }
+            }
+            TlbType::Either(left, right) => {
+                if slice.get_next_bit().map_err(value_error)? {
+                    json!({ "@type": "right", "value": self.decode_type(slice, right)? })
+                } else {
+                    json!({ "@type": "left", "value": self.decode_type(slice, left)? })
+                }
+            }
+            TlbType::HashmapE(key_size, value_type) => {
+                self.decode_dict(slice, *key_size, value_type)?
+            }
+            TlbType::Anonymous(fields) => self.decode_fields(slice, fields)?,
+            TlbType::Named(name) => self.decode_named(slice, name)?,
+        })
+    }
+}
@@ -0,0 +1,338 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::schema::{var_len_bits, TlbField, TlbSchema, TlbTag, TlbType};
+use crate::boc::encode::build_cell;
+use crate::boc::internal::{deserialize_cell_from_boc, serialize_cell_to_base64};
+use crate::boc::{BuilderOp, Error};
+use crate::client::ClientContext;
+use crate::error::{ClientError, ClientResult};
+use ever_block::{HashmapE, HashmapType, SliceData};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{Num, One, Signed, Zero};
+use serde_json::Value;
+use std::sync::Arc;
+
+pub(crate) struct TlbEncoder<'a> {
+    schema: &'a TlbSchema,
+    context: &'a Arc<ClientContext>,
+}
+
+fn value_error<E: std::fmt::Display>(err: E) -> ClientError {
+    Error::invalid_tlb_value(err)
+}
+
+fn integer(size: usize, value: impl ToString) -> BuilderOp {
+    BuilderOp::Integer {
+        size: size as u32,
+        value: Value::String(value.to_string()),
+    }
+}
+
+fn tag_to_bitstring(tag: &TlbTag) -> String {
+    let mut result = String::from("n");
+    for i in 0..tag.bits {
+        result.push(if tag.bit(i) { '1' } else { '0' });
+    }
+    result
+}
+
+/// Parses JSON number or decimal / `0x` prefixed hexadecimal string.
+fn parse_bigint(value: &Value) -> ClientResult<BigInt> {
+    if let Some(number) = value.as_i64() {
+        return Ok(BigInt::from(number));
+    }
+    if let Some(number) = value.as_u64() {
+        return Ok(BigInt::from(number));
+    }
+    let string = value
+        .as_str()
+        .ok_or_else(|| value_error("integer value must be a number or a string"))?
+        .trim();
+    let (negative, string) = if let Some(rest) = string.strip_prefix('-') {
+        (true, rest)
+    } else {
+        (false, string)
+    };
+    let unsigned = if let Some(hex) = string
+        .strip_prefix("0x")
+        .or_else(|| string.strip_prefix("0X"))
+    {
+        BigUint::from_str_radix(hex, 16)
+    } else {
+        BigUint::from_str_radix(string, 10)
+    }
+    .map_err(|err| value_error(format!("invalid integer [{}]: {}", string, err)))?;
+    Ok(BigInt::from_biguint(
+        if negative { Sign::Minus } else { Sign::Plus },
+        unsigned,
+    ))
+}
+
+fn check_uint(value: &Value, size: usize) -> ClientResult<BigInt> {
+    let number = parse_bigint(value)?;
+    if number.is_negative() || number.bits() > size as u64 {
+        return Err(value_error(format!(
+            "integer {} does not fit into {} unsigned bits",
+            number, size
+        )));
+    }
+    Ok(number)
+}
+
+fn check_int(value: &Value, size: usize) -> ClientResult<BigInt> {
+    let number = parse_bigint(value)?;
+    let bound = if size > 0 { BigInt::one() << (size - 1) } else { BigInt::zero() };
+    if number.is_positive() && number >= bound || number.is_negative() && number < -&bound {
+        return Err(value_error(format!(
+            "integer {} does not fit into {} signed bits",
+            number, size
+        )));
+    }
+    Ok(number)
+}
+
+impl<'a> TlbEncoder<'a> {
+    pub fn new(schema: &'a TlbSchema, context: &'a Arc<ClientContext>) -> Self {
+        Self { schema, context }
+    }
+
+    pub fn encode(&self, value: &Value, type_name: &str) -> ClientResult<Vec<BuilderOp>> {
+        let mut ops = Vec::new();
+        self.encode_named(&mut ops, value, type_name)?;
+        Ok(ops)
+    }
+
+    fn encode_named(
+        &self,
+        ops: &mut Vec<BuilderOp>,
+        value: &Value,
+        type_name: &str,
+    ) -> ClientResult<()> {
+        let constructors = self.schema.constructors(type_name)?;
+        let constructor = if let Some(name) = value["@type"].as_str() {
+            constructors.iter().find(|constructor| constructor.name == name)
+        } else if constructors.len() == 1 {
+            constructors.first()
+        } else {
+            return Err(value_error(format!(
+                "`@type` field is required to choose constructor of type [{}]",
+                type_name
+            )));
+        }
+        .ok_or_else(|| {
+            value_error(format!(
+                "type [{}] has no constructor [{}]",
+                type_name, value["@type"]
+            ))
+        })?;
+        if constructor.tag.bits > 0 {
+            ops.push(BuilderOp::BitString {
+                value: tag_to_bitstring(&constructor.tag),
+            });
+        }
+        self.encode_fields(ops, value, &constructor.fields)
+    }
+
+    fn encode_fields(
+        &self,
+        ops: &mut Vec<BuilderOp>,
+        value: &Value,
+        fields: &Vec<TlbField>,
+    ) -> ClientResult<()> {
+        if !value.is_object() {
+            return Err(value_error(format!("object expected, got {}", value)));
+        }
+        for field in fields {
+            let field_value = &value[&field.name];
+            if field_value.is_null() {
+                match field.field_type {
+                    TlbType::Maybe(_) | TlbType::Address => {}
+                    _ => {
+                        return Err(value_error(format!("field [{}] is missing", field.name)))
+                    }
+                }
+            }
+            self.encode_type(ops, field_value, &field.field_type)
+                .map_err(|mut err| {
+                    if err.data["field"].is_null() {
+                        err.data["field"] = field.name.clone().into();
+                    }
+                    err
+                })?;
+        }
+        Ok(())
+    }
+
+    fn build_slice(&self, ops: &Vec<BuilderOp>) -> ClientResult<SliceData> {
+        SliceData::load_cell(build_cell(self.context, ops)?).map_err(value_error)
+    }
+
+    fn encode_dict(
+        &self,
+        ops: &mut Vec<BuilderOp>,
+        value: &Value,
+        key_size: usize,
+        value_type: &TlbType,
+    ) -> ClientResult<()> {
+        let entries = value
+            .as_object()
+            .ok_or_else(|| value_error(format!("object expected, got {}", value)))?;
+        let mut dict = HashmapE::with_bit_len(key_size);
+        for (key, entry) in entries {
+            let key = check_uint(&Value::String(key.clone()), key_size)?;
+            let key = self.build_slice(&vec![integer(key_size, key)])?;
+            let mut entry_ops = Vec::new();
+            self.encode_type(&mut entry_ops, entry, value_type)?;
+            let entry = self.build_slice(&entry_ops)?;
+            dict.set(key, &entry).map_err(value_error)?;
+        }
+        match dict.data() {
+            Some(root) => {
+                ops.push(integer(1, 1));
+                ops.push(BuilderOp::CellBoc {
+                    boc: serialize_cell_to_base64(root, "dictionary")?,
+                });
+            }
+            None => ops.push(integer(1, 0)),
+        }
+        Ok(())
+    }
+
+    /// Appends data and references of the cell as is.
+    fn encode_any(&self, ops: &mut Vec<BuilderOp>, value: &Value) -> ClientResult<()> {
+        let boc = value
+            .as_str()
+            .ok_or_else(|| value_error(format!("cell BOC expected, got {}", value)))?;
+        let (_, cell) = deserialize_cell_from_boc(self.context, boc, "TL-B value")?;
+        let bit_len = cell.bit_length();
+        if bit_len > 0 {
+            let mut slice = SliceData::load_cell(cell.clone()).map_err(value_error)?;
+            let bytes = slice.get_next_bits(bit_len).map_err(value_error)?;
+            let padding = bytes.len() * 8 - bit_len;
+            ops.push(integer(
+                bit_len,
+                format!("0x{:x}", BigUint::from_bytes_be(&bytes) >> padding),
+            ));
+        }
+        for i in 0..cell.references_count() {
+            let reference = cell.reference(i).map_err(value_error)?;
+            ops.push(BuilderOp::CellBoc {
+                boc: serialize_cell_to_base64(&reference, "TL-B value")?,
+            });
+        }
+        Ok(())
+    }
+
+    fn encode_type(
+        &self,
+        ops: &mut Vec<BuilderOp>,
+        value: &Value,
+        tlb_type: &TlbType,
+    ) -> ClientResult<()> {
+        match tlb_type {
+            TlbType::UInt(size) => ops.push(integer(*size, check_uint(value, *size)?)),
+            TlbType::Int(size) => ops.push(integer(*size, check_int(value, *size)?)),
+            TlbType::Bits(size) => {
+                let bytes = value
+                    .as_str()
+                    .map(hex::decode)
+                    .transpose()
+                    .map_err(value_error)?
+                    .ok_or_else(|| value_error(format!("hex string expected, got {}", value)))?;
+                if bytes.len() != (size + 7) / 8 {
+                    return Err(value_error(format!(
+                        "{} bits expected, got {} bytes",
+                        size,
+                        bytes.len()
+                    )));
+                }
+                let padding = bytes.len() * 8 - size;
+                ops.push(integer(
+                    *size,
+                    format!("0x{:x}", BigUint::from_bytes_be(&bytes) >> padding),
+                ));
+            }
+            TlbType::Bool => {
+                let bit = value
+                    .as_bool()
+                    .ok_or_else(|| value_error(format!("boolean expected, got {}", value)))?;
+                ops.push(integer(1, bit as u8));
+            }
+            TlbType::VarUInt(n) => {
+                let number = check_uint(value, (n - 1) * 8)?;
+                let len = (number.bits() as usize + 7) / 8;
+                ops.push(integer(var_len_bits(*n), len));
+                if len > 0 {
+                    ops.push(integer(len * 8, number));
+                }
+            }
+            TlbType::VarInt(n) => {
+                let number = check_int(value, (n - 1) * 8)?;
+                let len = if number.is_zero() {
+                    0
+                } else {
+                    number.to_signed_bytes_be().len()
+                };
+                ops.push(integer(var_len_bits(*n), len));
+                if len > 0 {
+                    ops.push(integer(len * 8, number));
+                }
+            }
+            TlbType::Address => match value {
+                Value::Null => ops.push(BuilderOp::BitString {
+                    value: "n00".to_string(),
+                }),
+                Value::String(address) => ops.push(BuilderOp::Address {
+                    address: address.clone(),
+                }),
+                _ => return Err(value_error(format!("address expected, got {}", value))),
+            },
+            TlbType::Any => self.encode_any(ops, value)?,
+            TlbType::Ref(inner) => {
+                let mut builder = Vec::new();
+                self.encode_type(&mut builder, value, inner)?;
+                ops.push(BuilderOp::Cell { builder });
+            }
+            TlbType::Maybe(inner) => {
+                if value.is_null() {
+                    ops.push(integer(1, 0));
+                } else {
+                    ops.push(integer(1, 1));
+                    self.encode_type(ops, value, inner)?;
+                }
+            }
+            TlbType::Either(left, right) => match value["@type"].as_str() {
+                Some("left") => {
+                    ops.push(integer(1, 0));
+                    self.encode_type(ops, &value["value"], left)?;
+                }
+                Some("right") => {
+                    ops.push(integer(1, 1));
+                    self.encode_type(ops, &value["value"], right)?;
+                }
+                _ => {
+                    return Err(value_error(
+                        "`Either` value must have `@type` equal to `left` or `right`",
+                    ))
+                }
+            },
+            TlbType::HashmapE(key_size, value_type) => {
+                self.encode_dict(ops, value, *key_size, value_type)?
+            }
+            TlbType::Anonymous(fields) => self.encode_fields(ops, value, fields)?,
+            TlbType::Named(name) => self.encode_named(ops, value, name)?,
+        }
+        Ok(())
+    }
+}
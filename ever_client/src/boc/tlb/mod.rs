@@ -0,0 +1,118 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+pub(crate) mod decoder;
+pub(crate) mod encoder;
+pub(crate) mod schema;
+
+use crate::boc::encode::build_cell;
+use crate::boc::internal::{deserialize_cell_from_boc, serialize_cell_to_boc};
+use crate::boc::BocCacheType;
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use serde_json::Value;
+
+use decoder::TlbDecoder;
+use encoder::TlbEncoder;
+use schema::TlbSchema;
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfParseTlb {
+    /// Cell BOC encoded as base64 or BOC cache key.
+    pub boc: String,
+    /// TL-B schema text.
+    ///
+    /// Supported subset of TL-B:
+    /// - constructors with `#hex`, `$binary` or empty tags:
+    ///   `transfer#0f8a7ea5 query_id:uint64 amount:Coins = Msg;`;
+    ///   constructors without explicit tag have no tag;
+    /// - integers `uintN`, `intN`, `(uint N)`, `(int N)`, `(## N)`, `#`, `Bit`, `Bool`;
+    /// - bit strings `bitsN` and `(bits N)`;
+    /// - `Grams`, `Coins`, `(VarUInteger n)` and `(VarInteger n)`;
+    /// - `MsgAddress`, `MsgAddressInt` and `MsgAddressExt`;
+    /// - `Cell` and `Any` for the rest of the cell, `^X` for references,
+    ///   `^[ fields ]` for anonymous nested cells;
+    /// - `(Maybe X)`, `(Either X Y)` and `(HashmapE n X)`;
+    /// - references to other non-parametrized types declared in the schema.
+    ///
+    /// Implicit fields, constraints and parametrized user types are not supported.
+    /// Types that can contain themselves without consuming any data, e.g. `_ x:A = A;`,
+    /// are rejected.
+    pub schema: String,
+    /// Name of the type to decode the cell with.
+    pub type_name: String,
+    /// Cache type to put the nested cells into.
+    /// The BOCs themselves are returned if no cache type is provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ResultOfParseTlb {
+    /// JSON containing parsed cell.
+    ///
+    /// Objects decoded from the declared types contain the constructor name in the `@type` field.
+    /// Integers are returned as decimal strings, bit strings as hex strings,
+    /// cells as BOCs, `HashmapE` as objects with decimal keys and
+    /// `Either` as `{ "@type": "left" | "right", "value": ... }`.
+    pub parsed: Value,
+}
+
+/// Parses cell using TL-B schema.
+#[api_function]
+pub fn parse_tlb(
+    context: std::sync::Arc<ClientContext>,
+    params: ParamsOfParseTlb,
+) -> ClientResult<ResultOfParseTlb> {
+    let schema = TlbSchema::parse(&params.schema)?;
+    let (_, cell) = deserialize_cell_from_boc(&context, &params.boc, "cell")?;
+    let parsed =
+        TlbDecoder::new(&schema, &context, params.boc_cache).decode(cell, &params.type_name)?;
+    Ok(ResultOfParseTlb { parsed })
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ParamsOfEncodeTlb {
+    /// TL-B schema text. See `parse_tlb` for the supported subset.
+    pub schema: String,
+    /// Name of the type to encode the value with.
+    pub type_name: String,
+    /// Value in the same form as returned by `parse_tlb`.
+    ///
+    /// `@type` field can be omitted for types with a single constructor.
+    pub value: Value,
+    /// Cache type to put the result.
+    /// The BOC itself returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ApiType, Default)]
+pub struct ResultOfEncodeTlb {
+    /// Encoded cell BOC or BOC cache key.
+    pub boc: String,
+}
+
+/// Encodes cell using TL-B schema.
+///
+/// The value is converted into builder operations and encoded the same way as `encode_boc` does.
+#[api_function]
+pub fn encode_tlb(
+    context: std::sync::Arc<ClientContext>,
+    params: ParamsOfEncodeTlb,
+) -> ClientResult<ResultOfEncodeTlb> {
+    let schema = TlbSchema::parse(&params.schema)?;
+    let builder = TlbEncoder::new(&schema, &context).encode(&params.value, &params.type_name)?;
+    let cell = build_cell(&context, &builder)?;
+    Ok(ResultOfEncodeTlb {
+        boc: serialize_cell_to_boc(&context, cell, "TL-B value", params.boc_cache)?,
+    })
+}
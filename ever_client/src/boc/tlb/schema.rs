@@ -0,0 +1,605 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::boc::Error;
+use crate::error::{ClientError, ClientResult};
+use std::collections::{HashMap, HashSet};
+
+const MAX_INT_SIZE: usize = 256;
+const MAX_BITS_SIZE: usize = 1023;
+
+/// Bit size of the length prefix of `VarUInteger n` and `VarInteger n`.
+pub(crate) fn var_len_bits(n: usize) -> usize {
+    (usize::BITS - (n.max(1) - 1).leading_zeros()) as usize
+}
+
+/// Constructor tag: `bits` high bits of `value`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct TlbTag {
+    pub value: Vec<u8>,
+    pub bits: usize,
+}
+
+impl TlbTag {
+    fn from_binary(source: &str) -> Self {
+        let mut tag = Self::default();
+        for c in source.chars() {
+            tag.push_bit(c == '1');
+        }
+        tag
+    }
+
+    fn from_hex(source: &str) -> Self {
+        let (digits, completion) = if source.ends_with('_') {
+            (&source[..source.len() - 1], true)
+        } else {
+            (source, false)
+        };
+        let mut tag = Self::default();
+        for c in digits.chars() {
+            let digit = c.to_digit(16).unwrap_or(0) as u8;
+            for shift in (0..4).rev() {
+                tag.push_bit((digit >> shift) & 1 != 0);
+            }
+        }
+        if completion {
+            // remove trailing zeros and the completion bit
+            while tag.bits > 0 && !tag.bit(tag.bits - 1) {
+                tag.bits -= 1;
+            }
+            if tag.bits > 0 {
+                tag.bits -= 1;
+            }
+            tag.value.truncate((tag.bits + 7) / 8);
+            if tag.bits % 8 != 0 {
+                let last = tag.value.len() - 1;
+                tag.value[last] &= 0xFF << (8 - tag.bits % 8);
+            }
+        }
+        tag
+    }
+
+    fn push_bit(&mut self, bit: bool) {
+        if self.bits % 8 == 0 {
+            self.value.push(0);
+        }
+        if bit {
+            let last = self.value.len() - 1;
+            self.value[last] |= 0x80 >> (self.bits % 8);
+        }
+        self.bits += 1;
+    }
+
+    pub fn bit(&self, index: usize) -> bool {
+        self.value[index / 8] & (0x80 >> (index % 8)) != 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TlbType {
+    UInt(usize),
+    Int(usize),
+    Bits(usize),
+    Bool,
+    VarUInt(usize),
+    VarInt(usize),
+    Address,
+    /// Remaining data and references of the current cell.
+    Any,
+    Ref(Box<TlbType>),
+    Maybe(Box<TlbType>),
+    Either(Box<TlbType>, Box<TlbType>),
+    HashmapE(usize, Box<TlbType>),
+    Anonymous(Vec<TlbField>),
+    Named(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TlbField {
+    pub name: String,
+    pub field_type: TlbType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TlbConstructor {
+    pub name: String,
+    pub tag: TlbTag,
+    pub fields: Vec<TlbField>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct TlbSchema {
+    pub types: HashMap<String, Vec<TlbConstructor>>,
+}
+
+impl TlbSchema {
+    pub fn parse(source: &str) -> ClientResult<Self> {
+        let mut parser = Parser::tokenize(source)?;
+        let mut schema = Self::default();
+        while !parser.eof() {
+            let (type_name, constructor) = parser.parse_declaration()?;
+            schema.types.entry(type_name).or_insert_with(Vec::new).push(constructor);
+        }
+        schema.check()?;
+        Ok(schema)
+    }
+
+    pub fn constructors(&self, type_name: &str) -> ClientResult<&Vec<TlbConstructor>> {
+        self.types.get(type_name).ok_or_else(|| {
+            Error::invalid_tlb_schema(format!("type [{}] is not declared", type_name))
+        })
+    }
+
+    fn check_type(&self, tlb_type: &TlbType) -> ClientResult<()> {
+        match tlb_type {
+            TlbType::Named(name) => self.constructors(name).map(|_| ()),
+            TlbType::Ref(inner) | TlbType::Maybe(inner) | TlbType::HashmapE(_, inner) => {
+                self.check_type(inner)
+            }
+            TlbType::Either(left, right) => {
+                self.check_type(left)?;
+                self.check_type(right)
+            }
+            TlbType::Anonymous(fields) => {
+                for field in fields {
+                    self.check_type(&field.field_type)?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check(&self) -> ClientResult<()> {
+        for constructors in self.types.values() {
+            for constructor in constructors {
+                for field in &constructor.fields {
+                    self.check_type(&field.field_type)?;
+                }
+            }
+        }
+        self.check_recursion()
+    }
+
+    /// Checks if the type consumes at least one bit or reference of the data.
+    fn consumes_data(tlb_type: &TlbType, consuming: &HashSet<&str>) -> bool {
+        match tlb_type {
+            TlbType::UInt(size) | TlbType::Int(size) | TlbType::Bits(size) => *size > 0,
+            TlbType::VarUInt(n) | TlbType::VarInt(n) => var_len_bits(*n) > 0,
+            TlbType::Bool
+            | TlbType::Address
+            | TlbType::Ref(_)
+            | TlbType::Maybe(_)
+            | TlbType::Either(_, _)
+            | TlbType::HashmapE(_, _) => true,
+            TlbType::Any => false,
+            TlbType::Anonymous(fields) => fields
+                .iter()
+                .any(|field| Self::consumes_data(&field.field_type, consuming)),
+            TlbType::Named(name) => consuming.contains(name.as_str()),
+        }
+    }
+
+    /// Collects the types consuming data with every constructor.
+    fn consuming_types(&self) -> HashSet<&str> {
+        let mut consuming = HashSet::new();
+        loop {
+            let found = self
+                .types
+                .iter()
+                .filter(|(name, _)| !consuming.contains(name.as_str()))
+                .filter(|(_, constructors)| {
+                    constructors.iter().all(|constructor| {
+                        constructor.tag.bits > 0
+                            || constructor
+                                .fields
+                                .iter()
+                                .any(|field| Self::consumes_data(&field.field_type, &consuming))
+                    })
+                })
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+            if found.is_empty() {
+                return consuming;
+            }
+            consuming.extend(found);
+        }
+    }
+
+    /// Collects the named types of the fields read before any data is consumed.
+    ///
+    /// Returns `true` if the fields consume data.
+    fn leading_types<'s>(
+        fields: &'s [TlbField],
+        consuming: &HashSet<&str>,
+        result: &mut Vec<&'s str>,
+    ) -> bool {
+        for field in fields {
+            match &field.field_type {
+                TlbType::Named(name) => result.push(name),
+                TlbType::Anonymous(fields) => {
+                    if Self::leading_types(fields, consuming, result) {
+                        return true;
+                    }
+                }
+                _ => {}
+            }
+            if Self::consumes_data(&field.field_type, consuming) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Rejects the types which can contain themselves without consuming any data,
+    /// e.g. `_ x:A = A;`, since they can't be decoded in a finite number of steps.
+    fn check_recursion(&self) -> ClientResult<()> {
+        let consuming = self.consuming_types();
+        let mut leading = HashMap::new();
+        for (name, constructors) in &self.types {
+            let mut types = Vec::new();
+            for constructor in constructors.iter().filter(|constructor| constructor.tag.bits == 0) {
+                Self::leading_types(&constructor.fields, &consuming, &mut types);
+            }
+            leading.insert(name.as_str(), types);
+        }
+        for (&start, types) in &leading {
+            let mut visited = HashSet::new();
+            let mut pending = types.clone();
+            while let Some(name) = pending.pop() {
+                if name == start {
+                    return Err(Error::invalid_tlb_schema(format!(
+                        "type [{}] can contain itself without consuming any data",
+                        start
+                    )));
+                }
+                if visited.insert(name) {
+                    if let Some(types) = leading.get(name) {
+                        pending.extend(types);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    Number(usize),
+    Tag(TlbTag),
+    Hash,
+    DoubleHash,
+    Colon,
+    Equals,
+    Semicolon,
+    Caret,
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+    OpenBrace,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn is_ident_char(c: char) -> bool {
+        c.is_ascii_alphanumeric() || c == '_'
+    }
+
+    fn tokenize(source: &str) -> ClientResult<Self> {
+        let chars: Vec<char> = source.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        let take_while = |i: &mut usize, f: &dyn Fn(char) -> bool| -> String {
+            let start = *i;
+            while *i < chars.len() && f(chars[*i]) {
+                *i += 1;
+            }
+            chars[start..*i].iter().collect()
+        };
+        while i < chars.len() {
+            let c = chars[i];
+            if c.is_whitespace() {
+                i += 1;
+                continue;
+            }
+            if c == '/' && chars.get(i + 1) == Some(&'/') {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            if c == '/' && chars.get(i + 1) == Some(&'*') {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            i += 1;
+            let token = match c {
+                ':' => Token::Colon,
+                '=' => Token::Equals,
+                ';' => Token::Semicolon,
+                '^' => Token::Caret,
+                '(' => Token::Open,
+                ')' => Token::Close,
+                '[' => Token::OpenBracket,
+                ']' => Token::CloseBracket,
+                '{' => Token::OpenBrace,
+                '#' if chars.get(i) == Some(&'#') => {
+                    i += 1;
+                    Token::DoubleHash
+                }
+                '#' if chars.get(i).map(|c| c.is_ascii_hexdigit() || *c == '_') == Some(true) => {
+                    Token::Tag(TlbTag::from_hex(&take_while(&mut i, &|c: char| {
+                        c.is_ascii_hexdigit() || c == '_'
+                    })))
+                }
+                '#' => Token::Hash,
+                '$' => Token::Tag(TlbTag::from_binary(
+                    &take_while(&mut i, &|c: char| c == '0' || c == '1' || c == '_')
+                        .replace('_', ""),
+                )),
+                digit if digit.is_ascii_digit() => {
+                    let number = format!("{}{}", digit, take_while(&mut i, &|c: char| c.is_ascii_digit()));
+                    Token::Number(number.parse().map_err(|err| {
+                        Error::invalid_tlb_schema(format!("invalid number {}: {}", number, err))
+                    })?)
+                }
+                first if Self::is_ident_char(first) => Token::Identifier(format!(
+                    "{}{}",
+                    first,
+                    take_while(&mut i, &|c: char| Self::is_ident_char(c))
+                )),
+                _ => {
+                    return Err(Error::invalid_tlb_schema(format!(
+                        "invalid character `{}` at position {}",
+                        c,
+                        i - 1
+                    )))
+                }
+            };
+            tokens.push(token);
+        }
+        Ok(Self { tokens, pos: 0 })
+    }
+
+    fn eof(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn parse_error(&self, msg: &str) -> ClientError {
+        Error::invalid_tlb_schema(format!("{} at token #{}", msg, self.pos))
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn pass(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: &Token, msg: &str) -> ClientResult<()> {
+        if self.pass(expected) {
+            Ok(())
+        } else {
+            Err(self.parse_error(msg))
+        }
+    }
+
+    fn next_identifier(&mut self) -> Option<String> {
+        if let Some(Token::Identifier(ident)) = self.peek() {
+            let ident = ident.clone();
+            self.pos += 1;
+            Some(ident)
+        } else {
+            None
+        }
+    }
+
+    fn next_number(&mut self) -> ClientResult<usize> {
+        if let Some(Token::Number(number)) = self.peek() {
+            let number = *number;
+            self.pos += 1;
+            Ok(number)
+        } else {
+            Err(self.parse_error("number expected"))
+        }
+    }
+
+    fn parse_declaration(&mut self) -> ClientResult<(String, TlbConstructor)> {
+        let name = self
+            .next_identifier()
+            .ok_or_else(|| self.parse_error("constructor name expected"))?;
+        let tag = if let Some(Token::Tag(tag)) = self.peek() {
+            let tag = tag.clone();
+            self.pos += 1;
+            tag
+        } else {
+            TlbTag::default()
+        };
+        let fields = self.parse_fields(&Token::Equals)?;
+        self.expect(&Token::Equals, "`=` expected")?;
+        let type_name = self
+            .next_identifier()
+            .ok_or_else(|| self.parse_error("type name expected"))?;
+        if !self.pass(&Token::Semicolon) {
+            return Err(self.parse_error(
+                "`;` expected (parametrized types are not supported)",
+            ));
+        }
+        Ok((type_name, TlbConstructor { name, tag, fields }))
+    }
+
+    fn parse_fields(&mut self, terminator: &Token) -> ClientResult<Vec<TlbField>> {
+        let mut fields = Vec::new();
+        while self.peek() != Some(terminator) {
+            if self.eof() {
+                return Err(self.parse_error("unexpected end of schema"));
+            }
+            if self.peek() == Some(&Token::OpenBrace) {
+                return Err(self.parse_error("implicit fields and constraints are not supported"));
+            }
+            let name = if let (Some(Token::Identifier(name)), Some(Token::Colon)) =
+                (self.tokens.get(self.pos), self.tokens.get(self.pos + 1))
+            {
+                let name = name.clone();
+                self.pos += 2;
+                if name == "_" { fields.len().to_string() } else { name }
+            } else {
+                fields.len().to_string()
+            };
+            let field_type = self.parse_term()?;
+            fields.push(TlbField { name, field_type });
+        }
+        Ok(fields)
+    }
+
+    fn parse_size(&self, type_name: &str, prefix: &str, max: usize) -> ClientResult<Option<usize>> {
+        if !type_name.starts_with(prefix) {
+            return Ok(None);
+        }
+        let size = &type_name[prefix.len()..];
+        if size.is_empty() || !size.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(None);
+        }
+        match size.parse::<usize>() {
+            Ok(size) if size > 0 && size <= max => Ok(Some(size)),
+            _ => Err(self.parse_error(&format!(
+                "invalid size of type [{}]: must be in range 1..={}", type_name, max
+            ))),
+        }
+    }
+
+    fn check_size(&self, size: usize, max: usize) -> ClientResult<usize> {
+        if size > 0 && size <= max {
+            Ok(size)
+        } else {
+            Err(self.parse_error(&format!("size must be in range 1..={}", max)))
+        }
+    }
+
+    /// Parses a single type term: `^X`, `(X args)`, `[fields]` or a type name.
+    fn parse_term(&mut self) -> ClientResult<TlbType> {
+        if self.pass(&Token::Caret) {
+            return Ok(TlbType::Ref(Box::new(self.parse_term()?)));
+        }
+        if self.pass(&Token::Open) {
+            let result = self.parse_application()?;
+            self.expect(&Token::Close, "`)` expected")?;
+            return Ok(result);
+        }
+        if self.pass(&Token::OpenBracket) {
+            let fields = self.parse_fields(&Token::CloseBracket)?;
+            self.expect(&Token::CloseBracket, "`]` expected")?;
+            return Ok(TlbType::Anonymous(fields));
+        }
+        if self.pass(&Token::Hash) {
+            return Ok(TlbType::UInt(32));
+        }
+        let name = self
+            .next_identifier()
+            .ok_or_else(|| self.parse_error("type expected"))?;
+        if let Some(size) = self.parse_size(&name, "uint", MAX_INT_SIZE)? {
+            return Ok(TlbType::UInt(size));
+        }
+        if let Some(size) = self.parse_size(&name, "int", MAX_INT_SIZE)? {
+            return Ok(TlbType::Int(size));
+        }
+        if let Some(size) = self.parse_size(&name, "bits", MAX_BITS_SIZE)? {
+            return Ok(TlbType::Bits(size));
+        }
+        Ok(match name.as_str() {
+            "Bool" => TlbType::Bool,
+            "Bit" => TlbType::UInt(1),
+            "Grams" | "Coins" => TlbType::VarUInt(16),
+            "MsgAddress" | "MsgAddressInt" | "MsgAddressExt" => TlbType::Address,
+            "Cell" | "Any" => TlbType::Any,
+            "uint" | "int" | "bits" | "Maybe" | "Either" | "HashmapE" | "VarUInteger"
+            | "VarInteger" => {
+                return Err(self.parse_error(&format!(
+                    "type [{}] requires arguments and must be enclosed in parenthesis",
+                    name
+                )))
+            }
+            _ => TlbType::Named(name),
+        })
+    }
+
+    /// Parses type application inside parenthesis: `Maybe X`, `HashmapE n X`, `## n` etc.
+    fn parse_application(&mut self) -> ClientResult<TlbType> {
+        if self.pass(&Token::DoubleHash) {
+            let size = self.next_number()?;
+            return Ok(TlbType::UInt(self.check_size(size, MAX_INT_SIZE)?));
+        }
+        if let Some(Token::Identifier(name)) = self.peek() {
+            let name = name.clone();
+            let known = match name.as_str() {
+                "uint" | "int" | "bits" | "Maybe" | "Either" | "HashmapE" | "VarUInteger"
+                | "VarInteger" => true,
+                _ => false,
+            };
+            if known {
+                self.pos += 1;
+                return Ok(match name.as_str() {
+                    "uint" => {
+                        let size = self.next_number()?;
+                        TlbType::UInt(self.check_size(size, MAX_INT_SIZE)?)
+                    }
+                    "int" => {
+                        let size = self.next_number()?;
+                        TlbType::Int(self.check_size(size, MAX_INT_SIZE)?)
+                    }
+                    "bits" => {
+                        let size = self.next_number()?;
+                        TlbType::Bits(self.check_size(size, MAX_BITS_SIZE)?)
+                    }
+                    "VarUInteger" => {
+                        let size = self.next_number()?;
+                        TlbType::VarUInt(self.check_size(size, 32)?)
+                    }
+                    "VarInteger" => {
+                        let size = self.next_number()?;
+                        TlbType::VarInt(self.check_size(size, 32)?)
+                    }
+                    "Maybe" => TlbType::Maybe(Box::new(self.parse_term()?)),
+                    "Either" => {
+                        let left = self.parse_term()?;
+                        let right = self.parse_term()?;
+                        TlbType::Either(Box::new(left), Box::new(right))
+                    }
+                    _ => {
+                        let size = self.next_number()?;
+                        let size = self.check_size(size, MAX_BITS_SIZE)?;
+                        TlbType::HashmapE(size, Box::new(self.parse_term()?))
+                    }
+                });
+            }
+        }
+        self.parse_term()
+    }
+}
@@ -14,16 +14,23 @@
     limitations under the License.
 */
 
+use crate::abi::Abi;
 use crate::boc::internal::{
     deserialize_cell_from_boc, deserialize_object_from_boc, serialize_cell_to_base64,
-    serialize_object_to_cell,
+    serialize_cell_to_boc, serialize_object_to_cell,
 };
+use crate::boc::state_init::get_compiler_version_from_cell;
+use crate::boc::{BocCacheType, Error};
 use crate::error::ClientResult;
 use crate::ClientContext;
 use ever_struct::scheme::TVC;
-use ever_block::{StateInit, Deserializable};
+use ever_block::{BuilderData, IBitstring, SliceData, StateInit, Deserializable};
 use ever_block::Cell;
 
+/// TVC v2 tag. TVC v1 uses `0xa2f0b81c`.
+const TVC_V2_TAG: u32 = 0xa2f0b81d;
+const TEXT_CELL_BYTES: usize = 127;
+
 #[derive(Serialize, Deserialize, ApiType, Default)]
 pub struct ParamsOfDecodeTvc {
     /// Contract TVC BOC encoded as base64 or BOC handle
@@ -40,6 +47,7 @@ pub struct ResultOfDecodeTvc {
 #[serde(tag = "type", content = "value")]
 pub enum Tvc {
     V1(TvcV1),
+    V2(TvcV2),
 }
 
 #[derive(Serialize, ApiType, Eq, PartialEq, Debug)]
@@ -48,14 +56,179 @@ pub struct TvcV1 {
     pub description: Option<String>,
 }
 
+#[derive(Serialize, ApiType, Eq, PartialEq, Debug)]
+pub struct TvcV2 {
+    /// Contract code BOC encoded as base64
+    pub code: String,
+    /// Initial contract data BOC encoded as base64
+    pub data: Option<String>,
+    /// Contract ABI JSON
+    pub abi: Option<String>,
+    /// Compiler version
+    pub compiler_version: Option<String>,
+    /// Contract description
+    pub description: Option<String>,
+    /// Source metadata, e.g. the source file name, repository and commit
+    pub source_metadata: Option<String>,
+}
+
+/// Contract package stored in TVC v2.
+///
+/// ```text
+/// tvc_v2#a2f0b81d code:^Cell data:(Maybe ^Cell) meta:^TvcMeta = TvcV2;
+/// tvc_meta$_ abi:(Maybe ^Text) compiler_version:(Maybe ^Text)
+///     description:(Maybe ^Text) source_metadata:(Maybe ^Text) = TvcMeta;
+/// ```
+/// Text is stored as a chain of cells with up to 127 bytes each,
+/// the next cell is referenced by the first reference.
+#[derive(Default)]
+pub(crate) struct TvcV2Cells {
+    pub code: Cell,
+    pub data: Option<Cell>,
+    pub abi: Option<String>,
+    pub compiler_version: Option<String>,
+    pub description: Option<String>,
+    pub source_metadata: Option<String>,
+}
+
+impl TvcV2Cells {
+    /// Reads TVC v2. Returns `None` if the cell is not a TVC v2.
+    pub fn read(cell: &Cell) -> ClientResult<Option<Self>> {
+        let mut slice = match SliceData::load_cell(cell.clone()) {
+            Ok(slice) => slice,
+            Err(_) => return Ok(None),
+        };
+        if slice.get_next_u32().ok() != Some(TVC_V2_TAG) {
+            return Ok(None);
+        }
+        let code = slice.checked_drain_reference().map_err(invalid_tvc)?;
+        let data = read_maybe_ref(&mut slice)?;
+        let mut meta = SliceData::load_cell(slice.checked_drain_reference().map_err(invalid_tvc)?)
+            .map_err(invalid_tvc)?;
+        let mut read_text = || -> ClientResult<Option<String>> {
+            read_maybe_ref(&mut meta)?.map(text_from_cell).transpose()
+        };
+        Ok(Some(Self {
+            code,
+            data,
+            abi: read_text()?,
+            compiler_version: read_text()?,
+            description: read_text()?,
+            source_metadata: read_text()?,
+        }))
+    }
+
+    pub fn write(&self) -> ClientResult<Cell> {
+        let mut meta = BuilderData::new();
+        for text in [
+            &self.abi,
+            &self.compiler_version,
+            &self.description,
+            &self.source_metadata,
+        ] {
+            write_maybe_ref(&mut meta, text.as_deref().map(text_to_cell).transpose()?)?;
+        }
+
+        let mut builder = BuilderData::new();
+        builder.append_u32(TVC_V2_TAG).map_err(serialization_error)?;
+        builder
+            .checked_append_reference(self.code.clone())
+            .map_err(serialization_error)?;
+        write_maybe_ref(&mut builder, self.data.clone())?;
+        builder
+            .checked_append_reference(meta.into_cell().map_err(serialization_error)?)
+            .map_err(serialization_error)?;
+        builder.into_cell().map_err(serialization_error)
+    }
+
+    pub fn state_init(&self) -> StateInit {
+        let mut state_init = StateInit::default();
+        state_init.set_code(self.code.clone());
+        if let Some(data) = &self.data {
+            state_init.set_data(data.clone());
+        }
+        state_init
+    }
+}
+
+fn invalid_tvc(err: impl std::fmt::Display) -> crate::error::ClientError {
+    Error::invalid_boc(format!("invalid TVC v2: {}", err))
+}
+
+fn serialization_error(err: impl std::fmt::Display) -> crate::error::ClientError {
+    Error::serialization_error(err, "TVC")
+}
+
+fn read_maybe_ref(slice: &mut SliceData) -> ClientResult<Option<Cell>> {
+    if slice.get_next_bit().map_err(invalid_tvc)? {
+        Ok(Some(slice.checked_drain_reference().map_err(invalid_tvc)?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn write_maybe_ref(builder: &mut BuilderData, cell: Option<Cell>) -> ClientResult<()> {
+    builder.append_bit_bool(cell.is_some()).map_err(serialization_error)?;
+    if let Some(cell) = cell {
+        builder.checked_append_reference(cell).map_err(serialization_error)?;
+    }
+    Ok(())
+}
+
+fn text_to_cell(text: &str) -> ClientResult<Cell> {
+    let mut next: Option<Cell> = None;
+    for chunk in text.as_bytes().chunks(TEXT_CELL_BYTES).rev() {
+        let mut builder =
+            BuilderData::with_raw(chunk.to_vec(), chunk.len() * 8).map_err(serialization_error)?;
+        if let Some(next) = next {
+            builder.checked_append_reference(next).map_err(serialization_error)?;
+        }
+        next = Some(builder.into_cell().map_err(serialization_error)?);
+    }
+    Ok(next.unwrap_or_default())
+}
+
+fn text_from_cell(cell: Cell) -> ClientResult<String> {
+    let mut bytes = Vec::new();
+    let mut next = Some(cell);
+    while let Some(cell) = next {
+        if cell.bit_length() % 8 != 0 {
+            return Err(invalid_tvc("text cell is not aligned to bytes"));
+        }
+        bytes.extend_from_slice(&cell.data()[..cell.bit_length() / 8]);
+        next = cell.reference(0).ok();
+    }
+    String::from_utf8(bytes).map_err(invalid_tvc)
+}
+
 /// Decodes tvc according to the tvc spec.
 /// Read more about tvc structure here https://github.com/everx-labs/ever-struct/blob/main/src/scheme/mod.rs#L30
+///
+/// TVC v2 additionally contains the initial data, ABI, compiler version
+/// and source metadata, see `boc.encode_tvc`.
 
 #[api_function]
 pub fn decode_tvc(
     context: std::sync::Arc<ClientContext>,
     params: ParamsOfDecodeTvc,
 ) -> ClientResult<ResultOfDecodeTvc> {
+    let (_, cell) = deserialize_cell_from_boc(&context, &params.tvc, "TVC")?;
+    if let Some(tvc) = TvcV2Cells::read(&cell)? {
+        return Ok(ResultOfDecodeTvc {
+            tvc: Tvc::V2(TvcV2 {
+                code: serialize_cell_to_base64(&tvc.code, "TVC code")?,
+                data: tvc
+                    .data
+                    .map(|x| serialize_cell_to_base64(&x, "TVC data"))
+                    .transpose()?,
+                abi: tvc.abi,
+                compiler_version: tvc.compiler_version,
+                description: tvc.description,
+                source_metadata: tvc.source_metadata,
+            }),
+        });
+    }
+
     let tvc = deserialize_object_from_boc::<TVC>(&context, &params.tvc, "TVC")?.object;
     let tvc = Tvc::V1(TvcV1 {
         code: tvc
@@ -67,6 +240,65 @@ pub fn decode_tvc(
     Ok(ResultOfDecodeTvc { tvc })
 }
 
+#[derive(Serialize, Deserialize, ApiType, Default)]
+pub struct ParamsOfEncodeTvc {
+    /// Contract code BOC encoded as base64 or BOC handle
+    pub code: String,
+    /// Initial contract data BOC encoded as base64 or BOC handle
+    pub data: Option<String>,
+    /// Contract ABI
+    pub abi: Option<Abi>,
+    /// Compiler version.
+    ///
+    /// If not specified then the version stored in the code is used, if any.
+    pub compiler_version: Option<String>,
+    /// Contract description
+    pub description: Option<String>,
+    /// Source metadata, e.g. the source file name, repository and commit
+    pub source_metadata: Option<String>,
+    /// Cache type to put the result. The BOC itself returned if no cache type provided.
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default)]
+pub struct ResultOfEncodeTvc {
+    /// Contract TVC BOC encoded as base64 or BOC handle
+    pub tvc: String,
+}
+
+/// Encodes TVC v2 contract package.
+///
+/// The package bundles the code, the initial data, the ABI, the compiler version
+/// and the source metadata, so the contract can be distributed as a single file.
+/// It can be used as `tvc` in `DeploySet` directly.
+#[api_function]
+pub fn encode_tvc(
+    context: std::sync::Arc<ClientContext>,
+    params: ParamsOfEncodeTvc,
+) -> ClientResult<ResultOfEncodeTvc> {
+    let (_, code) = deserialize_cell_from_boc(&context, &params.code, "code")?;
+    let data = params
+        .data
+        .as_ref()
+        .map(|data| deserialize_cell_from_boc(&context, data, "data").map(|(_, cell)| cell))
+        .transpose()?;
+    let compiler_version = match params.compiler_version {
+        Some(version) => Some(version),
+        None => get_compiler_version_from_cell(code.clone()).ok().flatten(),
+    };
+    let tvc = TvcV2Cells {
+        code,
+        data,
+        abi: params.abi.as_ref().map(|abi| abi.json_string()).transpose()?,
+        compiler_version,
+        description: params.description,
+        source_metadata: params.source_metadata,
+    };
+    Ok(ResultOfEncodeTvc {
+        tvc: serialize_cell_to_boc(&context, tvc.write()?, "TVC", params.boc_cache)?,
+    })
+}
+
 pub(crate) fn state_init_with_code(code: Cell) -> ClientResult<Cell> {
     let mut state_init = StateInit::default();
     state_init.set_code(code);
@@ -78,7 +310,9 @@ pub(crate) fn resolve_state_init_cell(
     tvc_or_state_init: &str,
 ) -> ClientResult<Cell> {
     let cell = deserialize_cell_from_boc(context, tvc_or_state_init, "state init or TVC")?.1;
-    if let Ok(tvc) = TVC::construct_from_cell(cell.clone()) {
+    if let Some(tvc) = TvcV2Cells::read(&cell)? {
+        serialize_object_to_cell(&tvc.state_init(), "state init")
+    } else if let Ok(tvc) = TVC::construct_from_cell(cell.clone()) {
         if let Some(code) = tvc.code {
             state_init_with_code(code)
         } else {
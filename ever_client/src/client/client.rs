@@ -25,13 +25,14 @@ use super::std_client_env::ClientEnv;
 use super::wasm_client_env::ClientEnv;
 use super::{AppRequestResult, Error, ParamsOfAppRequest};
 use crate::abi::AbiConfig;
-use crate::boc::{cache::Bocs, BocConfig};
+use crate::boc::{cache::Bocs, libraries::MissingLibraries, BocConfig};
 use crate::client::storage::KeyValueStorage;
 use crate::client::{update_binding_config, BindingConfig};
 use crate::crypto::boxes::crypto_box::{CryptoBox, DerivedKeys};
 use crate::crypto::boxes::{encryption_box::EncryptionBox, signing_box::SigningBox};
 use crate::crypto::CryptoConfig;
 use crate::debot::DEngine;
+use crate::tvm::emulator::Emulator;
 use crate::error::ClientResult;
 use crate::json_interface::interop::ResponseType;
 use crate::json_interface::request::Request;
@@ -69,6 +70,7 @@ pub struct ClientContext {
 
     // boc module
     pub(crate) bocs: Arc<Bocs>,
+    pub(crate) missing_libraries: MissingLibraries,
 
     // net module
     pub(crate) net: Arc<NetworkContext>,
@@ -79,6 +81,9 @@ pub struct ClientContext {
     // proofs module
     pub(crate) proofs_storage: RwLock<Option<Arc<dyn KeyValueStorage>>>,
 
+    // tvm module
+    pub(crate) emulators: LockfreeMap<u32, Mutex<Emulator>>,
+
     // debot module
     pub(crate) debots: LockfreeMap<u32, Mutex<DEngine>>,
 }
@@ -113,7 +118,7 @@ impl ClientContext {
                 None
             };
 
-        let bocs = Arc::new(Bocs::new(config.boc.cache_max_size));
+        let bocs = Arc::new(Bocs::new(&config.boc, config.local_storage_path.clone()));
         let net = Arc::new(NetworkContext {
             env: env.clone(),
             server_link,
@@ -130,9 +135,11 @@ impl ClientContext {
             message_monitor,
             config,
             env: env.clone(),
+            emulators: LockfreeMap::new(),
             debots: LockfreeMap::new(),
             boxes: Default::default(),
             bocs,
+            missing_libraries: Default::default(),
             network_params: RwLock::new(None),
             app_requests: Mutex::new(HashMap::new()),
             proofs_storage: Default::default(),
@@ -126,6 +126,18 @@ impl ClientEnv {
         self.async_runtime_handle.spawn(future);
     }
 
+    /// Executes synchronous CPU-bound task on the blocking threads pool,
+    /// so it doesn't hold the asynchronous tasks workers
+    pub async fn spawn_blocking<R: Send + 'static>(
+        &self,
+        task: impl FnOnce() -> R + Send + 'static,
+    ) -> ClientResult<R> {
+        self.async_runtime_handle
+            .spawn_blocking(task)
+            .await
+            .map_err(|err| Error::can_not_receive_spawned_result(err))
+    }
+
     /// Executes asynchronous task blocking current thread
     pub fn block_on<F: Future>(&self, future: F) -> F::Output {
         self.async_runtime_handle.block_on(future)
@@ -270,7 +282,10 @@ impl LocalStorage {
         })
     }
 
-    fn calc_storage_path(local_storage_path: &Option<String>, storage_name: &str) -> PathBuf {
+    pub(crate) fn calc_storage_path(
+        local_storage_path: &Option<String>,
+        storage_name: &str,
+    ) -> PathBuf {
         let local_storage_path = local_storage_path
             .clone()
             .map(|path| PathBuf::from(path))
@@ -351,6 +351,15 @@ impl ClientEnv {
         wasm_bindgen_futures::spawn_local(future);
     }
 
+    /// Executes synchronous CPU-bound task. There are no threads in wasm,
+    /// so the task is executed in place
+    pub async fn spawn_blocking<R: 'static>(
+        &self,
+        task: impl FnOnce() -> R + 'static,
+    ) -> ClientResult<R> {
+        Ok(task())
+    }
+
     /// Connects to the websocket endpoint
     pub async fn websocket_connect(
         &self,
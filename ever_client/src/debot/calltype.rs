@@ -2,7 +2,7 @@ use super::errors::Error;
 use super::helpers::build_internal_message;
 use super::{BrowserCallbacks, DebotActivity, Spending, TonClient};
 use crate::abi::Signer;
-use crate::boc::internal::{deserialize_object_from_base64, serialize_object_to_base64};
+use crate::boc::internal::{deserialize_object_from_boc, serialize_object_to_base64};
 use crate::boc::{get_boc_hash, parse_message, ParamsOfGetBocHash, ParamsOfParse};
 use crate::crypto::SigningBoxHandle;
 use crate::encoding::decode_abi_number;
@@ -272,7 +272,7 @@ impl ContractCall {
         debot_addr: String,
         local_run: bool,
     ) -> ClientResult<Self> {
-        let mut msg: Message = deserialize_object_from_base64(&msg, "message")
+        let mut msg: Message = deserialize_object_from_boc(&ton, &msg, "message")
             .map_err(msg_err)?
             .object;
         let meta = get_meta(&mut msg)?;
@@ -350,6 +350,7 @@ impl ContractCall {
         }
         let out_msg = messages.pop().unwrap();
         build_answer_msg(
+            &self.ton,
             &out_msg,
             self.meta.answer_id,
             func_id,
@@ -451,6 +452,7 @@ impl ContractCall {
                     }
                     for out_msg in &res.out_messages {
                         let res = build_answer_msg(
+                            &self.ton,
                             out_msg,
                             self.meta.answer_id,
                             func_id,
@@ -534,13 +536,14 @@ fn build_onerror_body(onerror_id: u32, e: ClientError) -> ClientResult<SliceData
 }
 
 fn build_answer_msg(
+    ton: &TonClient,
     out_msg: &String,
     answer_id: u32,
     func_id: u32,
     dest_addr: &String,
     debot_addr: &String,
 ) -> Option<String> {
-    let out_message: Message = deserialize_object_from_base64(out_msg, "message")
+    let out_message: Message = deserialize_object_from_boc(ton, out_msg, "message")
         .ok()?
         .object;
     if out_message.is_internal() {
@@ -18,7 +18,7 @@ use crate::abi::{
     decode_message_body, encode_message, encode_message_body, Abi, CallSet, DeploySet, ErrorCode,
     ParamsOfDecodeMessageBody, ParamsOfEncodeMessage, ParamsOfEncodeMessageBody, Signer,
 };
-use crate::boc::internal::deserialize_cell_from_base64;
+use crate::boc::internal::deserialize_cell_from_boc;
 use crate::crypto::{remove_signing_box, RegisteredSigningBox, SigningBoxHandle};
 use crate::encoding::{decode_abi_number, slice_from_cell};
 use crate::error::{ClientError, ClientResult};
@@ -290,7 +290,7 @@ impl DEngine {
         let body = encode_message_body(self.ton.clone(), msg_params)
             .await?
             .body;
-        let (_, body_cell) = deserialize_cell_from_base64(&body, "message body")?;
+        let (_, body_cell) = deserialize_cell_from_boc(&self.ton, &body, "message body")?;
         let body = slice_from_cell(body_cell)?;
         let msg_base64 = build_internal_message(&source, &self.addr, body)?;
         self.send_to_debot(msg_base64).await
@@ -309,6 +309,7 @@ impl DEngine {
         )
         .await?;
         let mut run_output = RunOutput::new(
+            &self.ton,
             run_result.account,
             self.addr.clone(),
             run_result.decoded.and_then(|x| x.output),
@@ -761,6 +762,7 @@ impl DEngine {
 
         match result {
             Ok(res) => RunOutput::new(
+                &ton,
                 res.account,
                 addr,
                 res.decoded.and_then(|x| x.output),
@@ -1,7 +1,8 @@
 use super::action::DAction;
 use super::calltype::DebotCallType;
 use super::{JsonValue, DEBOT_WC};
-use crate::boc::internal::{deserialize_object_from_base64, serialize_object_to_base64};
+use crate::boc::internal::{deserialize_object_from_boc, serialize_object_to_base64};
+use crate::client::ClientContext;
 use crate::encoding::account_decode;
 use crate::error::ClientError;
 use std::collections::VecDeque;
@@ -18,6 +19,7 @@ pub(super) struct RunOutput {
 
 impl RunOutput {
     pub fn new(
+        context: &ClientContext,
         account: String,
         debot_addr: String,
         return_value: Option<JsonValue>,
@@ -28,7 +30,7 @@ impl RunOutput {
         output.return_value = return_value;
         output.std_addr = Some(account_decode(&debot_addr)?);
         while let Some(msg_base64) = msgs.pop() {
-            let msg: Message = deserialize_object_from_base64(&msg_base64, "message")?.object;
+            let msg: Message = deserialize_object_from_boc(context, &msg_base64, "message")?.object;
             output.filter_msg(msg, msg_base64);
         }
 
@@ -32,6 +32,7 @@ fn register_client(handlers: &mut RuntimeHandlers) {
     module.register_type::<crate::crypto::CryptoConfig>();
     module.register_type::<crate::abi::AbiConfig>();
     module.register_type::<crate::boc::BocConfig>();
+    module.register_type::<crate::boc::ExoticCellType>();
     module.register_type::<crate::proofs::ProofsConfig>();
     module.register_type::<crate::client::BuildInfoDependency>();
     module.register_type::<crate::client::ParamsOfAppRequest>();
@@ -320,6 +321,7 @@ fn register_abi(handlers: &mut RuntimeHandlers) {
     module.register_type::<crate::abi::AbiFunction>();
     module.register_type::<crate::abi::AbiContract>();
     module.register_type::<crate::abi::DataLayout>();
+    module.register_type::<crate::abi::DescribedParam>();
 
     module.register_async_fn(
         crate::abi::encode_message_body,
@@ -385,6 +387,14 @@ fn register_abi(handlers: &mut RuntimeHandlers) {
         crate::abi::get_signature_data,
         crate::abi::decode_message::get_signature_data_api,
     );
+    module.register_sync_fn(
+        crate::abi::describe_message,
+        crate::abi::describe_message::describe_message_api,
+    );
+    module.register_async_fn(
+        crate::abi::call_getter,
+        crate::abi::call_getter::call_getter_api,
+    );
     module.register();
 }
 
@@ -399,9 +409,11 @@ fn register_boc(handlers: &mut RuntimeHandlers) {
     module.register_type::<BuilderOp>();
     module.register_type::<crate::boc::Tvc>();
     module.register_type::<crate::boc::TvcV1>();
+    module.register_type::<crate::boc::TvcV2>();
 
     module.register_error_code::<crate::boc::ErrorCode>();
     module.register_sync_fn(crate::boc::decode_tvc, crate::boc::tvc::decode_tvc_api);
+    module.register_sync_fn(crate::boc::encode_tvc, crate::boc::tvc::encode_tvc_api);
     module.register_sync_fn(
         crate::boc::parse_message,
         crate::boc::parse::parse_message_api,
@@ -438,6 +450,8 @@ fn register_boc(handlers: &mut RuntimeHandlers) {
     module.register_sync_fn(crate::boc::cache_get, crate::boc::cache::cache_get_api);
     module.register_sync_fn(crate::boc::cache_set, crate::boc::cache::cache_set_api);
     module.register_sync_fn(crate::boc::cache_unpin, crate::boc::cache::cache_unpin_api);
+    module.register_async_fn(crate::boc::cache_load, crate::boc::cache::cache_load_api);
+    module.register_async_fn_no_args(crate::boc::cache_stats, crate::boc::cache::cache_stats_api);
     module.register_sync_fn(crate::boc::encode_boc, crate::boc::encode::encode_boc_api);
     module.register_sync_fn(
         crate::boc::get_code_salt,
@@ -463,6 +477,60 @@ fn register_boc(handlers: &mut RuntimeHandlers) {
         crate::boc::get_compiler_version,
         crate::boc::state_init::get_compiler_version_api,
     );
+    module.register_sync_fn(crate::boc::query_cell, crate::boc::reader::query_cell_api);
+    module.register_sync_fn(crate::boc::parse_tlb, crate::boc::tlb::parse_tlb_api);
+    module.register_sync_fn(crate::boc::encode_tlb, crate::boc::tlb::encode_tlb_api);
+    module.register_type::<crate::boc::MerkleProofDictEntries>();
+    module.register_sync_fn(
+        crate::boc::create_merkle_proof,
+        crate::boc::merkle::create_merkle_proof_api,
+    );
+    module.register_sync_fn(
+        crate::boc::verify_merkle_proof,
+        crate::boc::merkle::verify_merkle_proof_api,
+    );
+    module.register_sync_fn(
+        crate::boc::create_merkle_update,
+        crate::boc::merkle::create_merkle_update_api,
+    );
+    module.register_sync_fn(
+        crate::boc::apply_merkle_update,
+        crate::boc::merkle::apply_merkle_update_api,
+    );
+    module.register_type::<crate::boc::DictValueType>();
+    module.register_type::<crate::boc::DictEntry>();
+    module.register_sync_fn(crate::boc::dict_get, crate::boc::dict::dict_get_api);
+    module.register_sync_fn(crate::boc::dict_set, crate::boc::dict::dict_set_api);
+    module.register_sync_fn(crate::boc::dict_remove, crate::boc::dict::dict_remove_api);
+    module.register_sync_fn(crate::boc::dict_iterate, crate::boc::dict::dict_iterate_api);
+    module.register_type::<crate::boc::CellDiff>();
+    module.register_type::<crate::boc::CellDiffKind>();
+    module.register_type::<crate::boc::FieldDiff>();
+    module.register_sync_fn(crate::boc::diff, crate::boc::diff::diff_api);
+    module.register_type::<crate::boc::BocHeader>();
+    module.register_type::<crate::boc::ExoticCellsCount>();
+    module.register_sync_fn(crate::boc::inspect, crate::boc::inspect::inspect_api);
+    module.register_type::<crate::boc::BocFormat>();
+    module.register_sync_fn(crate::boc::reserialize, crate::boc::reserialize::reserialize_api);
+    module.register_async_fn(
+        crate::boc::resolve_libraries,
+        crate::boc::libraries::resolve_libraries_api,
+    );
+    module.register_sync_fn(
+        crate::boc::get_shardstate_account,
+        crate::boc::explorer::get_shardstate_account_api,
+    );
+    module.register_type::<crate::boc::ShardstateAccount>();
+    module.register_sync_fn(
+        crate::boc::list_shardstate_accounts,
+        crate::boc::explorer::list_shardstate_accounts_api,
+    );
+    module.register_type::<crate::boc::BlockMessageType>();
+    module.register_type::<crate::boc::BlockMessageDescr>();
+    module.register_sync_fn(
+        crate::boc::get_block_messages,
+        crate::boc::explorer::get_block_messages_api,
+    );
     module.register();
 }
 
@@ -482,6 +550,7 @@ fn register_net(handlers: &mut RuntimeHandlers) {
     module.register_type::<crate::net::AggregationFn>();
     module.register_type::<crate::net::TransactionNode>();
     module.register_type::<crate::net::MessageNode>();
+    module.register_type::<crate::net::DecodedEvent>();
 
     module.register_async_fn(crate::net::query, crate::net::queries::query_api);
     module.register_async_fn(crate::net::batch_query, crate::net::batch::batch_query_api);
@@ -523,6 +592,10 @@ fn register_net(handlers: &mut RuntimeHandlers) {
         crate::net::transaction_tree::query_transaction_tree,
         crate::net::transaction_tree::query_transaction_tree_api,
     );
+    module.register_async_fn(
+        crate::net::events::query_events,
+        crate::net::events::query_events_api,
+    );
 
     module.register_async_fn(
         crate::net::iterators::block_iterator::create_block_iterator,
@@ -626,14 +699,71 @@ fn register_tvm(handlers: &mut RuntimeHandlers) {
     module.register_error_code::<crate::tvm::ErrorCode>();
 
     module.register_type::<crate::tvm::types::ExecutionOptions>();
+    module.register_type::<crate::tvm::ExecutionBehaviorModifiers>();
+    module.register_type::<crate::tvm::ConfigOverrides>();
+    module.register_type::<crate::tvm::GasPricesOverrides>();
+    module.register_type::<crate::tvm::StoragePricesOverrides>();
     module.register_type::<crate::tvm::AccountForExecutor>();
     module.register_type::<crate::tvm::TransactionFees>();
+    module.register_type::<crate::tvm::TraceStep>();
+    module.register_type::<crate::tvm::CellGasUsage>();
+    module.register_type::<crate::tvm::FunctionGasUsage>();
+    module.register_type::<crate::tvm::GasProfile>();
+    module.register_type::<crate::tvm::TransactionFieldDiff>();
+    module.register_type::<crate::tvm::TypedStackItem>();
+    module.register_type::<crate::tvm::BatchAccount>();
+    module.register_type::<crate::tvm::RunGetBatchItem>();
     module.register_async_fn(
         crate::tvm::run_executor,
         crate::tvm::run_message::run_executor_api,
     );
     module.register_async_fn(crate::tvm::run_tvm, crate::tvm::run_message::run_tvm_api);
     module.register_async_fn(crate::tvm::run_get, crate::tvm::run_get::run_get_api);
+    module.register_async_fn(crate::tvm::run_get_raw, crate::tvm::run_get::run_get_raw_api);
+    module.register_async_fn(
+        crate::tvm::run_get_batch,
+        crate::tvm::run_get::run_get_batch_api,
+    );
+    module.register_async_fn(
+        crate::tvm::create_emulator,
+        crate::tvm::emulator::create_emulator_api,
+    );
+    module.register_sync_fn(
+        crate::tvm::remove_emulator,
+        crate::tvm::emulator::remove_emulator_api,
+    );
+    module.register_async_fn(
+        crate::tvm::emulator_deploy,
+        crate::tvm::emulator::emulator_deploy_api,
+    );
+    module.register_async_fn(
+        crate::tvm::emulator_send,
+        crate::tvm::emulator::emulator_send_api,
+    );
+    module.register_async_fn(
+        crate::tvm::emulator_set_account,
+        crate::tvm::emulator::emulator_set_account_api,
+    );
+    module.register_async_fn(
+        crate::tvm::emulator_get_account,
+        crate::tvm::emulator::emulator_get_account_api,
+    );
+    module.register_async_fn(
+        crate::tvm::emulator_query_transactions,
+        crate::tvm::emulator::emulator_query_transactions_api,
+    );
+    module.register_async_fn(
+        crate::tvm::emulator_advance_time,
+        crate::tvm::emulator::emulator_advance_time_api,
+    );
+    module.register_async_fn(
+        crate::tvm::run_executor_at,
+        crate::tvm::history::run_executor_at_api,
+    );
+    module.register_async_fn(
+        crate::tvm::replay_transaction,
+        crate::tvm::history::replay_transaction_api,
+    );
     module.register();
 }
 
@@ -658,6 +788,10 @@ fn register_utils(handlers: &mut RuntimeHandlers) {
         crate::utils::calc_storage_fee,
         crate::utils::calc_storage_fee::calc_storage_fee_api,
     );
+    module.register_async_fn(
+        crate::utils::forecast_storage_fee,
+        crate::utils::calc_storage_fee::forecast_storage_fee_api,
+    );
     #[cfg(feature = "include-zstd")]
     module.register_sync_fn(super::utils::compress_zstd, super::utils::compress_zstd_api);
     #[cfg(feature = "include-zstd")]
@@ -665,6 +799,21 @@ fn register_utils(handlers: &mut RuntimeHandlers) {
         super::utils::decompress_zstd,
         super::utils::decompress_zstd_api,
     );
+    #[cfg(feature = "include-zstd")]
+    module.register_sync_fn(
+        super::utils::train_zstd_dictionary,
+        super::utils::train_zstd_dictionary_api,
+    );
+    #[cfg(feature = "include-zstd")]
+    module.register_sync_fn(
+        super::utils::compress_zstd_batch,
+        super::utils::compress_zstd_batch_api,
+    );
+    #[cfg(feature = "include-zstd")]
+    module.register_sync_fn(
+        super::utils::decompress_zstd_batch,
+        super::utils::decompress_zstd_batch_api,
+    );
     module.register();
 }
 
@@ -24,6 +24,10 @@ pub struct ParamsOfCompressZstd {
     /// 21 - highest compression level (slowest compression).
     /// If level is omitted, the default compression level is used (currently `3`).
     pub level: Option<i32>,
+    /// Dictionary trained with `train_zstd_dictionary`. Must be encoded as base64.
+    ///
+    /// The same dictionary must be used to decompress the data.
+    pub dictionary: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, ApiType, Default, Debug)]
@@ -44,7 +48,15 @@ pub fn compress_zstd(
                 crate::utils::Error::compression_error(format!("Unable to decode BASE64: {}", err))
         )?;
 
-    let compressed = crate::utils::compression::compress_zstd(uncompressed.as_slice(), params.level)?;
+    let compressed = match params.dictionary {
+        Some(dictionary) => {
+            let dictionary = decode_dictionary(&dictionary)?;
+            crate::utils::compression::compress_zstd_batch(
+                &[uncompressed], params.level, Some(&dictionary)
+            )?.remove(0)
+        }
+        None => crate::utils::compression::compress_zstd(uncompressed.as_slice(), params.level)?,
+    };
 
     Ok(ResultOfCompressZstd {
         compressed: base64::encode(&compressed),
@@ -55,6 +67,10 @@ pub fn compress_zstd(
 pub struct ParamsOfDecompressZstd {
     /// Compressed data. Must be encoded as base64.
     pub compressed: String,
+    /// Dictionary used to compress the data. Must be encoded as base64.
+    ///
+    /// Data decompressed with the dictionary is limited to 16 MB per item.
+    pub dictionary: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, ApiType, Default, Debug)]
@@ -75,9 +91,147 @@ pub fn decompress_zstd(
                 crate::utils::Error::decompression_error(format!("Unable to decode BASE64: {}", err))
         )?;
 
-    let decompressed = crate::utils::compression::decompress_zstd(compressed.as_slice())?;
+    let dictionary = params.dictionary.as_deref().map(decode_dictionary).transpose()?;
+    let decompressed = crate::utils::compression::decompress_zstd_batch(
+        &[compressed], dictionary.as_deref()
+    )?.remove(0);
 
     Ok(ResultOfDecompressZstd {
         decompressed: base64::encode(&decompressed),
     })
 }
+
+fn decode_dictionary(dictionary: &str) -> ClientResult<Vec<u8>> {
+    base64::decode(dictionary)
+        .map_err(
+            |err|
+                crate::utils::Error::compression_error(
+                    format!("Unable to decode dictionary BASE64: {}", err)
+                )
+        )
+}
+
+fn decode_items(items: &[String], name: &str) -> ClientResult<Vec<Vec<u8>>> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| base64::decode(item)
+            .map_err(
+                |err|
+                    crate::utils::Error::compression_error(
+                        format!("Unable to decode BASE64 of {} #{}: {}", name, index, err)
+                    )
+            )
+        )
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug)]
+pub struct ParamsOfTrainZstdDictionary {
+    /// Samples of the data to be compressed, e.g. message or account BOCs.
+    /// Must be encoded as base64.
+    ///
+    /// Training requires a representative set of samples, usually hundreds or thousands.
+    pub samples: Vec<String>,
+    /// Maximum dictionary size in bytes. Default is 112640 (110 KB).
+    pub max_size: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug)]
+pub struct ResultOfTrainZstdDictionary {
+    /// Trained dictionary. Encoded as base64.
+    pub dictionary: String,
+}
+
+/// Trains Zstandard dictionary
+///
+/// Small data like message or account BOCs compress poorly on its own,
+/// so a dictionary trained on the similar data can be provided to
+/// `compress_zstd` and `compress_zstd_batch` to improve the compression ratio.
+#[api_function]
+pub fn train_zstd_dictionary(
+    _context: std::sync::Arc<ClientContext>,
+    params: ParamsOfTrainZstdDictionary,
+) -> ClientResult<ResultOfTrainZstdDictionary> {
+    let samples = decode_items(&params.samples, "sample")?;
+    let max_size = params.max_size
+        .map(|size| size as usize)
+        .unwrap_or(crate::utils::compression::DEFAULT_ZSTD_DICTIONARY_SIZE);
+
+    let dictionary = crate::utils::compression::train_zstd_dictionary(&samples, max_size)?;
+
+    Ok(ResultOfTrainZstdDictionary {
+        dictionary: base64::encode(&dictionary),
+    })
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug)]
+pub struct ParamsOfCompressZstdBatch {
+    /// Uncompressed items. Must be encoded as base64.
+    pub items: Vec<String>,
+    /// Compression level, from 1 to 21. See `compress_zstd`.
+    pub level: Option<i32>,
+    /// Dictionary trained with `train_zstd_dictionary`. Must be encoded as base64.
+    pub dictionary: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug)]
+pub struct ResultOfCompressZstdBatch {
+    /// Compressed items in the same order. Encoded as base64.
+    pub compressed: Vec<String>,
+}
+
+/// Compresses every item of the batch separately using Zstandard algorithm
+///
+/// Every compressed item can be decompressed on its own with `decompress_zstd`
+/// using the same dictionary.
+#[api_function]
+pub fn compress_zstd_batch(
+    _context: std::sync::Arc<ClientContext>,
+    params: ParamsOfCompressZstdBatch,
+) -> ClientResult<ResultOfCompressZstdBatch> {
+    let items = decode_items(&params.items, "item")?;
+    let dictionary = params.dictionary.as_deref().map(decode_dictionary).transpose()?;
+
+    let compressed = crate::utils::compression::compress_zstd_batch(
+        &items, params.level, dictionary.as_deref()
+    )?;
+
+    Ok(ResultOfCompressZstdBatch {
+        compressed: compressed.iter().map(base64::encode).collect(),
+    })
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug)]
+pub struct ParamsOfDecompressZstdBatch {
+    /// Compressed items. Must be encoded as base64.
+    pub items: Vec<String>,
+    /// Dictionary used to compress the items. Must be encoded as base64.
+    ///
+    /// Data decompressed with the dictionary is limited to 16 MB per item.
+    pub dictionary: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug)]
+pub struct ResultOfDecompressZstdBatch {
+    /// Decompressed items in the same order. Encoded as base64.
+    pub decompressed: Vec<String>,
+}
+
+/// Decompresses every item of the batch using Zstandard algorithm
+#[api_function]
+pub fn decompress_zstd_batch(
+    _context: std::sync::Arc<ClientContext>,
+    params: ParamsOfDecompressZstdBatch,
+) -> ClientResult<ResultOfDecompressZstdBatch> {
+    let items = decode_items(&params.items, "item")?;
+    let dictionary = params.dictionary.as_deref().map(decode_dictionary).transpose()?;
+
+    let decompressed = crate::utils::compression::decompress_zstd_batch(
+        &items, dictionary.as_deref()
+    )?;
+
+    Ok(ResultOfDecompressZstdBatch {
+        decompressed: decompressed.iter().map(base64::encode).collect(),
+    })
+}
@@ -0,0 +1,220 @@
+/*
+* Copyright 2018-2021 EverX Labs Ltd.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use serde_json::Value;
+
+use crate::abi::{decode_message_body, Abi, MessageBodyType, ParamsOfDecodeMessageBody};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use crate::net::{OrderBy, ParamsOfQueryCollection, SortDirection, MESSAGES_COLLECTION};
+use std::sync::Arc;
+
+const DEFAULT_EVENTS_LIMIT: u32 = 50;
+const PAGE_SIZE: u32 = 50;
+
+//------------------------------------------------------------------------------------ query_events
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfQueryEvents {
+    /// Contract address.
+    pub address: String,
+
+    /// Contract ABI used to decode the events.
+    pub abi: Abi,
+
+    /// Event name. If specified, only events with this name are returned.
+    pub event_name: Option<String>,
+
+    /// Event parameters filter.
+    ///
+    /// Object with parameter names and values. Only events with the decoded
+    /// parameters equal to the provided values are returned.
+    /// Values must be in the same form as returned by `abi.decode_message`.
+    pub params_filter: Option<Value>,
+
+    /// Lower bound (inclusive) of the event creation time (unix time in seconds).
+    pub start_time: Option<u32>,
+
+    /// Upper bound (exclusive) of the event creation time (unix time in seconds).
+    pub end_time: Option<u32>,
+
+    /// Maximum number of events to return. Default is 50.
+    pub limit: Option<u32>,
+
+    /// Cursor returned by the previous `query_events` call.
+    ///
+    /// If specified, events created after the cursor are returned.
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug)]
+pub struct DecodedEvent {
+    /// Event message id.
+    pub message_id: String,
+
+    /// Id of the transaction that emitted the event.
+    pub transaction_id: Option<String>,
+
+    /// Event creation time (unix time in seconds).
+    pub created_at: u32,
+
+    /// Event creation logical time.
+    pub created_lt: String,
+
+    /// Event name.
+    pub name: String,
+
+    /// Event parameters.
+    pub value: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug)]
+pub struct ResultOfQueryEvents {
+    /// Decoded events ordered by creation logical time.
+    pub events: Vec<DecodedEvent>,
+
+    /// Cursor to continue the query with.
+    ///
+    /// Pass it to the next `query_events` call to receive the following events.
+    /// Points to the last inspected message, so it can be used to poll for the new events.
+    pub cursor: Option<String>,
+}
+
+fn matches_params(value: &Option<Value>, params_filter: &Option<Value>) -> bool {
+    let filter = match params_filter.as_ref().and_then(|filter| filter.as_object()) {
+        Some(filter) => filter,
+        None => return true,
+    };
+    let value = match value {
+        Some(value) => value,
+        None => return filter.is_empty(),
+    };
+    filter.iter().all(|(name, expected)| &value[name] == expected)
+}
+
+fn decode_event(
+    context: &Arc<ClientContext>,
+    params: &ParamsOfQueryEvents,
+    message: &Value,
+) -> ClientResult<Option<DecodedEvent>> {
+    let body = match message["body"].as_str() {
+        Some(body) => body.to_string(),
+        None => return Ok(None),
+    };
+    let decoded = match decode_message_body(
+        context.clone(),
+        ParamsOfDecodeMessageBody {
+            abi: params.abi.clone(),
+            body,
+            is_internal: false,
+            ..Default::default()
+        },
+    ) {
+        Ok(decoded) => decoded,
+        Err(_) => return Ok(None),
+    };
+    if decoded.body_type != MessageBodyType::Event {
+        return Ok(None);
+    }
+    if let Some(event_name) = &params.event_name {
+        if &decoded.name != event_name {
+            return Ok(None);
+        }
+    }
+    if !matches_params(&decoded.value, &params.params_filter) {
+        return Ok(None);
+    }
+    Ok(Some(DecodedEvent {
+        message_id: message["id"].as_str().unwrap_or_default().to_string(),
+        transaction_id: message["src_transaction"]["id"]
+            .as_str()
+            .map(|id| id.to_string()),
+        created_at: message["created_at"].as_u64().unwrap_or_default() as u32,
+        created_lt: message["created_lt"].as_str().unwrap_or_default().to_string(),
+        name: decoded.name,
+        value: decoded.value,
+    }))
+}
+
+/// Queries and decodes events emitted by the contract.
+///
+/// Pages through the external outbound messages of the account in the order
+/// of their creation, decodes them with the provided ABI and returns the events
+/// that match the `event_name` and `params_filter`. Messages that can not be
+/// decoded as events of the ABI are skipped.
+///
+/// Use the returned `cursor` to continue the query.
+#[api_function]
+pub async fn query_events(
+    context: Arc<ClientContext>,
+    params: ParamsOfQueryEvents,
+) -> ClientResult<ResultOfQueryEvents> {
+    let server_link = context.get_server_link()?;
+    let limit = params.limit.unwrap_or(DEFAULT_EVENTS_LIMIT) as usize;
+    let mut cursor = params.cursor.clone();
+    let mut events = Vec::new();
+    loop {
+        let mut filter = json!({
+            "src": { "eq": params.address },
+            "msg_type": { "eq": 2 },
+        });
+        if let Some(cursor) = &cursor {
+            filter["created_lt"] = json!({ "gt": cursor });
+        }
+        if let Some(start_time) = params.start_time {
+            filter["created_at"]["ge"] = start_time.into();
+        }
+        if let Some(end_time) = params.end_time {
+            filter["created_at"]["lt"] = end_time.into();
+        }
+        let messages = server_link
+            .query_collection(
+                ParamsOfQueryCollection {
+                    collection: MESSAGES_COLLECTION.to_string(),
+                    filter: Some(filter),
+                    result: "id body created_at created_lt src_transaction { id }".to_string(),
+                    order: Some(vec![OrderBy {
+                        path: "created_lt".to_string(),
+                        direction: SortDirection::ASC,
+                    }]),
+                    limit: Some(PAGE_SIZE),
+                },
+                None,
+            )
+            .await?;
+        let messages = messages
+            .as_array()
+            .ok_or_else(|| crate::net::Error::invalid_server_response("Message array expected"))?;
+
+        for message in messages {
+            cursor = Some(
+                message["created_lt"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::net::Error::invalid_server_response("Message created_lt is missing")
+                    })?
+                    .to_string(),
+            );
+            if let Some(event) = decode_event(&context, &params, message)? {
+                events.push(event);
+                if events.len() >= limit {
+                    return Ok(ResultOfQueryEvents { events, cursor });
+                }
+            }
+        }
+        if messages.len() < PAGE_SIZE as usize {
+            break;
+        }
+    }
+    Ok(ResultOfQueryEvents { events, cursor })
+}
@@ -14,6 +14,7 @@
 pub use batch::{batch_query, ParamsOfBatchQuery, ResultOfBatchQuery};
 pub(crate) use endpoint::Endpoint;
 pub use errors::{Error, ErrorCode};
+pub use events::{query_events, DecodedEvent, ParamsOfQueryEvents, ResultOfQueryEvents};
 pub use iterators::block_iterator::{
     create_block_iterator, resume_block_iterator, ParamsOfCreateBlockIterator,
     ParamsOfResumeBlockIterator,
@@ -57,6 +58,7 @@ use crate::error::ClientResult;
 pub(crate) mod batch;
 mod endpoint;
 mod errors;
+pub(crate) mod events;
 mod gql;
 pub(crate) mod iterators;
 pub(crate) mod network_params;
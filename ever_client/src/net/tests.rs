@@ -1462,6 +1462,111 @@ async fn transaction_tree() {
     assert_eq!(result.transactions.len(), 2);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn query_events() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(crate::tests::EVENTS_OLD, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let address = client
+        .deploy_with_giver_async(
+            ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: Some(CallSet {
+                    function_name: "constructor".into(),
+                    header: Some(crate::abi::FunctionHeader {
+                        pubkey: Some(keys.public.clone()),
+                        ..Default::default()
+                    }),
+                    input: None,
+                }),
+                signer: Signer::Keys { keys: keys.clone() },
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+
+    for id in 1..=3 {
+        client
+            .net_process_function(
+                address.clone(),
+                abi.clone(),
+                "emitValue",
+                json!({ "id": id }),
+                Signer::Keys { keys: keys.clone() },
+            )
+            .await
+            .unwrap();
+    }
+
+    let result: ResultOfQueryEvents = client
+        .request_async(
+            "net.query_events",
+            ParamsOfQueryEvents {
+                address: address.clone(),
+                abi: abi.clone(),
+                event_name: Some("EventThrown".into()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.events.len(), 3);
+    for (event, id) in result.events.iter().zip(1..) {
+        assert_eq!(event.name, "EventThrown");
+        assert!(event.transaction_id.is_some());
+        assert!(event.created_at > 0);
+        assert_eq!(
+            event.value,
+            Some(json!({ "id": crate::utils::conversion::abi_uint(id, 256) }))
+        );
+    }
+
+    let result: ResultOfQueryEvents = client
+        .request_async(
+            "net.query_events",
+            ParamsOfQueryEvents {
+                address: address.clone(),
+                abi: abi.clone(),
+                params_filter: Some(json!({ "id": crate::utils::conversion::abi_uint(2, 256) })),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.events.len(), 1);
+
+    let first: ResultOfQueryEvents = client
+        .request_async(
+            "net.query_events",
+            ParamsOfQueryEvents {
+                address: address.clone(),
+                abi: abi.clone(),
+                limit: Some(2),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(first.events.len(), 2);
+    let rest: ResultOfQueryEvents = client
+        .request_async(
+            "net.query_events",
+            ParamsOfQueryEvents {
+                address,
+                abi,
+                cursor: first.cursor,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(rest.events.len(), 1);
+    assert_eq!(rest.cursor.as_deref(), Some(rest.events[0].created_lt.as_str()));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn order_by_fallback() {
     let params: ParamsOfQueryCollection = serde_json::from_str(
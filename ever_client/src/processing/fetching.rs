@@ -1,5 +1,5 @@
 use crate::abi::Abi;
-use crate::boc::internal::deserialize_object_from_base64;
+use crate::boc::internal::deserialize_object_from_boc;
 use crate::client::ClientContext;
 use crate::error::{ClientResult, AddNetworkUrl};
 use crate::net::{
@@ -195,7 +195,7 @@ pub async fn fetch_transaction_result(
         let balance = fetch_contract_balance(context_copy, &address_copy).await?;
         Ok((address_copy, balance))
     };
-    let transaction_object = deserialize_object_from_base64(&transaction_boc.boc, "transaction")?;
+    let transaction_object = deserialize_object_from_boc(context, &transaction_boc.boc, "transaction")?;
 
     let transaction = ever_sdk::Transaction::try_from(&transaction_object.object)
         .map_err(|err| crate::tvm::Error::can_not_read_transaction(err))?;
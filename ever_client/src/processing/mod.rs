@@ -17,7 +17,7 @@ mod tests;
 
 pub(crate) mod blocks_walking;
 mod errors;
-mod fetching;
+pub(crate) mod fetching;
 mod internal;
 mod message_monitor;
 mod message_monitor_sdk_services;
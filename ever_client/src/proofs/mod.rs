@@ -16,7 +16,7 @@ use ever_block::Result;
 
 pub(crate) use errors::ErrorCode;
 
-use crate::boc::internal::{deserialize_object_from_base64, deserialize_object_from_boc_bin};
+use crate::boc::internal::{deserialize_object_from_boc, deserialize_object_from_boc_bin};
 use crate::ClientContext;
 use crate::encoding::base64_decode;
 use crate::error::ClientResult;
@@ -330,7 +330,7 @@ pub(crate) async fn transaction_get_required_data<'trans>(
     }
 
     let transaction_stuff = if let Value::String(boc_base64) = boc_opt.as_ref() {
-        deserialize_object_from_base64(boc_base64, "transaction")?
+        deserialize_object_from_boc(engine.context(), boc_base64, "transaction")?
     } else {
         return Err(Error::internal_error("BOC is not found"));
     };
@@ -404,7 +404,7 @@ async fn message_get_required_data<'msg>(
     }
 
     let message_stuff = if let Value::String(boc_base64) = boc_opt.as_ref() {
-        deserialize_object_from_base64(boc_base64, "message")?
+        deserialize_object_from_boc(engine.context(), boc_base64, "message")?
     } else {
         return Err(Error::internal_error("BOC is not found"));
     };
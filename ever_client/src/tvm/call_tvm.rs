@@ -64,12 +64,20 @@ pub(crate) fn call_tvm(
     let data = account
         .get_data()
         .ok_or_else(|| Error::invalid_account_boc("Account has no code"))?;
-    let addr = account
-        .get_addr()
-        .ok_or_else(|| Error::invalid_account_boc("Account has no address"))?;
-    let balance = account
+    let addr = match &options.account_address {
+        Some(address) => address.clone(),
+        None => account
+            .get_addr()
+            .ok_or_else(|| Error::invalid_account_boc("Account has no address"))?
+            .clone(),
+    };
+    let mut balance = account
         .balance()
-        .ok_or_else(|| Error::invalid_account_boc("Account has no balance"))?;
+        .ok_or_else(|| Error::invalid_account_boc("Account has no balance"))?
+        .clone();
+    if let Some(account_balance) = options.account_balance {
+        balance.grams = account_balance.into();
+    }
 
     let mut ctrls = SaveList::new();
     ctrls
@@ -78,8 +86,8 @@ pub(crate) fn call_tvm(
 
     let mut sci = build_contract_info(
         options.blockchain_config.raw_config(),
-        addr,
-        balance,
+        &addr,
+        &balance,
         options.block_time,
         options.block_lt,
         options.transaction_lt,
@@ -87,6 +95,9 @@ pub(crate) fn call_tvm(
         account.init_code_hash(),
     );
     sci.capabilities = options.blockchain_config.capabilites();
+    if let Some(rand_seed) = &options.rand_seed {
+        sci.rand_seed = IntegerData::from_unsigned_bytes_be(rand_seed.as_slice());
+    }
     ctrls
         .put(7, &mut sci.into_temp_data_item())
         .map_err(|err| Error::internal_error(format!("can not put SCI to registers: {}", err)))?;
@@ -96,16 +107,20 @@ pub(crate) fn call_tvm(
 
     let mut engine = Engine::with_capabilities(
         options.blockchain_config.capabilites()
-    ).setup(
+    ).setup_with_libraries(
         slice_from_cell(code)?,
         Some(ctrls),
         Some(stack),
         Some(gas),
+        vec![account.libraries().inner(), options.libraries.clone()],
     );
 
     engine.set_signature_id(options.signature_id);
     engine.modify_behavior(options.behavior_modifiers);
     // engine.set_trace_callback(move |engine, info| { trace_callback(engine, info, true); });
+    if let Some(callback) = options.trace_callback() {
+        engine.set_trace_callback(move |engine, info| callback(engine, info));
+    }
 
     match engine.execute() {
         Err(err) => {
@@ -124,7 +139,7 @@ pub(crate) fn call_tvm(
                 exception.to_string(),
                 code,
                 Some(exit_arg),
-                addr,
+                &addr,
                 None,
                 true,
             ))
@@ -144,12 +159,24 @@ pub(crate) fn call_tvm_msg(
     options: ResolvedExecutionOptions,
     msg: &Message,
 ) -> ClientResult<Vec<Message>> {
+    Ok(call_tvm_msg_with_gas(account, options, msg)?.0)
+}
+
+/// Runs the message and returns the out messages together with the gas used
+pub(crate) fn call_tvm_msg_with_gas(
+    account: &mut Account,
+    options: ResolvedExecutionOptions,
+    msg: &Message,
+) -> ClientResult<(Vec<Message>, u64)> {
     let msg_cell = msg
         .serialize()
         .map_err(|err| Error::internal_error(format!("can not serialize message: {}", err)))?;
 
     let mut stack = Stack::new();
-    let balance = account.balance().map_or(0, |cc| cc.grams.as_u128());
+    let balance = match options.account_balance {
+        Some(balance) => balance as u128,
+        None => account.balance().map_or(0, |cc| cc.grams.as_u128()),
+    };
     let function_selector = match msg.header() {
         CommonMsgInfo::IntMsgInfo(_) => ever_vm::int!(0),
         CommonMsgInfo::ExtInMsgInfo(_) => ever_vm::int!(-1),
@@ -184,7 +211,7 @@ pub(crate) fn call_tvm_msg(
     }
 
     msgs.reverse();
-    Ok(msgs)
+    Ok((msgs, engine.gas_used() as u64))
 }
 
 // For solidity
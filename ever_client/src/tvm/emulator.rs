@@ -0,0 +1,533 @@
+/*
+ * Copyright 2018-2021 EverX Labs Ltd.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and
+ * limitations under the License.
+ *
+ */
+
+use super::run_message::{call_executor, parse_transaction};
+use super::types::{ExecutionOptions, ResolvedExecutionOptions};
+use crate::abi::Abi;
+use crate::boc::internal::{
+    deserialize_object_from_boc, deserialize_object_from_cell, serialize_cell_to_boc,
+    serialize_object_to_base64, serialize_object_to_cell,
+};
+use crate::boc::BocCacheType;
+use crate::client::ClientContext;
+use crate::encoding::account_decode;
+use crate::error::ClientResult;
+use crate::processing::{parsing::decode_output, DecodedOutput};
+use crate::tvm::Error;
+use ever_block::{
+    Account, Cell, CommonMsgInfo, CurrencyCollection, HashmapE, HashmapType, Message, MsgAddressInt,
+};
+use ever_executor::BlockchainConfig;
+use ever_vm::executor::BehaviorModifiers;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+const DEFAULT_DEPLOY_BALANCE: u64 = 100_000_000_000;
+const DEFAULT_TRANSACTION_MAX_COUNT: u32 = 100;
+
+/// State of the local blockchain emulator.
+pub(crate) struct Emulator {
+    blockchain_config: Arc<BlockchainConfig>,
+    signature_id: i32,
+    behavior_modifiers: BehaviorModifiers,
+    block_time: u32,
+    lt: u64,
+    accounts: HashMap<String, Cell>,
+    transactions: Vec<Value>,
+    provided_libraries: Option<Value>,
+    query_libraries: bool,
+}
+
+/// Emulator state saved before the message processing to roll back the failed one.
+struct EmulatorSnapshot {
+    accounts: HashMap<String, Cell>,
+    transactions: usize,
+    lt: u64,
+}
+
+impl Emulator {
+    fn snapshot(&self) -> EmulatorSnapshot {
+        EmulatorSnapshot {
+            accounts: self.accounts.clone(),
+            transactions: self.transactions.len(),
+            lt: self.lt,
+        }
+    }
+
+    fn restore(&mut self, snapshot: EmulatorSnapshot) {
+        self.accounts = snapshot.accounts;
+        self.transactions.truncate(snapshot.transactions);
+        self.lt = snapshot.lt;
+    }
+
+    fn options(&self) -> ResolvedExecutionOptions {
+        ResolvedExecutionOptions {
+            blockchain_config: self.blockchain_config.clone(),
+            signature_id: self.signature_id,
+            block_time: self.block_time,
+            block_lt: self.lt,
+            transaction_lt: self.lt,
+            behavior_modifiers: self.behavior_modifiers.clone(),
+            trace: None,
+            profiler: None,
+            rand_seed: None,
+            account_balance: None,
+            account_address: None,
+            provided_libraries: self.provided_libraries.clone(),
+            query_libraries: self.query_libraries,
+            libraries: HashmapE::with_bit_len(256),
+        }
+    }
+
+    fn account(&self, address: &MsgAddressInt) -> ClientResult<Cell> {
+        match self.accounts.get(&address.to_string()) {
+            Some(account) => Ok(account.clone()),
+            None => serialize_object_to_cell(&Account::default(), "account"),
+        }
+    }
+
+    async fn execute(
+        &mut self,
+        context: &Arc<ClientContext>,
+        message: Message,
+    ) -> ClientResult<(ever_block::Transaction, Value)> {
+        let address = message
+            .dst_ref()
+            .ok_or_else(|| Error::invalid_message_type())?
+            .clone();
+        let account = self.account(&address)?;
+        let mut options = self.options();
+        let message_cell = serialize_object_to_cell(&message, "message")?;
+        options
+            .resolve_libraries(context, &[account.clone(), message_cell])
+            .await?;
+        let account_copy = account.clone();
+        let contract_address = address.clone();
+        let contract_info = move || async move {
+            let account = deserialize_object_from_cell::<Account>(account_copy, "account")?;
+            Ok((
+                contract_address,
+                account
+                    .balance()
+                    .map(|balance| balance.grams.as_u128() as u64)
+                    .unwrap_or_default(),
+            ))
+        };
+        let (transaction, account) =
+            call_executor(account, message, options, contract_info, true).await?;
+
+        let state = deserialize_object_from_cell::<Account>(account.clone(), "account")?;
+        if state.is_none() {
+            self.accounts.remove(&address.to_string());
+        } else {
+            self.accounts.insert(address.to_string(), account);
+        }
+        self.lt = transaction.logical_time() + transaction.msg_count() as u64 + 1;
+
+        let parsed = parse_transaction(context, &transaction)?;
+        self.transactions.push(parsed.clone());
+        Ok((transaction, parsed))
+    }
+
+    async fn process(
+        &mut self,
+        context: &Arc<ClientContext>,
+        message: Message,
+        transaction_max_count: u32,
+    ) -> ClientResult<(Vec<Value>, Vec<String>)> {
+        let mut queue = BTreeMap::new();
+        let mut seq_no = 0u64;
+        queue.insert((0u64, seq_no), message);
+        let mut transactions = Vec::new();
+        let mut ext_out_messages = Vec::new();
+        while let Some((_, message)) = queue.pop_first() {
+            if transactions.len() >= transaction_max_count as usize {
+                return Err(Error::emulator_transaction_limit_exceeded(
+                    transaction_max_count,
+                ));
+            }
+            let (transaction, parsed) = self.execute(context, message).await?;
+            transactions.push(parsed);
+            for i in 0..transaction.msg_count() {
+                let message = transaction
+                    .get_out_msg(i)
+                    .map_err(|err| Error::can_not_read_transaction(err))?
+                    .ok_or_else(|| Error::can_not_read_transaction("message missing"))?;
+                match message.header() {
+                    CommonMsgInfo::IntMsgInfo(header) => {
+                        seq_no += 1;
+                        queue.insert((header.created_lt, seq_no), message);
+                    }
+                    _ => ext_out_messages.push(serialize_object_to_base64(&message, "message")?),
+                }
+            }
+        }
+        Ok((transactions, ext_out_messages))
+    }
+}
+
+async fn with_emulator<R>(
+    context: &Arc<ClientContext>,
+    handle: u32,
+    f: impl FnOnce(&mut Emulator) -> ClientResult<R>,
+) -> ClientResult<R> {
+    let emulator = context
+        .emulators
+        .get(&handle)
+        .ok_or_else(|| crate::client::Error::invalid_handle(handle, "emulator"))?;
+    let mut emulator = emulator.1.lock().await;
+    f(&mut emulator)
+}
+
+//--------------------------------------------------------------------------------- create_emulator
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default)]
+pub struct ParamsOfCreateEmulator {
+    /// Execution options.
+    ///
+    /// `block_time` and `transaction_lt` are used as the initial
+    /// emulator time and logical time. `libraries` and `resolve_libraries`
+    /// are used to resolve the libraries of every executed message.
+    pub execution_options: Option<ExecutionOptions>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default)]
+pub struct RegisteredEmulator {
+    /// Emulator handle.
+    ///
+    /// Must be removed using `remove_emulator`
+    /// when it is no more needed for the application.
+    pub handle: u32,
+}
+
+/// Creates local blockchain emulator.
+///
+/// Emulator keeps the accounts by address, executes the messages with the
+/// transaction executor and routes the produced internal messages to their destination
+/// accounts in the logical time order. It does not require network access.
+#[api_function]
+pub async fn create_emulator(
+    context: Arc<ClientContext>,
+    params: ParamsOfCreateEmulator,
+) -> ClientResult<RegisteredEmulator> {
+    let options = ResolvedExecutionOptions::from_options(&context, params.execution_options).await?;
+    let emulator = Emulator {
+        blockchain_config: options.blockchain_config,
+        signature_id: options.signature_id,
+        behavior_modifiers: options.behavior_modifiers,
+        block_time: options.block_time,
+        lt: options.transaction_lt,
+        accounts: HashMap::new(),
+        transactions: Vec::new(),
+        provided_libraries: options.provided_libraries,
+        query_libraries: options.query_libraries,
+    };
+    let handle = context.get_next_id();
+    context.emulators.insert(handle, Mutex::new(emulator));
+    Ok(RegisteredEmulator { handle })
+}
+
+/// Removes emulator and frees all its resources.
+#[api_function]
+pub fn remove_emulator(
+    context: Arc<ClientContext>,
+    params: RegisteredEmulator,
+) -> ClientResult<()> {
+    context
+        .emulators
+        .remove(&params.handle)
+        .ok_or_else(|| crate::client::Error::invalid_handle(params.handle, "emulator"))?;
+    Ok(())
+}
+
+//----------------------------------------------------------------------------------- emulator_send
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default)]
+pub struct ParamsOfEmulatorSend {
+    /// Emulator handle.
+    pub emulator: u32,
+    /// Input message BOC. Must be encoded as base64.
+    pub message: String,
+    /// Contract ABI for decoding external outbound messages.
+    pub abi: Option<Abi>,
+    /// Maximum number of transactions to execute. Default is 100.
+    pub transaction_max_count: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfEmulatorSend {
+    /// Parsed transactions in the execution order.
+    ///
+    /// The first transaction is produced by the input message,
+    /// the rest ones are produced by the internal messages.
+    pub transactions: Vec<Value>,
+
+    /// External outbound messages' BOCs. Encoded as `base64`
+    pub out_messages: Vec<String>,
+
+    /// Optional decoded external outbound messages according to the optional
+    /// `abi` parameter.
+    pub decoded: Option<DecodedOutput>,
+}
+
+async fn send_to_emulator(
+    context: &Arc<ClientContext>,
+    emulator: u32,
+    message: Message,
+    abi: Option<&Abi>,
+    transaction_max_count: Option<u32>,
+    prepare: impl FnOnce(&mut Emulator) -> ClientResult<()>,
+) -> ClientResult<ResultOfEmulatorSend> {
+    let emulator = context
+        .emulators
+        .get(&emulator)
+        .ok_or_else(|| crate::client::Error::invalid_handle(emulator, "emulator"))?;
+    let mut emulator = emulator.1.lock().await;
+    let snapshot = emulator.snapshot();
+    let result = match prepare(&mut emulator) {
+        Ok(()) => {
+            emulator
+                .process(
+                    context,
+                    message,
+                    transaction_max_count.unwrap_or(DEFAULT_TRANSACTION_MAX_COUNT),
+                )
+                .await
+        }
+        Err(err) => Err(err),
+    };
+    let (transactions, out_messages) = match result {
+        Ok(result) => result,
+        Err(err) => {
+            emulator.restore(snapshot);
+            return Err(err);
+        }
+    };
+    let decoded = abi
+        .map(|abi| decode_output(context, abi, out_messages.clone()))
+        .transpose()?;
+    Ok(ResultOfEmulatorSend {
+        transactions,
+        out_messages,
+        decoded,
+    })
+}
+
+/// Sends message to the emulator.
+///
+/// Executes the message and all the internal messages produced by it
+/// until there are no more messages to deliver. If the processing fails,
+/// e.g. the transaction limit is exceeded, the emulator state is rolled back
+/// and none of the transactions are kept.
+#[api_function]
+pub async fn emulator_send(
+    context: Arc<ClientContext>,
+    params: ParamsOfEmulatorSend,
+) -> ClientResult<ResultOfEmulatorSend> {
+    let message =
+        deserialize_object_from_boc::<Message>(&context, &params.message, "message")?.object;
+    send_to_emulator(
+        &context,
+        params.emulator,
+        message,
+        params.abi.as_ref(),
+        params.transaction_max_count,
+        |_| Ok(()),
+    )
+    .await
+}
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default)]
+pub struct ParamsOfEmulatorDeploy {
+    /// Emulator handle.
+    pub emulator: u32,
+    /// Deploy message BOC. Must be encoded as base64.
+    pub message: String,
+    /// Initial balance of the deployed account if there is no account
+    /// at its address yet. Default is 100 tokens.
+    pub balance: Option<u64>,
+    /// Contract ABI for decoding external outbound messages.
+    pub abi: Option<Abi>,
+    /// Maximum number of transactions to execute. Default is 100.
+    pub transaction_max_count: Option<u32>,
+}
+
+/// Deploys contract in the emulator.
+///
+/// Creates uninitialized account with the specified balance at the message
+/// destination address if there is no account there yet and sends the deploy
+/// message to it. The existing account, e.g. the one that received tokens before
+/// the deploy, is kept as is.
+#[api_function]
+pub async fn emulator_deploy(
+    context: Arc<ClientContext>,
+    params: ParamsOfEmulatorDeploy,
+) -> ClientResult<ResultOfEmulatorSend> {
+    let message =
+        deserialize_object_from_boc::<Message>(&context, &params.message, "message")?.object;
+    let address = message
+        .dst_ref()
+        .ok_or_else(|| Error::invalid_message_type())?
+        .clone();
+    let balance = CurrencyCollection::from(params.balance.unwrap_or(DEFAULT_DEPLOY_BALANCE));
+    send_to_emulator(
+        &context,
+        params.emulator,
+        message,
+        params.abi.as_ref(),
+        params.transaction_max_count,
+        |emulator| {
+            if !emulator.accounts.contains_key(&address.to_string()) {
+                let account = Account::uninit(address.clone(), 0, emulator.block_time, balance);
+                emulator.accounts.insert(
+                    address.to_string(),
+                    serialize_object_to_cell(&account, "account")?,
+                );
+            }
+            Ok(())
+        },
+    )
+    .await
+}
+
+//--------------------------------------------------------------------------------- emulator_account
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default)]
+pub struct ParamsOfEmulatorSetAccount {
+    /// Emulator handle.
+    pub emulator: u32,
+    /// Account BOC. Must be encoded as base64.
+    pub account: String,
+}
+
+/// Puts account into the emulator replacing the existing one with the same address.
+#[api_function]
+pub async fn emulator_set_account(
+    context: Arc<ClientContext>,
+    params: ParamsOfEmulatorSetAccount,
+) -> ClientResult<()> {
+    let account = deserialize_object_from_boc::<Account>(&context, &params.account, "account")?;
+    let address = account
+        .object
+        .get_addr()
+        .ok_or_else(|| Error::invalid_account_boc("Account has no address"))?
+        .to_string();
+    with_emulator(&context, params.emulator, |emulator| {
+        emulator.accounts.insert(address, account.cell);
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default)]
+pub struct ParamsOfEmulatorGetAccount {
+    /// Emulator handle.
+    pub emulator: u32,
+    /// Account address.
+    pub address: String,
+    /// Cache type to put the result. The BOC itself returned if no cache type provided
+    pub boc_cache: Option<BocCacheType>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfEmulatorGetAccount {
+    /// Account BOC encoded as `base64` or BOC cache key.
+    pub account: String,
+}
+
+/// Returns the current state of the emulator account.
+#[api_function]
+pub async fn emulator_get_account(
+    context: Arc<ClientContext>,
+    params: ParamsOfEmulatorGetAccount,
+) -> ClientResult<ResultOfEmulatorGetAccount> {
+    let address = account_decode(&params.address)?;
+    let account = with_emulator(&context, params.emulator, |emulator| {
+        emulator
+            .accounts
+            .get(&address.to_string())
+            .cloned()
+            .ok_or_else(|| Error::account_missing(&address))
+    })
+    .await?;
+    Ok(ResultOfEmulatorGetAccount {
+        account: serialize_cell_to_boc(&context, account, "account", params.boc_cache)?,
+    })
+}
+
+//---------------------------------------------------------------------------------- emulator_other
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default)]
+pub struct ParamsOfEmulatorQueryTransactions {
+    /// Emulator handle.
+    pub emulator: u32,
+    /// Account address. If specified, only the transactions of this account are returned.
+    pub address: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfEmulatorQueryTransactions {
+    /// Parsed transactions in the execution order.
+    pub transactions: Vec<Value>,
+}
+
+/// Returns transactions executed by the emulator.
+#[api_function]
+pub async fn emulator_query_transactions(
+    context: Arc<ClientContext>,
+    params: ParamsOfEmulatorQueryTransactions,
+) -> ClientResult<ResultOfEmulatorQueryTransactions> {
+    let address = params
+        .address
+        .as_ref()
+        .map(|address| account_decode(address))
+        .transpose()?
+        .map(|address| address.to_string());
+    let transactions = with_emulator(&context, params.emulator, |emulator| {
+        Ok(emulator
+            .transactions
+            .iter()
+            .filter(|transaction| match &address {
+                Some(address) => transaction["account_addr"].as_str() == Some(address.as_str()),
+                None => true,
+            })
+            .cloned()
+            .collect())
+    })
+    .await?;
+    Ok(ResultOfEmulatorQueryTransactions { transactions })
+}
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default)]
+pub struct ParamsOfEmulatorAdvanceTime {
+    /// Emulator handle.
+    pub emulator: u32,
+    /// Number of seconds to advance the emulator time by.
+    pub seconds: u32,
+}
+
+/// Advances the emulator block time.
+#[api_function]
+pub async fn emulator_advance_time(
+    context: Arc<ClientContext>,
+    params: ParamsOfEmulatorAdvanceTime,
+) -> ClientResult<()> {
+    with_emulator(&context, params.emulator, |emulator| {
+        emulator.block_time = emulator.block_time.saturating_add(params.seconds);
+        Ok(())
+    })
+    .await
+}
@@ -35,6 +35,10 @@ pub enum ErrorCode {
     InvalidMessageType = 413,
     ContractExecutionError = 414,
     AccountIsSuspended = 415,
+    EmulatorTransactionLimitExceeded = 416,
+    HistoricalAccountStateUnavailable = 417,
+    InvalidForkPoint = 418,
+    CanNotApplyConfigOverrides = 419,
 }
 pub struct Error;
 
@@ -311,6 +315,48 @@ impl Error {
         )
     }
 
+    pub fn emulator_transaction_limit_exceeded(transaction_max_count: u32) -> ClientError {
+        let mut error = error(
+            ErrorCode::EmulatorTransactionLimitExceeded,
+            format!(
+                "Emulator executed {} transactions but there are still undelivered messages",
+                transaction_max_count
+            ),
+        );
+        error.data["transaction_max_count"] = transaction_max_count.into();
+        error
+    }
+
+    pub fn historical_account_state_unavailable<E: Display>(
+        address: &str,
+        reason: E,
+    ) -> ClientError {
+        let mut error = error(
+            ErrorCode::HistoricalAccountStateUnavailable,
+            format!(
+                "Account state at the requested point is not available: {}. \
+                Provide the account BOC in the `account` parameter",
+                reason
+            ),
+        );
+        error.data["account_address"] = address.into();
+        error
+    }
+
+    pub fn invalid_fork_point<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::InvalidForkPoint,
+            format!("Invalid fork point: {}", err),
+        )
+    }
+
+    pub fn can_not_apply_config_overrides<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::CanNotApplyConfigOverrides,
+            format!("Can not apply blockchain config overrides: {}", err),
+        )
+    }
+
     pub fn internal_error<E: Display>(err: E) -> ClientError {
         error(
             ErrorCode::InternalError,
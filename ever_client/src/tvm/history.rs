@@ -0,0 +1,736 @@
+/*
+ * Copyright 2018-2021 EverX Labs Ltd.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and
+ * limitations under the License.
+ *
+ */
+
+use super::run_message::{
+    parse_transaction, run_executor_internal, AccountForExecutor, ParamsOfRunExecutor,
+};
+use super::{Error, ExecutionOptions, ResultOfRunExecutor};
+use crate::abi::Abi;
+use crate::boc::internal::{deserialize_object_from_boc, serialize_object_to_base64};
+use crate::boc::{get_blockchain_config, ParamsOfGetBlockchainConfig};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use crate::net::{
+    OrderBy, ParamsOfQueryCollection, SortDirection, BLOCKS_COLLECTION, TRANSACTIONS_COLLECTION,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use ever_block::{Account, AccountStatus, MsgAddressInt, Transaction, UInt256};
+
+/// Maximum number of the account transactions replayed to reconstruct
+/// the account state preceding a transaction.
+const MAX_RECONSTRUCTED_TRANSACTIONS: u32 = 50;
+
+/// Fields of the parsed transaction compared by `run_executor_at`.
+const DIFF_FIELDS: &[&str] = &[
+    "aborted",
+    "compute.exit_code",
+    "compute.gas_used",
+    "action.result_code",
+    "total_fees",
+    "outmsg_cnt",
+    "end_status",
+    "new_hash",
+];
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default)]
+pub struct ParamsOfRunExecutorAt {
+    /// Id of the on-chain transaction to fork at.
+    ///
+    /// The message is executed on the account state preceding the transaction with
+    /// the time, logical time and blockchain config of the transaction, and the result
+    /// is compared with the transaction.
+    pub transaction_id: Option<String>,
+
+    /// Masterchain block seq_no to fork at.
+    ///
+    /// The message is executed on the account state as of the end of the block with
+    /// the blockchain config in effect at that block. Ignored if `transaction_id` is specified.
+    pub mc_seq_no: Option<u32>,
+
+    /// Input message BOC. Encoded as `base64`.
+    ///
+    /// Default is the inbound message of the `transaction_id` transaction.
+    pub message: Option<String>,
+
+    /// Account BOC as of the fork point. Encoded as `base64`.
+    ///
+    /// For the `transaction_id` fork the BOC is checked against the state hash recorded
+    /// in the transaction. If it is not specified, the state is reconstructed by replaying
+    /// the account transactions since the account creation (at most 50 transactions).
+    /// For the `mc_seq_no` fork the current account state is fetched from the network
+    /// if it has not been changed since the block.
+    pub account: Option<String>,
+
+    /// Contract ABI for decoding output messages
+    pub abi: Option<Abi>,
+
+    /// Execution options.
+    ///
+    /// Unspecified `blockchain_config`, `block_time`, `block_lt`, `transaction_lt`
+    /// and `rand_seed` are taken from the fork point.
+    pub execution_options: Option<ExecutionOptions>,
+
+    /// Skip transaction check flag.
+    ///
+    /// Default is `true` for the `transaction_id` fork, so the aborted
+    /// transactions can be compared too.
+    pub skip_transaction_check: Option<bool>,
+
+    /// Return updated account flag. Empty string is returned if the flag is `false`
+    pub return_updated_account: Option<bool>,
+}
+
+/// Transaction phases compared field by field by `replay_transaction`.
+const REPLAY_PHASES: &[&str] = &["storage", "credit", "compute", "action", "bounce"];
+
+/// Transaction fields compared by `replay_transaction` in addition to the phases.
+const REPLAY_FIELDS: &[&str] = &[
+    "tr_type",
+    "aborted",
+    "destroyed",
+    "orig_status",
+    "end_status",
+    "total_fees",
+    "balance_delta",
+    "outmsg_cnt",
+    "out_msgs",
+    "old_hash",
+    "new_hash",
+];
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct TransactionFieldDiff {
+    /// Path of the field in the parsed transaction, e.g. `compute.exit_code`.
+    pub field: String,
+
+    /// Value of the field in the on-chain transaction.
+    pub on_chain: Value,
+
+    /// Value of the field in the emulated transaction.
+    pub emulated: Value,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfRunExecutorAt {
+    /// Result of the message execution.
+    pub result: ResultOfRunExecutor,
+
+    /// Seq_no of the key block the blockchain config was taken from.
+    pub key_block_seq_no: u32,
+
+    /// Differences between the on-chain and the emulated transactions.
+    ///
+    /// Returned only for the `transaction_id` fork. Empty if the emulated
+    /// transaction matches the on-chain one.
+    pub diff: Option<Vec<TransactionFieldDiff>>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default)]
+pub struct ParamsOfReplayTransaction {
+    /// Id of the on-chain transaction to replay.
+    pub transaction_id: String,
+
+    /// Account BOC preceding the transaction. Encoded as `base64`.
+    ///
+    /// The BOC is checked against the state hash recorded in the transaction.
+    /// The network stores only the current account states, so if the BOC is not specified,
+    /// the state is reconstructed by replaying the account transactions since the account
+    /// creation (at most 50 transactions).
+    pub account: Option<String>,
+
+    /// Execution options.
+    ///
+    /// Unspecified `blockchain_config`, `block_time`, `block_lt`, `transaction_lt`
+    /// and `rand_seed` are taken from the transaction.
+    pub execution_options: Option<ExecutionOptions>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfReplayTransaction {
+    /// The replayed transaction matches the on-chain one.
+    pub matches: bool,
+
+    /// Mismatched fields of the transaction phases, fees, out messages and state hashes.
+    pub mismatches: Vec<TransactionFieldDiff>,
+
+    /// Parsed replayed transaction.
+    pub transaction: Value,
+
+    /// Seq_no of the key block the blockchain config was taken from.
+    pub key_block_seq_no: u32,
+}
+
+/// Blockchain point to execute the message at.
+pub(crate) struct ForkPoint {
+    pub config_boc: String,
+    pub key_block_seq_no: u32,
+    pub block_time: u32,
+    pub block_lt: u64,
+    pub transaction_lt: u64,
+    pub rand_seed: Option<String>,
+}
+
+impl ForkPoint {
+    /// Fills the unspecified execution options with the fork point values.
+    fn apply(self, options: Option<ExecutionOptions>) -> ExecutionOptions {
+        let mut options = options.unwrap_or_default();
+        options.blockchain_config = options.blockchain_config.or(Some(self.config_boc));
+        options.block_time = options.block_time.or(Some(self.block_time));
+        options.block_lt = options.block_lt.or(Some(self.block_lt));
+        options.transaction_lt = options.transaction_lt.or(Some(self.transaction_lt));
+        options.rand_seed = options.rand_seed.or(self.rand_seed);
+        options
+    }
+}
+
+fn parse_lt(value: &Value, name: &str) -> ClientResult<u64> {
+    value
+        .as_str()
+        .and_then(|lt| lt.parse().ok())
+        .ok_or_else(|| crate::net::Error::invalid_server_response(format!("Invalid `{}`", name)))
+}
+
+async fn query_one(
+    context: &Arc<ClientContext>,
+    collection: &str,
+    filter: Value,
+    result: &str,
+) -> ClientResult<Option<Value>> {
+    let mut items = context
+        .get_server_link()?
+        .query_collection(
+            ParamsOfQueryCollection {
+                collection: collection.to_string(),
+                filter: Some(filter),
+                result: result.to_string(),
+                limit: Some(1),
+                ..Default::default()
+            },
+            None,
+        )
+        .await?;
+    Ok(items
+        .as_array_mut()
+        .and_then(|items| items.first_mut())
+        .map(|item| item.take()))
+}
+
+/// Fetches blockchain config stored in the masterchain key block
+/// (or in the zerostate for the seq_no 0) and returns it as a BOC.
+pub(crate) async fn fetch_config(
+    context: &Arc<ClientContext>,
+    key_block_seq_no: u32,
+) -> ClientResult<String> {
+    let boc = if key_block_seq_no == 0 {
+        query_one(
+            context,
+            "zerostates",
+            json!({ "id": { "eq": "zerostate:-1" } }),
+            "boc",
+        )
+        .await?
+    } else {
+        query_one(
+            context,
+            BLOCKS_COLLECTION,
+            json!({
+                "workchain_id": { "eq": -1 },
+                "seq_no": { "eq": key_block_seq_no },
+            }),
+            "boc",
+        )
+        .await?
+    };
+    let block_boc = boc
+        .as_ref()
+        .and_then(|block| block["boc"].as_str())
+        .ok_or_else(|| {
+            Error::can_not_read_blockchain_config(format!(
+                "Key block {} is not found",
+                key_block_seq_no
+            ))
+        })?
+        .to_string();
+    let config = get_blockchain_config(context.clone(), ParamsOfGetBlockchainConfig { block_boc })?;
+    Ok(config.config_boc)
+}
+
+/// Blockchain configs fetched within one call by the key block seq_no.
+///
+/// Consecutive transactions are usually preceded by the same key block,
+/// so its config is downloaded and parsed only once.
+#[derive(Default)]
+pub(crate) struct ConfigCache {
+    configs: HashMap<u32, String>,
+}
+
+impl ConfigCache {
+    pub async fn get(
+        &mut self,
+        context: &Arc<ClientContext>,
+        key_block_seq_no: u32,
+    ) -> ClientResult<String> {
+        if let Some(config) = self.configs.get(&key_block_seq_no) {
+            return Ok(config.clone());
+        }
+        let config = fetch_config(context, key_block_seq_no).await?;
+        self.configs.insert(key_block_seq_no, config.clone());
+        Ok(config)
+    }
+}
+
+/// On-chain transaction with the point preceding it.
+pub(crate) struct FetchedTransaction {
+    pub address: String,
+    pub transaction: Transaction,
+    pub point: ForkPoint,
+}
+
+/// Fetches the on-chain transaction and the point preceding it.
+pub(crate) async fn fetch_transaction(
+    context: &Arc<ClientContext>,
+    transaction_id: &str,
+    configs: &mut ConfigCache,
+) -> ClientResult<FetchedTransaction> {
+    let fetched = query_one(
+        context,
+        TRANSACTIONS_COLLECTION,
+        json!({ "id": { "eq": transaction_id } }),
+        "boc block_id account_addr",
+    )
+    .await?
+    .ok_or_else(|| {
+        Error::can_not_read_transaction(format!("Transaction {} is not found", transaction_id))
+    })?;
+    let boc = fetched["boc"]
+        .as_str()
+        .ok_or_else(|| crate::net::Error::invalid_server_response("Transaction boc is missing"))?;
+    let transaction = deserialize_object_from_boc::<Transaction>(context, boc, "transaction")?.object;
+
+    let block_id = fetched["block_id"].as_str().unwrap_or_default();
+    let block = query_one(
+        context,
+        BLOCKS_COLLECTION,
+        json!({ "id": { "eq": block_id } }),
+        "prev_key_block_seqno start_lt(format:DEC) rand_seed",
+    )
+    .await?
+    .ok_or_else(|| {
+        Error::can_not_read_transaction(format!("Transaction block {} is not found", block_id))
+    })?;
+    let key_block_seq_no = block["prev_key_block_seqno"].as_u64().ok_or_else(|| {
+        Error::can_not_read_transaction(format!(
+            "Transaction block {} has no `prev_key_block_seqno`",
+            block_id
+        ))
+    })? as u32;
+
+    let point = ForkPoint {
+        config_boc: configs.get(context, key_block_seq_no).await?,
+        key_block_seq_no,
+        block_time: transaction.now(),
+        block_lt: parse_lt(&block["start_lt"], "start_lt")?,
+        transaction_lt: transaction.logical_time(),
+        rand_seed: block["rand_seed"].as_str().map(|seed| seed.to_string()),
+    };
+    Ok(FetchedTransaction {
+        address: fetched["account_addr"].as_str().unwrap_or_default().to_string(),
+        transaction,
+        point,
+    })
+}
+
+async fn fetch_block_point(
+    context: &Arc<ClientContext>,
+    mc_seq_no: u32,
+) -> ClientResult<(ForkPoint, u64)> {
+    let block = query_one(
+        context,
+        BLOCKS_COLLECTION,
+        json!({
+            "workchain_id": { "eq": -1 },
+            "seq_no": { "eq": mc_seq_no },
+        }),
+        "gen_utime key_block prev_key_block_seqno end_lt(format:DEC) rand_seed",
+    )
+    .await?
+    .ok_or_else(|| {
+        Error::can_not_read_blockchain_config(format!(
+            "Masterchain block {} is not found",
+            mc_seq_no
+        ))
+    })?;
+    // Config stored in a key block is in effect for the following blocks
+    let key_block_seq_no = if block["key_block"].as_bool().unwrap_or_default() {
+        mc_seq_no
+    } else {
+        block["prev_key_block_seqno"].as_u64().ok_or_else(|| {
+            Error::can_not_read_blockchain_config(format!(
+                "Masterchain block {} has no `prev_key_block_seqno`",
+                mc_seq_no
+            ))
+        })? as u32
+    };
+    let end_lt = parse_lt(&block["end_lt"], "end_lt")?;
+    let point = ForkPoint {
+        config_boc: fetch_config(context, key_block_seq_no).await?,
+        key_block_seq_no,
+        block_time: block["gen_utime"].as_u64().unwrap_or_default() as u32,
+        block_lt: end_lt,
+        transaction_lt: end_lt + 1,
+        rand_seed: block["rand_seed"].as_str().map(|seed| seed.to_string()),
+    };
+    Ok((point, end_lt))
+}
+
+async fn fetch_account_at(
+    context: &Arc<ClientContext>,
+    address: &MsgAddressInt,
+    end_lt: u64,
+) -> ClientResult<String> {
+    let account = crate::processing::fetching::fetch_account(
+        context.clone(),
+        address,
+        "boc last_trans_lt(format:DEC)",
+    )
+    .await?;
+    let boc = account["boc"]
+        .as_str()
+        .ok_or_else(|| Error::account_missing(address))?;
+    let last_trans_lt = parse_lt(&account["last_trans_lt"], "last_trans_lt")?;
+    if last_trans_lt > end_lt {
+        return Err(Error::historical_account_state_unavailable(
+            &address.to_string(),
+            format!(
+                "account has been changed by the transaction with lt {} after the block end lt {}",
+                last_trans_lt, end_lt
+            ),
+        ));
+    }
+    Ok(boc.to_string())
+}
+
+fn check_state_hash(
+    context: &Arc<ClientContext>,
+    account: &str,
+    address: &str,
+    expected: &UInt256,
+    point: &str,
+) -> ClientResult<()> {
+    let account = deserialize_object_from_boc::<Account>(context, account, "account")?;
+    if &account.cell.repr_hash() != expected {
+        return Err(Error::historical_account_state_unavailable(
+            address,
+            format!(
+                "account hash {:x} does not match the state hash {:x} {} the transaction",
+                account.cell.repr_hash(),
+                expected,
+                point,
+            ),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_account_hash(
+    context: &Arc<ClientContext>,
+    account: &str,
+    fetched: &FetchedTransaction,
+) -> ClientResult<()> {
+    let state_update = fetched
+        .transaction
+        .read_state_update()
+        .map_err(|err| Error::can_not_read_transaction(err))?;
+    check_state_hash(context, account, &fetched.address, &state_update.old_hash, "before")
+}
+
+/// Returns the account state preceding the transaction.
+///
+/// Checks the provided state against the transaction. Otherwise, since the network
+/// stores only the current account states, reconstructs the state by replaying
+/// the account transactions starting from the one that created the account.
+pub(crate) async fn account_before(
+    context: &Arc<ClientContext>,
+    account: Option<String>,
+    fetched: &FetchedTransaction,
+    configs: &mut ConfigCache,
+) -> ClientResult<String> {
+    if let Some(account) = account {
+        check_account_hash(context, &account, fetched)?;
+        return Ok(account);
+    }
+    let mut account = serialize_object_to_base64(&Account::default(), "account")?;
+    if fetched.transaction.orig_status == AccountStatus::AccStateNonexist {
+        return Ok(account);
+    }
+
+    let unavailable = |reason: String| {
+        Error::historical_account_state_unavailable(&fetched.address, reason)
+    };
+    let previous = context
+        .get_server_link()?
+        .query_collection(
+            ParamsOfQueryCollection {
+                collection: TRANSACTIONS_COLLECTION.to_string(),
+                filter: Some(json!({
+                    "account_addr": { "eq": fetched.address },
+                    "lt": { "lt": format!("0x{:x}", fetched.transaction.logical_time()) },
+                })),
+                result: "id orig_status_name".to_string(),
+                order: Some(vec![OrderBy {
+                    path: "lt".to_string(),
+                    direction: SortDirection::DESC,
+                }]),
+                limit: Some(MAX_RECONSTRUCTED_TRANSACTIONS),
+            },
+            None,
+        )
+        .await?;
+    let previous = previous.as_array().cloned().unwrap_or_default();
+    let created = previous
+        .iter()
+        .position(|transaction| transaction["orig_status_name"] == "NonExist")
+        .ok_or_else(|| {
+            unavailable(format!(
+                "the account creation is not found among the {} preceding transactions",
+                MAX_RECONSTRUCTED_TRANSACTIONS
+            ))
+        })?;
+
+    for transaction in previous[..=created].iter().rev() {
+        let id = transaction["id"].as_str().unwrap_or_default();
+        let replayed = fetch_transaction(context, id, configs).await?;
+        let message = in_message(&replayed.transaction).map_err(|err| {
+            unavailable(format!("transaction {} can not be replayed: {}", id, err.message))
+        })?;
+        let state_update = replayed
+            .transaction
+            .read_state_update()
+            .map_err(|err| Error::can_not_read_transaction(err))?;
+        let result = run_executor_internal(
+            context.clone(),
+            ParamsOfRunExecutor {
+                message,
+                account: AccountForExecutor::Account {
+                    boc: account,
+                    unlimited_balance: None,
+                },
+                execution_options: Some(replayed.point.apply(None)),
+                skip_transaction_check: Some(true),
+                return_updated_account: Some(true),
+                ..Default::default()
+            },
+            false,
+        )
+        .await?;
+        account = result.account;
+        check_state_hash(context, &account, &fetched.address, &state_update.new_hash, "after")?;
+    }
+    check_account_hash(context, &account, fetched)?;
+    Ok(account)
+}
+
+pub(crate) fn transaction_diff(
+    on_chain: &Value,
+    emulated: &Value,
+    fields: &[&str],
+) -> Vec<TransactionFieldDiff> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let pointer = format!("/{}", field.replace('.', "/"));
+            let on_chain = on_chain.pointer(&pointer).cloned().unwrap_or_default();
+            let emulated = emulated.pointer(&pointer).cloned().unwrap_or_default();
+            if on_chain != emulated {
+                Some(TransactionFieldDiff {
+                    field: field.to_string(),
+                    on_chain,
+                    emulated,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn phase_diff(on_chain: &Value, emulated: &Value, phase: &str) -> Vec<TransactionFieldDiff> {
+    let mut fields = Vec::new();
+    for value in &[&on_chain[phase], &emulated[phase]] {
+        if let Some(object) = value.as_object() {
+            for key in object.keys() {
+                let field = format!("{}.{}", phase, key);
+                if !fields.contains(&field) {
+                    fields.push(field);
+                }
+            }
+        }
+    }
+    if fields.is_empty() {
+        return transaction_diff(on_chain, emulated, &[phase]);
+    }
+    let fields = fields.iter().map(|field| field.as_str()).collect::<Vec<_>>();
+    transaction_diff(on_chain, emulated, &fields)
+}
+
+fn in_message(transaction: &Transaction) -> ClientResult<String> {
+    let message = transaction
+        .read_in_msg()
+        .map_err(|err| Error::can_not_read_transaction(err))?
+        .ok_or_else(|| Error::can_not_read_transaction("Transaction has no inbound message"))?;
+    serialize_object_to_base64(&message, "message")
+}
+
+/// Executes the message against the historical blockchain state
+///
+/// Forks the blockchain at the specified transaction or masterchain block:
+/// fetches the blockchain config in effect at that point from the key block,
+/// takes the time and logical time of the point and executes the message
+/// with `run_executor` on the account state as of that point.
+///
+/// For the `transaction_id` fork the inbound message of the transaction is executed by default
+/// and the emulated transaction is compared with the on-chain one: the differences of
+/// the status, exit codes, gas, fees, out messages count and new state hash are returned in `diff`.
+/// Since the network does not store the historical account states, the account state preceding
+/// the transaction is reconstructed by replaying the account transactions since the account
+/// creation unless it is provided in `account`. The block random seed is used as the seed
+/// unless `execution_options.rand_seed` is specified.
+///
+/// Can be used to investigate incidents: replay a transaction with the trace enabled or
+/// check how a different message would have been processed at that point.
+#[api_function]
+pub async fn run_executor_at(
+    context: Arc<ClientContext>,
+    params: ParamsOfRunExecutorAt,
+) -> ClientResult<ResultOfRunExecutorAt> {
+    let (point, account, message, on_chain) = if let Some(id) = &params.transaction_id {
+        let mut configs = ConfigCache::default();
+        let fetched = fetch_transaction(&context, id, &mut configs).await?;
+        let account =
+            account_before(&context, params.account.clone(), &fetched, &mut configs).await?;
+        let message = match &params.message {
+            Some(message) => message.clone(),
+            None => in_message(&fetched.transaction)?,
+        };
+        let parsed = parse_transaction(&context, &fetched.transaction)?;
+        (fetched.point, account, message, Some(parsed))
+    } else if let Some(mc_seq_no) = params.mc_seq_no {
+        let message = params.message.clone().ok_or_else(|| {
+            Error::invalid_fork_point("`message` is required for the `mc_seq_no` fork")
+        })?;
+        let (point, end_lt) = fetch_block_point(&context, mc_seq_no).await?;
+        let account = match &params.account {
+            Some(account) => account.clone(),
+            None => {
+                let message = deserialize_object_from_boc::<ever_block::Message>(
+                    &context, &message, "message",
+                )?
+                .object;
+                let address = message
+                    .dst_ref()
+                    .ok_or_else(|| Error::invalid_message_type())?
+                    .clone();
+                fetch_account_at(&context, &address, end_lt).await?
+            }
+        };
+        (point, account, message, None)
+    } else {
+        return Err(Error::invalid_fork_point(
+            "either `transaction_id` or `mc_seq_no` must be specified",
+        ));
+    };
+
+    let key_block_seq_no = point.key_block_seq_no;
+    let options = point.apply(params.execution_options);
+
+    let result = run_executor_internal(
+        context.clone(),
+        ParamsOfRunExecutor {
+            message,
+            account: AccountForExecutor::Account {
+                boc: account,
+                unlimited_balance: None,
+            },
+            execution_options: Some(options),
+            abi: params.abi,
+            skip_transaction_check: params.skip_transaction_check.or(Some(on_chain.is_some())),
+            boc_cache: None,
+            return_updated_account: params.return_updated_account,
+        },
+        true,
+    )
+    .await?;
+
+    let diff = on_chain.map(|parsed| transaction_diff(&parsed, &result.transaction, DIFF_FIELDS));
+    Ok(ResultOfRunExecutorAt {
+        result,
+        key_block_seq_no,
+        diff,
+    })
+}
+
+/// Replays the on-chain transaction and compares the result with it
+///
+/// Fetches the transaction with its inbound message, the blockchain config
+/// in effect at the transaction block and the block random seed, re-executes the message
+/// on the account state preceding the transaction with the local transaction executor
+/// and compares every phase of the replayed transaction (storage, credit, compute, action,
+/// bounce), the fees, out messages and the new state hash with the on-chain transaction.
+///
+/// Can be used to validate executor upgrades and to audit critical transactions.
+#[api_function]
+pub async fn replay_transaction(
+    context: Arc<ClientContext>,
+    params: ParamsOfReplayTransaction,
+) -> ClientResult<ResultOfReplayTransaction> {
+    let mut configs = ConfigCache::default();
+    let fetched = fetch_transaction(&context, &params.transaction_id, &mut configs).await?;
+    let account = account_before(&context, params.account, &fetched, &mut configs).await?;
+    let message = in_message(&fetched.transaction)?;
+    let on_chain = parse_transaction(&context, &fetched.transaction)?;
+
+    let key_block_seq_no = fetched.point.key_block_seq_no;
+    let options = fetched.point.apply(params.execution_options);
+
+    let result = run_executor_internal(
+        context.clone(),
+        ParamsOfRunExecutor {
+            message,
+            account: AccountForExecutor::Account {
+                boc: account,
+                unlimited_balance: None,
+            },
+            execution_options: Some(options),
+            skip_transaction_check: Some(true),
+            ..Default::default()
+        },
+        false,
+    )
+    .await?;
+
+    let mut mismatches = Vec::new();
+    for phase in REPLAY_PHASES {
+        mismatches.append(&mut phase_diff(&on_chain, &result.transaction, phase));
+    }
+    mismatches.append(&mut transaction_diff(&on_chain, &result.transaction, REPLAY_FIELDS));
+
+    Ok(ResultOfReplayTransaction {
+        matches: mismatches.is_empty(),
+        mismatches,
+        transaction: result.transaction,
+        key_block_seq_no,
+    })
+}
@@ -14,21 +14,45 @@
 
 pub(crate) mod call_tvm;
 pub(crate) mod check_transaction;
+pub(crate) mod emulator;
 mod errors;
+pub(crate) mod history;
 pub(crate) mod run_get;
 pub(crate) mod run_message;
+pub(crate) mod trace;
+pub(crate) mod typed_stack;
 pub(crate) mod types;
 
 mod stack;
 #[cfg(test)]
 mod tests;
 
+pub use emulator::{
+    create_emulator, emulator_advance_time, emulator_deploy, emulator_get_account,
+    emulator_query_transactions, emulator_send, emulator_set_account, remove_emulator,
+    ParamsOfCreateEmulator, ParamsOfEmulatorAdvanceTime, ParamsOfEmulatorDeploy,
+    ParamsOfEmulatorGetAccount, ParamsOfEmulatorQueryTransactions, ParamsOfEmulatorSend,
+    ParamsOfEmulatorSetAccount, RegisteredEmulator, ResultOfEmulatorGetAccount,
+    ResultOfEmulatorQueryTransactions, ResultOfEmulatorSend,
+};
 pub use errors::{Error, ErrorCode, StdContractError};
-pub use run_get::{run_get, ParamsOfRunGet, ResultOfRunGet};
+pub use history::{
+    replay_transaction, run_executor_at, ParamsOfReplayTransaction, ParamsOfRunExecutorAt,
+    ResultOfReplayTransaction, ResultOfRunExecutorAt, TransactionFieldDiff,
+};
+pub use run_get::{
+    run_get, run_get_batch, run_get_raw, BatchAccount, ParamsOfRunGet, ParamsOfRunGetBatch,
+    ParamsOfRunGetRaw, ResultOfRunGet, ResultOfRunGetBatch, ResultOfRunGetRaw, RunGetBatchItem,
+};
 pub use run_message::{
     run_executor, run_tvm, run_solidity_getter, AccountForExecutor, ParamsOfRunExecutor, ParamsOfRunTvm,
     ResultOfRunExecutor, ResultOfRunTvm,
 };
 pub(crate) use run_message::run_executor_internal;
 pub use ever_sdk::TransactionFees;
-pub use types::ExecutionOptions;
+pub use trace::{CellGasUsage, FunctionGasUsage, GasProfile, TraceStep};
+pub use typed_stack::TypedStackItem;
+pub use types::{
+    ConfigOverrides, ExecutionBehaviorModifiers, ExecutionOptions, GasPricesOverrides,
+    StoragePricesOverrides,
+};
@@ -11,15 +11,21 @@
 * limitations under the License.
 */
 
+use futures::StreamExt;
 use serde_json::Value;
 
 use super::stack;
+use super::typed_stack::{deserialize_typed_item, serialize_typed_item, TypedStackItem};
 use super::types::{ExecutionOptions, ResolvedExecutionOptions};
 use crate::boc::internal::deserialize_object_from_boc;
 use crate::client::ClientContext;
 use crate::crypto::internal::ton_crc16;
-use crate::error::ClientResult;
+use crate::encoding::account_decode;
+use crate::error::{ClientError, ClientResult};
+use crate::net::{ParamsOfQueryCollection, ParamsOfQueryOperation, ACCOUNTS_COLLECTION};
 use crate::tvm::Error;
+use ever_block::{Cell, UInt256};
+use std::collections::HashMap;
 use std::sync::Arc;
 use ever_vm::stack::integer::IntegerData;
 use ever_vm::stack::{Stack, StackItem};
@@ -49,6 +55,33 @@ pub struct ResultOfRunGet {
     pub output: Value,
 }
 
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfRunGetRaw {
+    /// Account BOC in `base64`
+    pub account: String,
+    /// Get-method name. Used to calculate the method id if `method_id` is not specified.
+    pub function_name: Option<String>,
+    /// Get-method id.
+    pub method_id: Option<u32>,
+    /// Input stack. The first item is the bottom of the stack.
+    pub stack: Vec<TypedStackItem>,
+    /// Execution options
+    pub execution_options: Option<ExecutionOptions>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug, PartialEq)]
+pub struct ResultOfRunGetRaw {
+    /// Output stack. The first item is the bottom of the stack.
+    pub stack: Vec<TypedStackItem>,
+    /// Gas used by the get-method.
+    pub gas_used: u64,
+}
+
+pub(crate) fn get_method_id(function_name: &str) -> u32 {
+    let crc = ton_crc16(function_name.as_bytes());
+    ((crc as u32) & 0xffff) | 0x10000
+}
+
 /// Executes a get-method of FIFT contract
 ///
 /// Executes a get-method of FIFT contract that fulfills the smc-guidelines https://test.ton.org/smc-guidelines.txt
@@ -59,36 +92,292 @@ pub async fn run_get(
     context: Arc<ClientContext>,
     params: ParamsOfRunGet,
 ) -> ClientResult<ResultOfRunGet> {
-    let mut account: ever_block::Account =
-        deserialize_object_from_boc(&context, &params.account, "account")?.object;
-    let options = ResolvedExecutionOptions::from_options(&context, params.execution_options).await?;
+    let account =
+        deserialize_object_from_boc::<ever_block::Account>(&context, &params.account, "account")?;
+    let mut options = ResolvedExecutionOptions::from_options(&context, params.execution_options)
+        .await?
+        .without_collectors();
 
-    if account.is_none() {
+    if account.object.is_none() {
         return Err(Error::invalid_account_boc("Account is None"))
     }
+    options.resolve_libraries(&context, &[account.cell]).await?;
+    let mut account = account.object;
 
-    let crc = ton_crc16(params.function_name.as_bytes());
-    let function_id = ((crc as u32) & 0xffff) | 0x10000;
+    let function_id = get_method_id(&params.function_name);
+    Ok(ResultOfRunGet {
+        output: run_get_method(
+            &mut account,
+            options,
+            function_id,
+            deserialize_input(&context, params.input.as_ref())?,
+            params.tuple_list_as_array.unwrap_or_default(),
+        )?,
+    })
+}
+
+fn deserialize_input(context: &ClientContext, input: Option<&Value>) -> ClientResult<Vec<StackItem>> {
+    match input {
+        Some(Value::Array(array)) => stack::deserialize_items(context, array.iter()),
+        Some(input) => Ok(vec![stack::deserialize_item(context, input)?]),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn run_get_method(
+    account: &mut ever_block::Account,
+    options: ResolvedExecutionOptions,
+    function_id: u32,
+    input: Vec<StackItem>,
+    tuple_list_as_array: bool,
+) -> ClientResult<Value> {
     let mut stack_in = Stack::new();
-    if let Some(input) = params.input {
-        if let Value::Array(array) = input {
-            for value in array {
-                stack_in.push(stack::deserialize_item(&value)?);
-            }
-        } else {
-            stack_in.push(stack::deserialize_item(&input)?);
-        }
+    for item in input {
+        stack_in.push(item);
     }
 
     stack_in.push(StackItem::Integer(Arc::new(IntegerData::from_u32(
         function_id,
     ))));
 
+    let engine = super::call_tvm::call_tvm(account, options, stack_in)?;
+    stack::serialize_items(Box::new(engine.stack().iter()), tuple_list_as_array)
+}
+
+/// Executes a get-method with the typed stack
+///
+/// Pushes the typed input stack and the method id, executes the account code
+/// and returns the typed output stack. Unlike `run_get`, the stack items are passed
+/// as is, without conversion of lists and with exact slice and builder contents,
+/// so it can be used with the contracts requiring exact stack control.
+#[api_function]
+pub async fn run_get_raw(
+    context: Arc<ClientContext>,
+    params: ParamsOfRunGetRaw,
+) -> ClientResult<ResultOfRunGetRaw> {
+    let account =
+        deserialize_object_from_boc::<ever_block::Account>(&context, &params.account, "account")?;
+    let mut options = ResolvedExecutionOptions::from_options(&context, params.execution_options)
+        .await?
+        .without_collectors();
+
+    if account.object.is_none() {
+        return Err(Error::invalid_account_boc("Account is None"))
+    }
+    options.resolve_libraries(&context, &[account.cell]).await?;
+    let mut account = account.object;
+
+    let method_id = match (params.method_id, &params.function_name) {
+        (Some(method_id), _) => method_id,
+        (None, Some(function_name)) => get_method_id(function_name),
+        (None, None) => {
+            return Err(Error::invalid_input_stack(
+                "either `method_id` or `function_name` must be specified",
+                &Value::Null,
+            ))
+        }
+    };
+    let mut stack_in = Stack::new();
+    for item in &params.stack {
+        stack_in.push(deserialize_typed_item(&context, item)?);
+    }
+    stack_in.push(StackItem::Integer(Arc::new(IntegerData::from_u32(method_id))));
+
     let engine = super::call_tvm::call_tvm(&mut account, options, stack_in)?;
-    Ok(ResultOfRunGet {
-        output: stack::serialize_items(
-            Box::new(engine.stack().iter()),
-            params.tuple_list_as_array.unwrap_or_default(),
-        )?,
+    Ok(ResultOfRunGetRaw {
+        stack: engine
+            .stack()
+            .iter()
+            .map(serialize_typed_item)
+            .collect::<ClientResult<_>>()?,
+        gas_used: engine.gas_used() as u64,
     })
 }
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct BatchAccount {
+    /// Account BOC in `base64`.
+    ///
+    /// If not specified, the account is fetched from the network by `address`.
+    pub account: Option<String>,
+    /// Account address.
+    pub address: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfRunGetBatch {
+    /// Accounts to run the get-method on.
+    pub accounts: Vec<BatchAccount>,
+    /// Function name
+    pub function_name: String,
+    /// Input parameters
+    pub input: Option<Value>,
+    /// Execution options shared by all the accounts
+    pub execution_options: Option<ExecutionOptions>,
+    /// Convert lists based on nested tuples in the **result** into plain arrays. Default is `false`.
+    pub tuple_list_as_array: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug)]
+pub struct RunGetBatchItem {
+    /// Account address.
+    pub address: Option<String>,
+    /// Values returned by get-method on stack. Empty if the execution failed.
+    pub output: Option<Value>,
+    /// Error of the account execution.
+    pub error: Option<ClientError>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ResultOfRunGetBatch {
+    /// Results in the order of `accounts`.
+    pub results: Vec<RunGetBatchItem>,
+}
+
+/// Fetches BOCs of the accounts having only the address in one batch query.
+async fn fetch_batch_accounts(
+    context: &Arc<ClientContext>,
+    accounts: &[BatchAccount],
+) -> Vec<ClientResult<String>> {
+    let mut results = Vec::with_capacity(accounts.len());
+    let mut fetch = Vec::new();
+    for (index, account) in accounts.iter().enumerate() {
+        results.push(match (&account.account, &account.address) {
+            (Some(boc), _) => Some(Ok(boc.clone())),
+            (None, Some(address)) => match account_decode(address) {
+                Ok(address) => {
+                    fetch.push((index, address));
+                    None
+                }
+                Err(err) => Some(Err(err)),
+            },
+            (None, None) => Some(Err(Error::invalid_account_boc(
+                "Either account BOC or address must be provided",
+            ))),
+        });
+    }
+    if fetch.is_empty() {
+        return results.into_iter().flatten().collect();
+    }
+
+    let operations = fetch
+        .iter()
+        .map(|(_, address)| {
+            ParamsOfQueryOperation::QueryCollection(ParamsOfQueryCollection {
+                collection: ACCOUNTS_COLLECTION.to_string(),
+                filter: Some(json!({ "id": { "eq": address.to_string() } })),
+                result: "boc".to_string(),
+                limit: Some(1),
+                ..Default::default()
+            })
+        })
+        .collect::<Vec<_>>();
+    let fetched = match context.get_server_link() {
+        Ok(link) => link.batch_query(&operations, None).await,
+        Err(err) => Err(err),
+    };
+    for (position, (index, address)) in fetch.iter().enumerate() {
+        results[*index] = Some(match &fetched {
+            Ok(fetched) => fetched
+                .get(position)
+                .and_then(|accounts| accounts[0]["boc"].as_str())
+                .map(|boc| boc.to_string())
+                .ok_or_else(|| Error::account_missing(address)),
+            Err(err) => Err(err.clone()),
+        });
+    }
+    results.into_iter().flatten().collect()
+}
+
+/// Executes a get-method of FIFT contract on many accounts
+///
+/// Resolves the execution options once, fetches the accounts specified by address
+/// in one batch query, deserializes each distinct account BOC once, resolves
+/// the libraries of each distinct code once and runs the get-method on the accounts
+/// in parallel on the blocking threads, at most one run per CPU core. In wasm
+/// the get-method is run on the accounts one by one.
+///
+/// Returns the result or the error for each account in the order of `accounts`.
+#[api_function]
+pub async fn run_get_batch(
+    context: Arc<ClientContext>,
+    params: ParamsOfRunGetBatch,
+) -> ClientResult<ResultOfRunGetBatch> {
+    let mut options = ResolvedExecutionOptions::from_options(&context, params.execution_options)
+        .await?
+        .without_collectors();
+    let function_id = get_method_id(&params.function_name);
+    let input = deserialize_input(&context, params.input.as_ref())?;
+    let tuple_list_as_array = params.tuple_list_as_array.unwrap_or_default();
+
+    let bocs = fetch_batch_accounts(&context, &params.accounts).await;
+    let mut deserialized = HashMap::<String, ClientResult<ever_block::Account>>::new();
+    let mut codes = HashMap::<UInt256, Cell>::new();
+    let mut accounts = Vec::with_capacity(bocs.len());
+    for boc in bocs {
+        accounts.push(boc.and_then(|boc| {
+            deserialized
+                .entry(boc)
+                .or_insert_with_key(|boc| {
+                    let account: ever_block::Account =
+                        deserialize_object_from_boc(&context, boc, "account")?.object;
+                    if account.is_none() {
+                        return Err(Error::invalid_account_boc("Account is None"));
+                    }
+                    if let Some(code) = account.get_code() {
+                        codes.entry(code.repr_hash()).or_insert(code);
+                    }
+                    Ok(account)
+                })
+                .clone()
+        }));
+    }
+    let codes: Vec<Cell> = codes.into_values().collect();
+    options.resolve_libraries(&context, &codes).await?;
+
+    let addresses: Vec<Option<String>> = accounts
+        .iter()
+        .zip(params.accounts.iter())
+        .map(|(account, batch_account)| {
+            account
+                .as_ref()
+                .ok()
+                .and_then(|account| account.get_addr())
+                .map(|address| address.to_string())
+                .or_else(|| batch_account.address.clone())
+        })
+        .collect();
+    let runs = accounts
+        .into_iter()
+        .map(|account| {
+            let env = context.env.clone();
+            let options = options.clone();
+            let input = input.clone();
+            async move {
+                let mut account = account?;
+                env.spawn_blocking(move || {
+                    run_get_method(&mut account, options, function_id, input, tuple_list_as_array)
+                })
+                .await?
+            }
+        })
+        .collect::<Vec<_>>();
+    // TVM runs are CPU-bound, so there is no sense to run more of them than the CPU cores
+    let max_parallel_runs = std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1);
+    let outputs: Vec<ClientResult<Value>> = futures::stream::iter(runs)
+        .buffered(max_parallel_runs)
+        .collect()
+        .await;
+
+    let results = addresses
+        .into_iter()
+        .zip(outputs)
+        .map(|(address, output)| match output {
+            Ok(output) => RunGetBatchItem { address, output: Some(output), error: None },
+            Err(error) => RunGetBatchItem { address, output: None, error: Some(error) },
+        })
+        .collect();
+    Ok(ResultOfRunGetBatch { results })
+}
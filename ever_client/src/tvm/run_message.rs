@@ -13,14 +13,15 @@
  */
 
 use super::stack::serialize_item;
+use super::trace::{GasProfile, TraceCollector, TraceStep};
 use super::types::{ExecutionOptions, ResolvedExecutionOptions};
 use crate::boc::internal::{
     deserialize_cell_from_boc, deserialize_object_from_boc, deserialize_object_from_cell,
-    serialize_cell_to_boc, serialize_object_to_base64, serialize_object_to_boc,
-    serialize_object_to_cell,
+    serialize_cell_to_base64, serialize_cell_to_boc, serialize_object_to_base64,
+    serialize_object_to_boc, serialize_object_to_cell,
 };
 use crate::client::ClientContext;
-use crate::error::ClientResult;
+use crate::error::{ClientError, ClientResult};
 use crate::processing::{parsing::decode_output, DecodedOutput};
 use crate::tvm::{check_transaction::calc_transaction_fees, Error};
 use crate::{abi::Abi, boc::BocCacheType};
@@ -176,6 +177,16 @@ pub struct ResultOfRunExecutor {
 
     /// Transaction fees
     pub fees: TransactionFees,
+
+    /// Trace of the executed TVM instructions.
+    ///
+    /// Returned only if `execution_options.trace` is `true`.
+    pub trace: Option<Vec<TraceStep>>,
+
+    /// Gas consumption report.
+    ///
+    /// Returned only if `execution_options.profile` is `true`.
+    pub profile: Option<GasProfile>,
 }
 
 #[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
@@ -190,9 +201,58 @@ pub struct ResultOfRunTvm {
     /// Updated account state BOC. Encoded as `base64`.
     /// Attention! Only `account_state.storage.state.data` part of the BOC is updated.
     pub account: String,
+
+    /// Trace of the executed TVM instructions.
+    ///
+    /// Returned only if `execution_options.trace` is `true`.
+    pub trace: Option<Vec<TraceStep>>,
+
+    /// Gas consumption report.
+    ///
+    /// Returned only if `execution_options.profile` is `true`.
+    pub profile: Option<GasProfile>,
+}
+
+fn attach_trace(mut err: ClientError, trace: &Option<Arc<TraceCollector>>) -> ClientError {
+    if let Some(trace) = trace {
+        err.data["trace"] = json!(trace.steps());
+    }
+    err
 }
 
-fn parse_transaction(
+/// Finds the id of the function the message calls.
+///
+/// Decodes the message body with ABI if it is provided, otherwise takes the first 32 bits
+/// of the internal message body. The body of the external message starts with the signature
+/// and the headers, so the function id can not be read without ABI.
+fn message_function(
+    context: &Arc<ClientContext>,
+    abi: Option<&Abi>,
+    message: &Message,
+) -> Option<(u32, Option<String>)> {
+    let body = message.body()?;
+    let decoded = abi.and_then(|abi| {
+        let decoded = crate::abi::decode_message_body(
+            context.clone(),
+            crate::abi::ParamsOfDecodeMessageBody {
+                abi: abi.clone(),
+                body: serialize_cell_to_base64(&body.clone().into_cell(), "message body").ok()?,
+                is_internal: message.is_internal(),
+                ..Default::default()
+            },
+        )
+        .ok()
+        .filter(|decoded| decoded.body_type == crate::abi::MessageBodyType::Input)?;
+        let function_id = abi.abi().ok()?.function(&decoded.name).ok()?.get_input_id();
+        Some((function_id, Some(decoded.name)))
+    });
+    if decoded.is_some() || !message.is_internal() {
+        return decoded;
+    }
+    body.clone().get_next_u32().ok().map(|function_id| (function_id, None))
+}
+
+pub(crate) fn parse_transaction(
     context: &Arc<ClientContext>,
     transaction: &Transaction,
 ) -> ClientResult<Value> {
@@ -238,6 +298,10 @@ fn parse_transaction(
 /// downloaded from a particular key block - then specify `execution_options` parameter.
 ///
 /// If you need to see the aborted transaction as a result, not as an error, set `skip_transaction_check` to `true`.
+///
+/// To debug the contract execution set `execution_options.trace` to `true`: the trace of the executed
+/// TVM instructions is returned in the `trace` field of the result or in the `trace` field
+/// of the error data if the execution failed.
 
 #[api_function]
 pub async fn run_executor(
@@ -252,15 +316,19 @@ pub async fn run_executor_internal(
     params: ParamsOfRunExecutor,
     show_tips_on_error: bool,
 ) -> ClientResult<ResultOfRunExecutor> {
-    let message =
-        deserialize_object_from_boc::<Message>(&context, &params.message, "message")?.object;
+    let message = deserialize_object_from_boc::<Message>(&context, &params.message, "message")?;
+    let message_cell = message.cell;
+    let message = message.object;
     let msg_address = message
         .dst_ref()
         .ok_or_else(|| Error::invalid_message_type())?
         .clone();
     let (account, _) = params.account.get_account(&context, msg_address.clone())?;
-    let options =
+    let mut options =
         ResolvedExecutionOptions::from_options(&context, params.execution_options).await?;
+    options
+        .resolve_libraries(&context, &[account.clone(), message_cell])
+        .await?;
 
     let account_copy = account.clone();
     let contract_info = move || async move {
@@ -272,6 +340,14 @@ pub async fn run_executor_internal(
         }
     };
 
+    let trace = options.trace.clone();
+    let profiler = options.profiler.clone();
+    if let Some(profiler) = &profiler {
+        let function = message_function(&context, params.abi.as_ref(), &message);
+        if let Some((function_id, name)) = function {
+            profiler.set_function(function_id, name);
+        }
+    }
     let (transaction, modified_account) = call_executor(
         account.clone(),
         message,
@@ -279,7 +355,8 @@ pub async fn run_executor_internal(
         contract_info.clone(),
         show_tips_on_error,
     )
-    .await?;
+    .await
+    .map_err(|err| attach_trace(err, &trace))?;
 
     let sdk_transaction = ever_sdk::Transaction::try_from(&transaction)
         .map_err(|err| Error::can_not_read_transaction(err))?;
@@ -321,6 +398,8 @@ pub async fn run_executor_internal(
         account,
         decoded,
         fees,
+        trace: trace.map(|trace| trace.steps()),
+        profile: profiler.map(|profiler| profiler.report()),
     })
 }
 
@@ -344,15 +423,27 @@ pub async fn run_tvm(
     params: ParamsOfRunTvm,
 ) -> ClientResult<ResultOfRunTvm> {
     let mut account = deserialize_object_from_boc::<Account>(&context, &params.account, "account")?;
-    let message =
-        deserialize_object_from_boc::<Message>(&context, &params.message, "message")?.object;
-    let options =
+    let message = deserialize_object_from_boc::<Message>(&context, &params.message, "message")?;
+    let mut options =
         ResolvedExecutionOptions::from_options(&context, params.execution_options).await?;
     if account.object.is_none() {
         return Err(Error::invalid_account_boc("Account is None"));
     }
-
-    let messages = super::call_tvm::call_tvm_msg(&mut account.object, options, &message)?;
+    options
+        .resolve_libraries(&context, &[account.cell.clone(), message.cell])
+        .await?;
+    let message = message.object;
+
+    let trace = options.trace.clone();
+    let profiler = options.profiler.clone();
+    if let Some(profiler) = &profiler {
+        let function = message_function(&context, params.abi.as_ref(), &message);
+        if let Some((function_id, name)) = function {
+            profiler.set_function(function_id, name);
+        }
+    }
+    let messages = super::call_tvm::call_tvm_msg(&mut account.object, options, &message)
+        .map_err(|err| attach_trace(err, &trace))?;
 
     let mut out_messages = vec![];
     for message in messages {
@@ -381,6 +472,8 @@ pub async fn run_tvm(
         out_messages,
         account,
         decoded,
+        trace: trace.map(|trace| trace.steps()),
+        profile: profiler.map(|profiler| profiler.report()),
     })
 }
 
@@ -405,16 +498,17 @@ pub async fn run_solidity_getter(
     stack_items: Vec<StackItem>
 ) -> ClientResult<Vec<StackItem>> {
     let mut account = deserialize_object_from_boc::<Account>(&context, &params.account, "account")?;
-    let options =
+    let mut options =
         ResolvedExecutionOptions::from_options(&context, params.execution_options).await?;
     if account.object.is_none() {
         return Err(Error::invalid_account_boc("Account is None"));
     }
+    options.resolve_libraries(&context, &[account.cell.clone()]).await?;
 
     Ok(super::call_tvm::call_tvm_msg_getter(&mut account.object, options, stack_items)?)
 }
 
-async fn call_executor<F>(
+pub(crate) async fn call_executor<F>(
     mut account_root: Cell,
     msg: Message,
     options: ResolvedExecutionOptions,
@@ -424,6 +518,7 @@ async fn call_executor<F>(
 where
     F: futures::Future<Output = ClientResult<(MsgAddressInt, u64)>>,
 {
+    let trace_callback = options.trace_callback();
     let executor = OrdinaryTransactionExecutor::new(
         Arc::try_unwrap(options.blockchain_config).unwrap_or_else(|arc| arc.as_ref().clone()),
     );
@@ -432,8 +527,10 @@ where
         block_lt: options.block_lt,
         last_tr_lt: Arc::new(AtomicU64::new(options.transaction_lt)),
         behavior_modifiers: Some(options.behavior_modifiers),
-        seed_block: UInt256::rand(),
+        seed_block: options.rand_seed.clone().unwrap_or_else(UInt256::rand),
         signature_id: options.signature_id,
+        trace_callback,
+        state_libs: options.libraries.clone(),
         ..ExecuteParams::default()
     };
     let msg = ever_block::CommonMessage::Std(msg);
@@ -12,7 +12,8 @@
  *
  */
 
-use crate::boc::internal::{deserialize_cell_from_base64, serialize_cell_to_base64};
+use crate::boc::internal::{deserialize_cell_from_boc, serialize_cell_to_base64};
+use crate::client::ClientContext;
 use crate::encoding::slice_from_cell;
 use crate::error::ClientResult;
 use crate::tvm::Error;
@@ -97,10 +98,10 @@ pub fn serialize_items<'a>(
     }
 }
 
-pub fn deserialize_items(values: Iter<Value>) -> ClientResult<Vec<StackItem>> {
+pub fn deserialize_items(context: &ClientContext, values: Iter<Value>) -> ClientResult<Vec<StackItem>> {
     let mut items = Vec::<StackItem>::new();
     for value in values {
-        items.push(deserialize_item(value)?)
+        items.push(deserialize_item(context, value)?)
     }
     Ok(items)
 }
@@ -156,7 +157,7 @@ fn process_item(item: &StackItem) -> ClientResult<ProcessingResult> {
     })
 }
 
-pub fn deserialize_item(value: &Value) -> ClientResult<StackItem> {
+pub fn deserialize_item(context: &ClientContext, value: &Value) -> ClientResult<StackItem> {
     Ok(match value {
         Value::Null => StackItem::None,
         Value::Bool(v) => StackItem::boolean(*v),
@@ -168,34 +169,34 @@ pub fn deserialize_item(value: &Value) -> ClientResult<StackItem> {
             }
         }
         Value::String(s) => StackItem::integer(parse_integer_data(&s)?),
-        Value::Array(array) => StackItem::tuple(deserialize_items(array.iter())?),
+        Value::Array(array) => StackItem::tuple(deserialize_items(context, array.iter())?),
         Value::Object(_) => {
             let object = serde_json::from_value(value.clone())
                 .map_err(|err| Error::invalid_input_stack(
                     format!("Can not parse object: {}", err),value))?;
             match object {
                 ComplexType::Builder(string) => {
-                    let cell = deserialize_cell_from_base64(&string, "Builder")?.1;
+                    let cell = deserialize_cell_from_boc(context, &string, "Builder")?.1;
                     StackItem::builder(BuilderData::from_cell(&cell)
                        .map_err(|err| Error::invalid_input_stack(
                            format!("Can't create Builder from cell: {}", err), value))?)
                 }
                 ComplexType::Cell(string) => {
-                    let cell = deserialize_cell_from_base64(&string, "Cell")?.1;
+                    let cell = deserialize_cell_from_boc(context, &string, "Cell")?.1;
                     StackItem::cell(cell)
                 }
                 ComplexType::Continuation(string) => {
-                    let cell = deserialize_cell_from_base64(&string, "Continuation")?.1;
+                    let cell = deserialize_cell_from_boc(context, &string, "Continuation")?.1;
                     StackItem::continuation(ContinuationData::with_code(slice_from_cell(cell)?))
                 }
                 ComplexType::Slice(string) => {
-                    let cell = deserialize_cell_from_base64(&string, "Slice")?.1;
+                    let cell = deserialize_cell_from_boc(context, &string, "Slice")?.1;
                     StackItem::slice(slice_from_cell(cell)?)
                 }
                 ComplexType::List(mut vec) => {
                     let mut list = StackItem::None;
                     while let Some(item) = vec.pop() {
-                        list = StackItem::tuple(vec![deserialize_item(&item)?, list]);
+                        list = StackItem::tuple(vec![deserialize_item(context, &item)?, list]);
                     }
                     list
                 }
@@ -21,7 +21,7 @@ use crate::abi::{
 use crate::boc::ParamsOfEncodeStateInit;
 use crate::tests::GIVER_V2;
 use crate::boc::{
-    internal::{deserialize_object_from_base64, serialize_cell_to_base64},
+    internal::{deserialize_object_from_base64, serialize_cell_to_base64, serialize_object_to_base64},
     BocCacheType,
 };
 use crate::error::ClientResult;
@@ -584,7 +584,9 @@ fn test_stack_serialization() {
         ]),
     ]);
 
-    let stack_items = stack::deserialize_items(input.as_array().unwrap().iter()).unwrap();
+    let context = crate::ClientContext::new(crate::ClientConfig::default()).unwrap();
+    let stack_items =
+        stack::deserialize_items(&context, input.as_array().unwrap().iter()).unwrap();
 
     assert_eq!(
         stack_items,
@@ -1275,3 +1277,945 @@ async fn test_signature_id() {
         .unwrap_err();
     assert_eq!(result.data["exit_code"].as_i64().unwrap(), StdContractError::InvalidSignature as i64);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_emulator() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(crate::tests::EVENTS, Some(2));
+    let owner_keys = client.generate_sign_keys();
+    let other_keys = client.generate_sign_keys();
+
+    let emulator: RegisteredEmulator = client
+        .request_async("tvm.create_emulator", ParamsOfCreateEmulator::default())
+        .await
+        .unwrap();
+
+    let mut addresses = Vec::new();
+    for keys in [&owner_keys, &other_keys] {
+        let deploy_message = client
+            .encode_message(ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                deploy_set: DeploySet::some_with_tvc(tvc.clone()),
+                call_set: CallSet::some_with_function("constructor"),
+                signer: Signer::Keys { keys: keys.clone() },
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let result: ResultOfEmulatorSend = client
+            .request_async(
+                "tvm.emulator_deploy",
+                ParamsOfEmulatorDeploy {
+                    emulator: emulator.handle,
+                    message: deploy_message.message,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.transactions.len(), 1);
+        addresses.push(deploy_message.address);
+    }
+
+    let emit_message = client
+        .encode_message(ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            address: Some(addresses[0].clone()),
+            call_set: CallSet::some_with_function_and_input("emitValue", json!({ "id": 1 })),
+            signer: Signer::Keys {
+                keys: owner_keys.clone(),
+            },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let result: ResultOfEmulatorSend = client
+        .request_async(
+            "tvm.emulator_send",
+            ParamsOfEmulatorSend {
+                emulator: emulator.handle,
+                message: emit_message.message,
+                abi: Some(abi.clone()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.out_messages.len(), 1);
+    assert_eq!(
+        result.decoded.unwrap().out_messages[0].as_ref().unwrap().name,
+        "EventThrown"
+    );
+
+    let _: () = client
+        .request_async(
+            "tvm.emulator_advance_time",
+            ParamsOfEmulatorAdvanceTime {
+                emulator: emulator.handle,
+                seconds: 10,
+            },
+        )
+        .await
+        .unwrap();
+
+    let send_message = client
+        .encode_message(ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            address: Some(addresses[0].clone()),
+            call_set: CallSet::some_with_function_and_input(
+                "sendAllMoney",
+                json!({ "dest_addr": addresses[1] }),
+            ),
+            signer: Signer::Keys { keys: owner_keys },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let error = client
+        .request_async::<_, ResultOfEmulatorSend>(
+            "tvm.emulator_send",
+            ParamsOfEmulatorSend {
+                emulator: emulator.handle,
+                message: send_message.message.clone(),
+                transaction_max_count: Some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::EmulatorTransactionLimitExceeded as u32);
+    let transactions: ResultOfEmulatorQueryTransactions = client
+        .request_async(
+            "tvm.emulator_query_transactions",
+            ParamsOfEmulatorQueryTransactions {
+                emulator: emulator.handle,
+                address: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(transactions.transactions.len(), 3);
+
+    let result: ResultOfEmulatorSend = client
+        .request_async(
+            "tvm.emulator_send",
+            ParamsOfEmulatorSend {
+                emulator: emulator.handle,
+                message: send_message.message,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.transactions.len(), 2);
+    assert_eq!(result.transactions[1]["account_addr"], addresses[1]);
+
+    let transactions: ResultOfEmulatorQueryTransactions = client
+        .request_async(
+            "tvm.emulator_query_transactions",
+            ParamsOfEmulatorQueryTransactions {
+                emulator: emulator.handle,
+                address: Some(addresses[1].clone()),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(transactions.transactions.len(), 2);
+
+    let account: ResultOfEmulatorGetAccount = client
+        .request_async(
+            "tvm.emulator_get_account",
+            ParamsOfEmulatorGetAccount {
+                emulator: emulator.handle,
+                address: addresses[1].clone(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let parsed: crate::boc::ResultOfParse = client
+        .request_async(
+            "boc.parse_account",
+            crate::boc::ParamsOfParse {
+                boc: account.account,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(parsed.parsed["acc_type_name"], "Active");
+
+    let _: () = client
+        .request_async("tvm.remove_emulator", emulator.clone())
+        .await
+        .unwrap();
+    let error = client
+        .request_async::<_, ResultOfEmulatorQueryTransactions>(
+            "tvm.emulator_query_transactions",
+            ParamsOfEmulatorQueryTransactions {
+                emulator: emulator.handle,
+                address: None,
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, crate::client::ErrorCode::InvalidHandle as u32);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_trace() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(crate::tests::EVENTS, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let deploy_message = client
+        .encode_message(ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            deploy_set: DeploySet::some_with_tvc(tvc),
+            call_set: CallSet::some_with_function("constructor"),
+            signer: Signer::Keys { keys },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let deployed: ResultOfRunExecutor = client
+        .request_async(
+            "tvm.run_executor",
+            ParamsOfRunExecutor {
+                message: deploy_message.message,
+                account: AccountForExecutor::Uninit,
+                return_updated_account: Some(true),
+                execution_options: Some(ExecutionOptions {
+                    trace: Some(true),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let trace = deployed.trace.unwrap();
+    assert!(trace.iter().any(|step| step.kind == "Normal" && step.gas_cmd > 0));
+
+    let message = client
+        .encode_message(ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            address: Some(deploy_message.address.clone()),
+            call_set: CallSet::some_with_function_and_input("returnValue", json!({ "id": 1 })),
+            signer: Signer::None,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let first_step: ResultOfRunTvm = client
+        .request_async(
+            "tvm.run_tvm",
+            ParamsOfRunTvm {
+                message: message.message.clone(),
+                account: deployed.account.clone(),
+                execution_options: Some(ExecutionOptions {
+                    trace: Some(true),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let trace = first_step.trace.unwrap();
+    let step = trace.iter().find(|step| step.kind == "Normal").unwrap();
+
+    let result: ResultOfRunTvm = client
+        .request_async(
+            "tvm.run_tvm",
+            ParamsOfRunTvm {
+                message: message.message,
+                account: deployed.account,
+                execution_options: Some(ExecutionOptions {
+                    trace: Some(true),
+                    debug_map: Some(json!({
+                        format!("{}:{}", step.cell_hash, step.offset): "Events.sol:1"
+                    })),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(result
+        .trace
+        .unwrap()
+        .iter()
+        .any(|step| step.source.as_deref() == Some("Events.sol:1")));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_gas_profile() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(crate::tests::EVENTS, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let deploy_message = client
+        .encode_message(ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            deploy_set: DeploySet::some_with_tvc(tvc),
+            call_set: CallSet::some_with_function("constructor"),
+            signer: Signer::Keys { keys: keys.clone() },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let deployed: ResultOfRunExecutor = client
+        .request_async(
+            "tvm.run_executor",
+            ParamsOfRunExecutor {
+                message: deploy_message.message,
+                account: AccountForExecutor::Uninit,
+                return_updated_account: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(deployed.profile.is_none());
+
+    let message = client
+        .encode_message(ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            address: Some(deploy_message.address.clone()),
+            call_set: CallSet::some_with_function_and_input("emitValue", json!({ "id": 1 })),
+            signer: Signer::Keys { keys },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let result: ResultOfRunExecutor = client
+        .request_async(
+            "tvm.run_executor",
+            ParamsOfRunExecutor {
+                message: message.message,
+                account: AccountForExecutor::Account {
+                    boc: deployed.account,
+                    unlimited_balance: None,
+                },
+                abi: Some(abi.clone()),
+                execution_options: Some(ExecutionOptions {
+                    profile: Some(true),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let profile = result.profile.unwrap();
+    assert!(profile.total_gas > 0);
+    assert!(!profile.cells.is_empty());
+    assert_eq!(
+        profile.cells.iter().map(|cell| cell.gas).sum::<i64>(),
+        profile.total_gas
+    );
+    assert!(profile.cells.windows(2).all(|pair| pair[0].gas >= pair[1].gas));
+
+    let function_id = abi.abi().unwrap().function("emitValue").unwrap().get_input_id();
+    assert_eq!(profile.functions.len(), 1);
+    let function = &profile.functions[0];
+    assert_eq!(function.function_id, format!("0x{:08x}", function_id));
+    assert_eq!(function.name.as_deref(), Some("emitValue"));
+    assert!(function.gas > 0);
+    assert!(function.gas < profile.total_gas);
+    assert!(function.instructions < profile.instructions);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_run_executor_at() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(crate::tests::EVENTS, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let address = client
+        .deploy_with_giver_async(
+            ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: CallSet::some_with_function("constructor"),
+                signer: Signer::Keys { keys: keys.clone() },
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+    let account: String = client.fetch_account(&address).await["boc"]
+        .as_str()
+        .unwrap()
+        .into();
+
+    let processed = client
+        .net_process_function(
+            address.clone(),
+            abi.clone(),
+            "emitValue",
+            json!({ "id": 1 }),
+            Signer::Keys { keys },
+        )
+        .await
+        .unwrap();
+    let transaction_id = processed.transaction["id"].as_str().unwrap().to_string();
+
+    // the account state is reconstructed from the account transactions
+    let reconstructed: ResultOfRunExecutorAt = client
+        .request_async(
+            "tvm.run_executor_at",
+            ParamsOfRunExecutorAt {
+                transaction_id: Some(transaction_id.clone()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(reconstructed.diff, Some(vec![]));
+
+    let result: ResultOfRunExecutorAt = client
+        .request_async(
+            "tvm.run_executor_at",
+            ParamsOfRunExecutorAt {
+                transaction_id: Some(transaction_id.clone()),
+                account: Some(account.clone()),
+                abi: Some(abi.clone()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.diff, Some(vec![]));
+    assert_eq!(
+        result.result.transaction["new_hash"],
+        processed.transaction["new_hash"]
+    );
+    assert_eq!(result.result.out_messages.len(), 1);
+
+    // the account state after the call does not match the transaction
+    let account_after: String = client.fetch_account(&address).await["boc"]
+        .as_str()
+        .unwrap()
+        .into();
+    let error = client
+        .request_async::<_, ResultOfRunExecutorAt>(
+            "tvm.run_executor_at",
+            ParamsOfRunExecutorAt {
+                transaction_id: Some(transaction_id),
+                account: Some(account_after),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::HistoricalAccountStateUnavailable as u32);
+
+    let error = client
+        .request_async::<_, ResultOfRunExecutorAt>(
+            "tvm.run_executor_at",
+            ParamsOfRunExecutorAt::default(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidForkPoint as u32);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_replay_transaction() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(crate::tests::EVENTS, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let address = client
+        .deploy_with_giver_async(
+            ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: CallSet::some_with_function("constructor"),
+                signer: Signer::Keys { keys: keys.clone() },
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+    let account: String = client.fetch_account(&address).await["boc"]
+        .as_str()
+        .unwrap()
+        .into();
+
+    let processed = client
+        .net_process_function(
+            address,
+            abi,
+            "returnValue",
+            json!({ "id": 1 }),
+            Signer::Keys { keys },
+        )
+        .await
+        .unwrap();
+
+    let result: ResultOfReplayTransaction = client
+        .request_async(
+            "tvm.replay_transaction",
+            ParamsOfReplayTransaction {
+                transaction_id: processed.transaction["id"].as_str().unwrap().to_string(),
+                account: Some(account),
+                execution_options: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert!(result.matches, "{:?}", result.mismatches);
+    assert!(result.mismatches.is_empty());
+    assert_eq!(result.transaction["new_hash"], processed.transaction["new_hash"]);
+
+    let reconstructed: ResultOfReplayTransaction = client
+        .request_async(
+            "tvm.replay_transaction",
+            ParamsOfReplayTransaction {
+                transaction_id: processed.transaction["id"].as_str().unwrap().to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(reconstructed.matches, "{:?}", reconstructed.mismatches);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_config_overrides() {
+    let context = Arc::new(crate::ClientContext::new(crate::ClientConfig::default()).unwrap());
+    let (config, _) = offline_config();
+    let capabilities = config.raw_config().get_global_version().unwrap().capabilities;
+    assert!(!config.has_capability(GlobalCapabilities::CapSignatureWithId));
+
+    let options = types::ResolvedExecutionOptions::from_options(
+        &context,
+        Some(ExecutionOptions {
+            config_overrides: Some(ConfigOverrides {
+                enable_capabilities: Some(GlobalCapabilities::CapSignatureWithId as u64),
+                gas_prices: Some(GasPricesOverrides {
+                    gas_price: Some(1000 << 16),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    let config = options.blockchain_config.raw_config();
+    assert!(options
+        .blockchain_config
+        .has_capability(GlobalCapabilities::CapSignatureWithId));
+    assert_eq!(
+        config.get_global_version().unwrap().capabilities,
+        capabilities | GlobalCapabilities::CapSignatureWithId as u64
+    );
+    match config.config(21).unwrap() {
+        Some(ConfigParamEnum::ConfigParam21(prices)) => assert_eq!(prices.gas_price, 1000 << 16),
+        _ => panic!("gas prices are missing"),
+    }
+
+    let options = types::ResolvedExecutionOptions::from_options(
+        &context,
+        Some(ExecutionOptions {
+            config_overrides: Some(ConfigOverrides {
+                capabilities: Some(capabilities),
+                disable_capabilities: Some(GlobalCapabilities::CapSignatureWithId as u64),
+                global_version: Some(100),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    let global_version = options.blockchain_config.raw_config().get_global_version().unwrap();
+    assert_eq!(global_version.version, 100);
+    assert_eq!(global_version.capabilities, capabilities);
+    assert!(!options.behavior_modifiers.chksig_always_succeed);
+
+    let options = types::ResolvedExecutionOptions::from_options(
+        &context,
+        Some(ExecutionOptions {
+            chksig_always_succeed: Some(true),
+            behavior_modifiers: Some(ExecutionBehaviorModifiers {
+                chksig_always_succeed: Some(false),
+            }),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    assert!(!options.behavior_modifiers.chksig_always_succeed);
+
+    let options = types::ResolvedExecutionOptions::from_options(
+        &context,
+        Some(ExecutionOptions {
+            behavior_modifiers: Some(ExecutionBehaviorModifiers {
+                chksig_always_succeed: Some(true),
+            }),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    assert!(options.behavior_modifiers.chksig_always_succeed);
+
+    use ever_block::HashmapType;
+    let mut raw_config = offline_config().0.raw_config().clone();
+    let key = SliceData::load_cell(20u32.serialize().unwrap()).unwrap();
+    raw_config.config_params.remove(key).unwrap();
+    let error = types::ResolvedExecutionOptions::from_options(
+        &context,
+        Some(ExecutionOptions {
+            blockchain_config: Some(serialize_object_to_base64(&raw_config, "config").unwrap()),
+            signature_id: Some(0),
+            config_overrides: Some(ConfigOverrides {
+                masterchain_gas_prices: Some(GasPricesOverrides {
+                    gas_price: Some(1000 << 16),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+    )
+    .await
+    .err()
+    .unwrap();
+    assert_eq!(error.code, ErrorCode::CanNotApplyConfigOverrides as u32);
+}
+
+fn raw_code_account(client: &TestClient, code: Cell, balance: u64) -> String {
+    let state_init: crate::boc::ResultOfEncodeStateInit = client
+        .request(
+            "boc.encode_state_init",
+            ParamsOfEncodeStateInit {
+                code: Some(serialize_cell_to_base64(&code, "code").unwrap()),
+                data: Some(serialize_cell_to_base64(&Cell::default(), "data").unwrap()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let account: crate::abi::ResultOfEncodeAccount = client
+        .request(
+            "abi.encode_account",
+            ParamsOfEncodeAccount {
+                state_init: state_init.state_init,
+                balance: Some(balance),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    account.account
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_getter_overrides() {
+    let client = TestClient::new();
+
+    // DROP NOW RANDSEED BALANCE FIRST
+    let code = BuilderData::with_raw(vec![0x30, 0xF8, 0x23, 0xF8, 0x26, 0xF8, 0x27, 0x6F, 0x10], 72)
+        .unwrap()
+        .into_cell()
+        .unwrap();
+    let account = raw_code_account(&client, code, 1000);
+
+    let run_get = |execution_options: ExecutionOptions| {
+        client.request_async::<_, ResultOfRunGet>(
+            "tvm.run_get",
+            ParamsOfRunGet {
+                account: account.clone(),
+                function_name: "any".into(),
+                execution_options: Some(execution_options),
+                ..Default::default()
+            },
+        )
+    };
+    let options = ExecutionOptions {
+        block_time: Some(1_700_000_000),
+        rand_seed: Some("ff".repeat(32)),
+        account_balance: Some(12345),
+        ..Default::default()
+    };
+    let result = run_get(options.clone()).await.unwrap();
+    assert_eq!(
+        result.output,
+        json!([
+            "1700000000",
+            format!("0x{}", "f".repeat(64)),
+            "12345",
+        ])
+    );
+    assert_eq!(run_get(options).await.unwrap().output, result.output);
+
+    let result = run_get(ExecutionOptions::default()).await.unwrap();
+    assert_eq!(result.output[2], "1000");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_run_get_raw() {
+    let client = TestClient::new();
+
+    // DROP
+    let code = BuilderData::with_raw(vec![0x30], 8).unwrap().into_cell().unwrap();
+    let account = raw_code_account(&client, code, 1000);
+
+    let mut builder = BuilderData::with_raw(vec![0xAB, 0xCD, 0xEF], 24).unwrap();
+    builder.checked_append_reference(Cell::default()).unwrap();
+    let cell = builder.clone().into_cell().unwrap();
+    let mut slice = SliceData::load_cell(cell.clone()).unwrap();
+    slice.move_by(4).unwrap();
+    slice.shrink_data(..12);
+
+    let stack = vec![
+        StackItem::int(-5),
+        StackItem::integer(ever_vm::stack::integer::IntegerData::nan()),
+        StackItem::None,
+        StackItem::cell(cell),
+        StackItem::builder(builder),
+        StackItem::slice(slice),
+        StackItem::tuple(vec![StackItem::None, StackItem::tuple(vec![StackItem::int(1)])]),
+    ];
+    let stack = stack
+        .iter()
+        .map(|item| typed_stack::serialize_typed_item(item).unwrap())
+        .collect::<Vec<_>>();
+    let mut continuation = ContinuationData::with_code(SliceData::default());
+    typed_stack::serialize_typed_item(&StackItem::continuation(continuation.clone())).unwrap();
+    continuation.stack.push(StackItem::int(1));
+    typed_stack::serialize_typed_item(&StackItem::continuation(continuation)).unwrap_err();
+    assert_eq!(
+        stack[5],
+        TypedStackItem::Slice {
+            boc: match &stack[3] {
+                TypedStackItem::Cell { boc } => boc.clone(),
+                _ => unreachable!(),
+            },
+            data_start: 4,
+            data_end: 16,
+            refs_start: 0,
+            refs_end: 1,
+        }
+    );
+
+    let result: ResultOfRunGetRaw = client
+        .request_async(
+            "tvm.run_get_raw",
+            ParamsOfRunGetRaw {
+                account: account.clone(),
+                function_name: Some("echo".to_string()),
+                stack: stack.clone(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.stack, stack);
+    assert!(result.gas_used > 0);
+
+    let result: ResultOfRunGetRaw = client
+        .request_async(
+            "tvm.run_get_raw",
+            ParamsOfRunGetRaw {
+                account: account.clone(),
+                method_id: Some(run_get::get_method_id("echo")),
+                stack: vec![TypedStackItem::Integer { value: "1".to_string() }],
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.stack, vec![TypedStackItem::Integer { value: "1".to_string() }]);
+
+    let error = client
+        .request_async::<_, ResultOfRunGetRaw>(
+            "tvm.run_get_raw",
+            ParamsOfRunGetRaw {
+                account,
+                method_id: Some(0),
+                stack: vec![TypedStackItem::Slice {
+                    boc: serialize_cell_to_base64(&Cell::default(), "cell").unwrap(),
+                    data_start: 0,
+                    data_end: 1,
+                    refs_start: 0,
+                    refs_end: 0,
+                }],
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidInputStack as u32);
+}
+
+#[test]
+fn test_stack_boc_limits() {
+    let context = crate::ClientContext::new(
+        serde_json::from_value(json!({ "boc": { "max_cells": 1 } })).unwrap(),
+    )
+    .unwrap();
+    let boc = base64::encode(include_bytes!("../boc/test_data/account.boc"));
+    let assert_limit = |result: ClientResult<StackItem>| {
+        assert_eq!(
+            result.unwrap_err().code,
+            crate::boc::ErrorCode::BocLimitExceeded as u32
+        );
+    };
+
+    assert_limit(stack::deserialize_item(&context, &json!({ "type": "Cell", "value": boc })));
+    assert_limit(stack::deserialize_item(
+        &context,
+        &json!([{ "type": "Slice", "value": boc }]),
+    ));
+    assert_limit(typed_stack::deserialize_typed_item(
+        &context,
+        &TypedStackItem::Builder { boc: boc.clone() },
+    ));
+    assert_limit(typed_stack::deserialize_typed_item(
+        &context,
+        &TypedStackItem::Slice { boc, data_start: 0, data_end: 0, refs_start: 0, refs_end: 0 },
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_run_get_batch() {
+    let client = TestClient::new();
+
+    // DROP BALANCE FIRST
+    let code = BuilderData::with_raw(vec![0x30, 0xF8, 0x27, 0x6F, 0x10], 40)
+        .unwrap()
+        .into_cell()
+        .unwrap();
+    let accounts = (1..=3)
+        .map(|i| BatchAccount {
+            account: Some(raw_code_account(&client, code.clone(), i * 1000)),
+            address: None,
+        })
+        .chain(std::iter::once(BatchAccount::default()))
+        .collect::<Vec<_>>();
+
+    let result: ResultOfRunGetBatch = client
+        .request_async(
+            "tvm.run_get_batch",
+            ParamsOfRunGetBatch {
+                accounts,
+                function_name: "balance".into(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.results.len(), 4);
+    for (i, item) in result.results[..3].iter().enumerate() {
+        assert!(item.error.is_none());
+        assert!(item.address.is_some());
+        assert_eq!(item.output, Some(json!([((i + 1) * 1000).to_string()])));
+    }
+    assert!(result.results[3].output.is_none());
+    assert_eq!(
+        result.results[3].error.as_ref().unwrap().code,
+        ErrorCode::InvalidAccountBoc as u32
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_run_get_batch_by_address() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(crate::tests::EVENTS, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let address = client
+        .deploy_with_giver_async(
+            ParamsOfEncodeMessage {
+                abi,
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: CallSet::some_with_function("constructor"),
+                signer: Signer::Keys { keys },
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+    let account: String = client.fetch_account(&address).await["boc"]
+        .as_str()
+        .unwrap()
+        .into();
+    let missing = format!("0:{}", "1".repeat(64));
+
+    let by_address = |address: &str| BatchAccount {
+        account: None,
+        address: Some(address.to_string()),
+    };
+    let result: ResultOfRunGetBatch = client
+        .request_async(
+            "tvm.run_get_batch",
+            ParamsOfRunGetBatch {
+                accounts: vec![
+                    by_address(&address),
+                    BatchAccount { account: Some(account.clone()), address: None },
+                    BatchAccount { account: Some(account), address: None },
+                    by_address(&missing),
+                ],
+                function_name: "seqno".into(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.results.len(), 4);
+    for item in &result.results[..3] {
+        assert_eq!(item.address.as_deref(), Some(address.as_str()));
+        assert_eq!(item.output, result.results[0].output);
+        assert_eq!(
+            item.error.as_ref().map(|error| error.code),
+            result.results[0].error.as_ref().map(|error| error.code),
+        );
+    }
+    assert_eq!(result.results[3].address.as_deref(), Some(missing.as_str()));
+    assert_eq!(
+        result.results[3].error.as_ref().unwrap().code,
+        ErrorCode::AccountMissing as u32
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_run_get_with_library() {
+    let client = TestClient::new();
+
+    let library = BuilderData::with_raw(vec![42], 8).unwrap().into_cell().unwrap();
+    // DROP PUSHREF CTOS PLDU 8
+    let mut builder = BuilderData::with_raw(vec![0x30, 0x88, 0xD0, 0xD7, 0x0B, 0x07], 48).unwrap();
+    builder
+        .checked_append_reference(crate::boc::tests::library_reference_cell(&library.repr_hash()))
+        .unwrap();
+    let account = raw_code_account(&client, builder.into_cell().unwrap(), 1000);
+
+    let run_get = |libraries: Option<Value>| {
+        client.request_async::<_, ResultOfRunGet>(
+            "tvm.run_get",
+            ParamsOfRunGet {
+                account: account.clone(),
+                function_name: "library".into(),
+                execution_options: Some(ExecutionOptions {
+                    libraries,
+                    resolve_libraries: Some(false),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+    };
+    assert!(run_get(None).await.is_err());
+
+    let libraries = json!({
+        library.repr_hash().as_hex_string(): serialize_cell_to_base64(&library, "library").unwrap(),
+    });
+    let result = run_get(Some(libraries)).await.unwrap();
+    assert_eq!(result.output, json!(["42"]));
+}
@@ -0,0 +1,314 @@
+/*
+ * Copyright 2018-2021 EverX Labs Ltd.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and
+ * limitations under the License.
+ *
+ */
+
+use super::stack::serialize_item;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use ever_block::UInt256;
+use ever_vm::executor::{Engine, EngineTraceInfo, EngineTraceInfoType, TraceCallback};
+use ever_vm::stack::integer::IntegerData;
+use ever_vm::stack::StackItem;
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct TraceStep {
+    /// Step number.
+    pub step: u32,
+
+    /// Kind of the step: `Start`, `Normal`, `Implicit`, `Exception` or `Finish`.
+    pub kind: String,
+
+    /// Executed instruction.
+    pub instruction: String,
+
+    /// Representation hash of the code cell containing the instruction. Encoded as hex.
+    pub cell_hash: String,
+
+    /// Bit offset of the instruction in the code cell.
+    pub offset: u32,
+
+    /// Gas used by the instruction.
+    pub gas_cmd: i64,
+
+    /// Gas used since the start of the execution.
+    pub gas_used: i64,
+
+    /// Gas remaining after the instruction.
+    pub gas_remaining: i64,
+
+    /// Stack after the instruction, top item is the last one.
+    ///
+    /// Items are serialized the same way as for `run_get`.
+    pub stack: Vec<Value>,
+
+    /// Source location of the instruction found in the `debug_map`.
+    pub source: Option<String>,
+}
+
+/// Collects the TVM instructions trace.
+pub(crate) struct TraceCollector {
+    steps: Mutex<Vec<TraceStep>>,
+    debug_map: HashMap<String, String>,
+}
+
+impl TraceCollector {
+    pub fn new(debug_map: Option<Value>) -> Arc<Self> {
+        let debug_map = debug_map
+            .as_ref()
+            .and_then(|map| map.as_object())
+            .map(|map| {
+                map.iter()
+                    .filter_map(|(key, value)| {
+                        value.as_str().map(|value| (key.to_lowercase(), value.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Arc::new(Self {
+            steps: Mutex::new(Vec::new()),
+            debug_map,
+        })
+    }
+
+    fn record(&self, engine: &Engine, info: &EngineTraceInfo) {
+        let kind = match info.info_type {
+            EngineTraceInfoType::Start => "Start",
+            EngineTraceInfoType::Normal => "Normal",
+            EngineTraceInfoType::Implicit => "Implicit",
+            EngineTraceInfoType::Exception => "Exception",
+            EngineTraceInfoType::Finish => "Finish",
+            _ => return,
+        };
+        let cell_hash = info.cmd_code.cell().repr_hash().as_hex_string();
+        let offset = info.cmd_code.pos() as u32;
+        let source = self
+            .debug_map
+            .get(&format!("{}:{}", cell_hash, offset))
+            .cloned();
+        let step = TraceStep {
+            step: info.step,
+            kind: kind.to_string(),
+            instruction: info.cmd_str.clone(),
+            cell_hash,
+            offset,
+            gas_cmd: info.gas_cmd,
+            gas_used: info.gas_used,
+            gas_remaining: engine.gas_remaining(),
+            stack: info
+                .stack
+                .iter()
+                .map(|item| serialize_item(item).unwrap_or(Value::Null))
+                .collect(),
+            source,
+        };
+        if let Ok(mut steps) = self.steps.lock() {
+            steps.push(step);
+        }
+    }
+
+    pub fn steps(&self) -> Vec<TraceStep> {
+        self.steps
+            .lock()
+            .map(|steps| steps.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct CellGasUsage {
+    /// Representation hash of the code cell. Encoded as hex.
+    pub cell_hash: String,
+
+    /// Gas consumed by the instructions of the cell.
+    pub gas: i64,
+
+    /// Number of executed instructions of the cell.
+    pub instructions: u32,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct FunctionGasUsage {
+    /// Function id encoded as `0x` prefixed hex.
+    pub function_id: String,
+
+    /// Function name. Provided if the message is decoded with ABI.
+    pub name: Option<String>,
+
+    /// Gas consumed since the dispatcher has jumped into the function code.
+    pub gas: i64,
+
+    /// Number of executed instructions of the function.
+    pub instructions: u32,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct GasProfile {
+    /// Total gas consumed by the executed instructions.
+    pub total_gas: i64,
+
+    /// Number of executed instructions.
+    pub instructions: u32,
+
+    /// Gas consumption by code cells sorted from the most expensive one.
+    pub cells: Vec<CellGasUsage>,
+
+    /// Gas consumption by ABI functions.
+    ///
+    /// Contains the function called by the inbound message. The function id is taken
+    /// from the body decoded with ABI or from the first 32 bits of the internal message body.
+    /// Empty if the function id is unknown or the contract doesn't dispatch the message by it.
+    pub functions: Vec<FunctionGasUsage>,
+}
+
+/// Tracks the jump of the contract dispatcher into the called function.
+///
+/// The dispatcher leaves the function id on the stack top while searching for
+/// the function code. The first step executed in another code cell after the function id
+/// has been taken from the stack is the beginning of the function.
+struct FunctionDispatch {
+    function_id: u32,
+    name: Option<String>,
+    selector: IntegerData,
+    /// Code cell of the last step that left the function id on the stack top.
+    selector_cell: Option<UInt256>,
+    entered: bool,
+    gas: i64,
+    instructions: u32,
+}
+
+impl FunctionDispatch {
+    fn record(&mut self, cell_hash: &UInt256, info: &EngineTraceInfo) {
+        let selector_on_top = info.stack.depth() > 0
+            && matches!(info.stack.get(0), StackItem::Integer(value) if **value == self.selector);
+        if !self.entered {
+            self.entered = !selector_on_top
+                && self
+                    .selector_cell
+                    .as_ref()
+                    .map_or(false, |selector_cell| selector_cell != cell_hash);
+        }
+        if self.entered {
+            self.gas += info.gas_cmd;
+            self.instructions += 1;
+        } else if selector_on_top {
+            self.selector_cell = Some(cell_hash.clone());
+        }
+    }
+
+    fn report(&self) -> Option<FunctionGasUsage> {
+        if !self.entered {
+            return None;
+        }
+        Some(FunctionGasUsage {
+            function_id: format!("0x{:08x}", self.function_id),
+            name: self.name.clone(),
+            gas: self.gas,
+            instructions: self.instructions,
+        })
+    }
+}
+
+/// Accumulates gas consumption by code cells and by the called function.
+#[derive(Default)]
+pub(crate) struct GasProfiler {
+    cells: Mutex<HashMap<String, CellGasUsage>>,
+    dispatch: Mutex<Option<FunctionDispatch>>,
+}
+
+impl GasProfiler {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Sets the function the inbound message is dispatched to.
+    pub fn set_function(&self, function_id: u32, name: Option<String>) {
+        if let Ok(mut dispatch) = self.dispatch.lock() {
+            *dispatch = Some(FunctionDispatch {
+                function_id,
+                name,
+                selector: IntegerData::from_u32(function_id),
+                selector_cell: None,
+                entered: false,
+                gas: 0,
+                instructions: 0,
+            });
+        }
+    }
+
+    fn record(&self, info: &EngineTraceInfo) {
+        match info.info_type {
+            EngineTraceInfoType::Normal | EngineTraceInfoType::Implicit => {}
+            _ => return,
+        }
+        let cell_hash = info.cmd_code.cell().repr_hash();
+        if let Ok(mut dispatch) = self.dispatch.lock() {
+            if let Some(dispatch) = dispatch.as_mut() {
+                dispatch.record(&cell_hash, info);
+            }
+        }
+        let cell_hash = cell_hash.as_hex_string();
+        if let Ok(mut cells) = self.cells.lock() {
+            let usage = cells.entry(cell_hash.clone()).or_insert_with(|| CellGasUsage {
+                cell_hash,
+                ..Default::default()
+            });
+            usage.gas += info.gas_cmd;
+            usage.instructions += 1;
+        }
+    }
+
+    pub fn report(&self) -> GasProfile {
+        let mut cells = self
+            .cells
+            .lock()
+            .map(|cells| cells.values().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        cells.sort_by(|a, b| b.gas.cmp(&a.gas).then_with(|| a.cell_hash.cmp(&b.cell_hash)));
+        let total_gas = cells.iter().map(|cell| cell.gas).sum();
+        let instructions = cells.iter().map(|cell| cell.instructions).sum();
+        let functions = self
+            .dispatch
+            .lock()
+            .ok()
+            .and_then(|dispatch| dispatch.as_ref().and_then(|dispatch| dispatch.report()))
+            .into_iter()
+            .collect();
+        GasProfile {
+            total_gas,
+            instructions,
+            cells,
+            functions,
+        }
+    }
+}
+
+/// Builds TVM trace callback feeding the enabled trace collector and gas profiler.
+pub(crate) fn trace_callback(
+    trace: &Option<Arc<TraceCollector>>,
+    profiler: &Option<Arc<GasProfiler>>,
+) -> Option<Arc<TraceCallback>> {
+    if trace.is_none() && profiler.is_none() {
+        return None;
+    }
+    let trace = trace.clone();
+    let profiler = profiler.clone();
+    Some(Arc::new(move |engine: &Engine, info: &EngineTraceInfo| {
+        if let Some(trace) = &trace {
+            trace.record(engine, info);
+        }
+        if let Some(profiler) = &profiler {
+            profiler.record(info);
+        }
+    }))
+}
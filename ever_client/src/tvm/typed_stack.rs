@@ -0,0 +1,223 @@
+/*
+ * Copyright 2018-2021 EverX Labs Ltd.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and
+ * limitations under the License.
+ *
+ */
+
+use crate::boc::internal::{deserialize_cell_from_boc, serialize_cell_to_base64};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use crate::tvm::Error;
+use ever_block::{BuilderData, SliceData};
+use ever_vm::stack::continuation::{ContinuationData, ContinuationType};
+use ever_vm::stack::{integer::IntegerData, StackItem};
+
+/// TVM stack item with the exact type information.
+///
+/// Unlike the `run_get` stack representation, the typed items are converted
+/// to the TVM stack items and back without loss: slices keep their data and
+/// references windows, builders keep their data and references, tuples keep nulls.
+#[derive(Serialize, Deserialize, ApiType, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum TypedStackItem {
+    /// Null value.
+    Null,
+    /// Integer value.
+    Integer {
+        /// Integer encoded as decimal string (with `-` sign for negative values)
+        /// or `NaN`.
+        value: String,
+    },
+    /// Cell.
+    Cell {
+        /// Cell BOC encoded as `base64`.
+        boc: String,
+    },
+    /// Builder.
+    Builder {
+        /// BOC of the cell with the builder data and references encoded as `base64`.
+        boc: String,
+    },
+    /// Slice.
+    Slice {
+        /// BOC of the cell the slice is taken from encoded as `base64`.
+        boc: String,
+        /// Start bit of the slice data window.
+        data_start: u32,
+        /// End bit (exclusive) of the slice data window.
+        data_end: u32,
+        /// Start index of the slice references window.
+        refs_start: u32,
+        /// End index (exclusive) of the slice references window.
+        refs_end: u32,
+    },
+    /// Tuple.
+    Tuple {
+        /// Tuple items.
+        items: Vec<TypedStackItem>,
+    },
+    /// Ordinary continuation.
+    ///
+    /// Only the continuation code is represented, so the continuations with
+    /// the saved control registers, the stack or the arguments number can not be
+    /// returned and the execution result with such continuation is an error.
+    Continuation {
+        /// BOC of the cell the continuation code is taken from encoded as `base64`.
+        boc: String,
+        /// Start bit of the code.
+        data_start: u32,
+        /// End bit (exclusive) of the code.
+        data_end: u32,
+        /// Start index of the code references.
+        refs_start: u32,
+        /// End index (exclusive) of the code references.
+        refs_end: u32,
+    },
+}
+
+fn slice_window(slice: &SliceData, name: &str) -> ClientResult<(String, u32, u32, u32, u32)> {
+    let refs = slice.get_references();
+    Ok((
+        serialize_cell_to_base64(slice.cell(), name)?,
+        slice.pos() as u32,
+        (slice.pos() + slice.remaining_bits()) as u32,
+        refs.start as u32,
+        refs.end as u32,
+    ))
+}
+
+fn load_slice(
+    context: &ClientContext,
+    item: &TypedStackItem,
+    boc: &str,
+    data: (u32, u32),
+    refs: (u32, u32),
+) -> ClientResult<SliceData> {
+    let cell = deserialize_cell_from_boc(context, boc, "stack item")?.1;
+    if data.0 > data.1
+        || data.1 as usize > cell.bit_length()
+        || refs.0 > refs.1
+        || refs.1 as usize > cell.references_count()
+    {
+        return Err(Error::invalid_input_stack(
+            "Slice window is out of the cell bounds",
+            &json!(item),
+        ));
+    }
+    let mut slice = SliceData::load_cell(cell)
+        .map_err(|err| Error::invalid_input_stack(err, &json!(item)))?;
+    slice.shrink_data(data.0 as usize..data.1 as usize);
+    slice.shrink_references(refs.0 as usize..refs.1 as usize);
+    Ok(slice)
+}
+
+/// Converts TVM stack item to the typed representation.
+pub(crate) fn serialize_typed_item(item: &StackItem) -> ClientResult<TypedStackItem> {
+    Ok(match item {
+        StackItem::None => TypedStackItem::Null,
+        StackItem::Integer(value) => TypedStackItem::Integer {
+            value: value.to_str_radix(10),
+        },
+        StackItem::Cell(cell) => TypedStackItem::Cell {
+            boc: serialize_cell_to_base64(cell, "stack item `Cell`")?,
+        },
+        StackItem::Builder(builder) => {
+            let cell = builder
+                .as_ref()
+                .clone()
+                .into_cell()
+                .map_err(|err| Error::unknown_execution_error(err))?;
+            TypedStackItem::Builder {
+                boc: serialize_cell_to_base64(&cell, "stack item `Builder`")?,
+            }
+        }
+        StackItem::Slice(slice) => {
+            let (boc, data_start, data_end, refs_start, refs_end) =
+                slice_window(slice, "stack item `Slice`")?;
+            TypedStackItem::Slice { boc, data_start, data_end, refs_start, refs_end }
+        }
+        StackItem::Tuple(items) => TypedStackItem::Tuple {
+            items: items
+                .iter()
+                .map(serialize_typed_item)
+                .collect::<ClientResult<_>>()?,
+        },
+        StackItem::Continuation(continuation) => {
+            if !matches!(continuation.type_of, ContinuationType::Ordinary)
+                || continuation.nargs >= 0
+                || continuation.stack.depth() > 0
+                || !continuation.savelist.is_empty()
+            {
+                return Err(Error::unknown_execution_error(
+                    "continuation with the saved control registers, stack or arguments number \
+                    can not be represented as typed stack item",
+                ));
+            }
+            let (boc, data_start, data_end, refs_start, refs_end) =
+                slice_window(continuation.code(), "stack item `Continuation`")?;
+            TypedStackItem::Continuation { boc, data_start, data_end, refs_start, refs_end }
+        }
+    })
+}
+
+/// Converts typed stack item to the TVM stack item.
+pub(crate) fn deserialize_typed_item(
+    context: &ClientContext,
+    item: &TypedStackItem,
+) -> ClientResult<StackItem> {
+    Ok(match item {
+        TypedStackItem::Null => StackItem::None,
+        TypedStackItem::Integer { value } => {
+            if value == "NaN" {
+                StackItem::integer(IntegerData::nan())
+            } else {
+                StackItem::integer(
+                    IntegerData::from_str_radix(value, 10)
+                        .map_err(|err| Error::invalid_input_stack(err, &json!(item)))?,
+                )
+            }
+        }
+        TypedStackItem::Cell { boc } => {
+            StackItem::cell(deserialize_cell_from_boc(context, boc, "Cell")?.1)
+        }
+        TypedStackItem::Builder { boc } => {
+            let cell = deserialize_cell_from_boc(context, boc, "Builder")?.1;
+            StackItem::builder(
+                BuilderData::from_cell(&cell)
+                    .map_err(|err| Error::invalid_input_stack(err, &json!(item)))?,
+            )
+        }
+        TypedStackItem::Slice { boc, data_start, data_end, refs_start, refs_end } => {
+            StackItem::slice(load_slice(
+                context,
+                item,
+                boc,
+                (*data_start, *data_end),
+                (*refs_start, *refs_end),
+            )?)
+        }
+        TypedStackItem::Tuple { items } => StackItem::tuple(
+            items
+                .iter()
+                .map(|item| deserialize_typed_item(context, item))
+                .collect::<ClientResult<_>>()?,
+        ),
+        TypedStackItem::Continuation { boc, data_start, data_end, refs_start, refs_end } => {
+            StackItem::continuation(ContinuationData::with_code(load_slice(
+                context,
+                item,
+                boc,
+                (*data_start, *data_end),
+                (*refs_start, *refs_end),
+            )?))
+        }
+    })
+}
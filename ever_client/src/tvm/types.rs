@@ -12,14 +12,23 @@
  *
  */
 
+use super::trace::{trace_callback, GasProfiler, TraceCollector};
 use super::Error;
 use crate::client::{ClientContext, NetworkParams};
-use crate::error::ClientResult;
+use crate::error::{ClientError, ClientResult};
 use crate::net::network_params::get_default_params;
-use crate::boc::internal::deserialize_object_from_boc;
+use crate::boc::internal::{deserialize_cell_from_boc, deserialize_object_from_boc};
+use crate::boc::libraries::resolve_library_cells;
+use crate::encoding::account_decode;
+use serde_json::Value;
 use std::sync::Arc;
+use ever_block::{
+    Cell, ConfigParam18, ConfigParam8, ConfigParamEnum, ConfigParams, GasLimitsPrices, HashmapE,
+    HashmapType, MsgAddressInt, Serializable, SliceData, UInt256,
+};
+use std::str::FromStr;
 use ever_executor::BlockchainConfig;
-use ever_vm::executor::BehaviorModifiers;
+use ever_vm::executor::{BehaviorModifiers, TraceCallback};
 
 #[derive(Serialize, Deserialize, ApiType, Clone, Default)]
 pub struct ExecutionOptions {
@@ -34,11 +43,269 @@ pub struct ExecutionOptions {
     /// Overrides standard TVM behaviour.
     /// If set to `true` then CHKSIG always will return `true`.
     pub chksig_always_succeed: Option<bool>,
+    /// Overrides standard TVM behaviour.
+    ///
+    /// The modifiers specified here take precedence over `chksig_always_succeed`.
+    pub behavior_modifiers: Option<ExecutionBehaviorModifiers>,
     /// Signature ID to be used in signature verifying instructions when CapSignatureWithId
     /// capability is enabled
     pub signature_id: Option<i32>,
+    /// Records the trace of the executed TVM instructions.
+    ///
+    /// The trace is returned in the `trace` field of the result.
+    /// Attention! Each step contains a stack snapshot so the trace can be large.
+    /// Ignored by the get-method functions.
+    pub trace: Option<bool>,
+    /// Map of the code locations to the source lines used to annotate the trace.
+    ///
+    /// Object with `<code cell hash>:<bit offset>` keys and source location values,
+    /// e.g. `{ "5a1f...:40": "Wallet.sol:12" }`.
+    pub debug_map: Option<Value>,
+    /// Collects gas consumption by code cells and by the ABI function called by the message.
+    ///
+    /// The report is returned in the `profile` field of the result.
+    /// Ignored by the get-method functions.
+    pub profile: Option<bool>,
+    /// Overrides the blockchain config parameters.
+    ///
+    /// Applied to the `blockchain_config` or to the default network config,
+    /// so the contracts can be tested under the upcoming network configs
+    /// without constructing the config BOC.
+    pub config_overrides: Option<ConfigOverrides>,
+    /// Random seed. 256-bit unsigned integer encoded as hex.
+    ///
+    /// `run_tvm` and `run_get` use it as the contract random seed,
+    /// `run_executor` uses it as the block seed the contract random seed
+    /// is derived from. Random by default.
+    pub rand_seed: Option<String>,
+    /// Account balance in nanotokens visible to the contract.
+    ///
+    /// Overrides the balance of the account in `run_tvm` and `run_get`.
+    pub account_balance: Option<u64>,
+    /// Account address visible to the contract.
+    ///
+    /// Overrides the address of the account in `run_tvm` and `run_get`.
+    pub account_address: Option<String>,
+    /// Libraries used by the account code.
+    ///
+    /// Object with library hashes encoded as hex as keys and library cell BOCs
+    /// encoded as base64 or BOC cache keys as values. Used in addition to
+    /// the account own libraries and the libraries from the BOC cache.
+    pub libraries: Option<Value>,
+    /// Queries the libraries referenced by the account or the message from the network
+    /// if they are missing in `libraries` and in the BOC cache. Default is `true`.
+    ///
+    /// The received libraries are saved into the BOC cache.
+    pub resolve_libraries: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default, Debug)]
+pub struct ExecutionBehaviorModifiers {
+    /// If set to `true` then CHKSIG always will return `true`.
+    pub chksig_always_succeed: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default, Debug)]
+pub struct ConfigOverrides {
+    /// Global version (config parameter 8).
+    pub global_version: Option<u32>,
+    /// Global capabilities bitmask (config parameter 8).
+    pub capabilities: Option<u64>,
+    /// Capabilities to enable in addition to the config ones.
+    pub enable_capabilities: Option<u64>,
+    /// Capabilities to disable.
+    pub disable_capabilities: Option<u64>,
+    /// Masterchain gas limits and prices (config parameter 20).
+    pub masterchain_gas_prices: Option<GasPricesOverrides>,
+    /// Workchain gas limits and prices (config parameter 21).
+    pub gas_prices: Option<GasPricesOverrides>,
+    /// Storage prices (config parameter 18).
+    ///
+    /// Applied to all the storage prices periods.
+    pub storage_prices: Option<StoragePricesOverrides>,
+    /// Raw config parameters to inject.
+    ///
+    /// Object with config parameter indexes as keys and parameter cell BOCs
+    /// encoded as `base64` as values, e.g. `{ "34": "te6c..." }`.
+    /// Applied after the other overrides.
+    pub params: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default, Debug)]
+pub struct GasPricesOverrides {
+    /// Gas price in nanotokens * 65536.
+    pub gas_price: Option<u64>,
+    /// Gas limit of the transaction.
+    pub gas_limit: Option<u64>,
+    /// Gas limit of the special accounts transaction.
+    pub special_gas_limit: Option<u64>,
+    /// Gas credit of the external inbound message.
+    pub gas_credit: Option<u64>,
+    /// Gas limit of the block.
+    pub block_gas_limit: Option<u64>,
+    /// Storage debt limit to freeze the account.
+    pub freeze_due_limit: Option<u64>,
+    /// Storage debt limit to delete the account.
+    pub delete_due_limit: Option<u64>,
+    /// Gas amount charged with the flat price.
+    pub flat_gas_limit: Option<u64>,
+    /// Price of the `flat_gas_limit` gas in nanotokens.
+    pub flat_gas_price: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Clone, Default, Debug)]
+pub struct StoragePricesOverrides {
+    /// Workchain bit storage price in nanotokens * 65536 per second.
+    pub bit_price_ps: Option<u64>,
+    /// Workchain cell storage price in nanotokens * 65536 per second.
+    pub cell_price_ps: Option<u64>,
+    /// Masterchain bit storage price in nanotokens * 65536 per second.
+    pub mc_bit_price_ps: Option<u64>,
+    /// Masterchain cell storage price in nanotokens * 65536 per second.
+    pub mc_cell_price_ps: Option<u64>,
+}
+
+fn override_value(target: &mut u64, value: Option<u64>) {
+    if let Some(value) = value {
+        *target = value;
+    }
+}
+
+impl GasPricesOverrides {
+    fn apply(&self, prices: &mut GasLimitsPrices) {
+        override_value(&mut prices.gas_price, self.gas_price);
+        override_value(&mut prices.gas_limit, self.gas_limit);
+        override_value(&mut prices.special_gas_limit, self.special_gas_limit);
+        override_value(&mut prices.gas_credit, self.gas_credit);
+        override_value(&mut prices.block_gas_limit, self.block_gas_limit);
+        override_value(&mut prices.freeze_due_limit, self.freeze_due_limit);
+        override_value(&mut prices.delete_due_limit, self.delete_due_limit);
+        override_value(&mut prices.flat_gas_limit, self.flat_gas_limit);
+        override_value(&mut prices.flat_gas_price, self.flat_gas_price);
+    }
+}
+
+impl ConfigOverrides {
+    fn apply(&self, context: &ClientContext, config: &mut ConfigParams) -> ClientResult<()> {
+        let mut global_version = config
+            .get_global_version()
+            .map_err(|err| Error::can_not_read_blockchain_config(err))?;
+        override_value(&mut global_version.capabilities, self.capabilities);
+        global_version.capabilities |= self.enable_capabilities.unwrap_or_default();
+        global_version.capabilities &= !self.disable_capabilities.unwrap_or_default();
+        if let Some(version) = self.global_version {
+            global_version.version = version;
+        }
+        set_config_param(config, ConfigParamEnum::ConfigParam8(ConfigParam8 { global_version }))?;
+
+        if let Some(overrides) = &self.masterchain_gas_prices {
+            match get_config_param(config, 20)? {
+                ConfigParamEnum::ConfigParam20(mut prices) => {
+                    overrides.apply(&mut prices);
+                    set_config_param(config, ConfigParamEnum::ConfigParam20(prices))?;
+                }
+                _ => return Err(unexpected_config_param(20)),
+            }
+        }
+        if let Some(overrides) = &self.gas_prices {
+            match get_config_param(config, 21)? {
+                ConfigParamEnum::ConfigParam21(mut prices) => {
+                    overrides.apply(&mut prices);
+                    set_config_param(config, ConfigParamEnum::ConfigParam21(prices))?;
+                }
+                _ => return Err(unexpected_config_param(21)),
+            }
+        }
+        if let Some(overrides) = &self.storage_prices {
+            let param = match get_config_param(config, 18)? {
+                ConfigParamEnum::ConfigParam18(param) => param,
+                _ => return Err(unexpected_config_param(18)),
+            };
+            let mut overridden = ConfigParam18::default();
+            let count = param
+                .len()
+                .map_err(|err| Error::can_not_read_blockchain_config(err))?;
+            for index in 0..count {
+                let mut prices = param
+                    .get(index as u32)
+                    .map_err(|err| Error::can_not_read_blockchain_config(err))?;
+                override_value(&mut prices.bit_price_ps, overrides.bit_price_ps);
+                override_value(&mut prices.cell_price_ps, overrides.cell_price_ps);
+                override_value(&mut prices.mc_bit_price_ps, overrides.mc_bit_price_ps);
+                override_value(&mut prices.mc_cell_price_ps, overrides.mc_cell_price_ps);
+                overridden
+                    .insert(&prices)
+                    .map_err(|err| Error::can_not_apply_config_overrides(err))?;
+            }
+            set_config_param(config, ConfigParamEnum::ConfigParam18(overridden))?;
+        }
+        if let Some(params) = self.params.as_ref().and_then(|params| params.as_object()) {
+            for (index, boc) in params {
+                let index = index.parse::<u32>().map_err(|err| {
+                    Error::can_not_apply_config_overrides(format!(
+                        "invalid config parameter index `{}`: {}",
+                        index, err
+                    ))
+                })?;
+                let boc = boc.as_str().ok_or_else(|| {
+                    Error::can_not_apply_config_overrides(format!(
+                        "config parameter {} must be a BOC encoded as base64",
+                        index
+                    ))
+                })?;
+                let (_, cell) = deserialize_cell_from_boc(context, boc, "config parameter")?;
+                let key = index
+                    .serialize()
+                    .and_then(|key| SliceData::load_cell(key))
+                    .map_err(|err| Error::can_not_apply_config_overrides(err))?;
+                config
+                    .config_params
+                    .setref(key, &cell)
+                    .map_err(|err| Error::can_not_apply_config_overrides(err))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The overridden parameters must be present in the config, since the unspecified
+/// fields are taken from it.
+fn get_config_param(config: &ConfigParams, index: u32) -> ClientResult<ConfigParamEnum> {
+    config
+        .config(index)
+        .map_err(|err| Error::can_not_read_blockchain_config(err))?
+        .ok_or_else(|| {
+            Error::can_not_apply_config_overrides(format!(
+                "config parameter {} is missing in the blockchain config",
+                index
+            ))
+        })
+}
+
+fn unexpected_config_param(index: u32) -> ClientError {
+    Error::can_not_apply_config_overrides(format!(
+        "config parameter {} has unexpected type",
+        index
+    ))
+}
+
+fn set_config_param(config: &mut ConfigParams, param: ConfigParamEnum) -> ClientResult<()> {
+    config
+        .set_config(param)
+        .map_err(|err| Error::can_not_apply_config_overrides(err))
+}
+
+fn apply_config_overrides(
+    context: &ClientContext,
+    config: &BlockchainConfig,
+    overrides: &ConfigOverrides,
+) -> ClientResult<BlockchainConfig> {
+    let mut config = config.raw_config().clone();
+    overrides.apply(context, &mut config)?;
+    BlockchainConfig::with_config(config).map_err(|err| Error::can_not_apply_config_overrides(err))
 }
 
+#[derive(Clone)]
 pub(crate) struct ResolvedExecutionOptions {
     pub blockchain_config: Arc<BlockchainConfig>,
     pub signature_id: i32,
@@ -46,6 +313,14 @@ pub(crate) struct ResolvedExecutionOptions {
     pub block_lt: u64,
     pub transaction_lt: u64,
     pub behavior_modifiers: BehaviorModifiers,
+    pub trace: Option<Arc<TraceCollector>>,
+    pub profiler: Option<Arc<GasProfiler>>,
+    pub rand_seed: Option<UInt256>,
+    pub account_balance: Option<u64>,
+    pub account_address: Option<MsgAddressInt>,
+    pub provided_libraries: Option<Value>,
+    pub query_libraries: bool,
+    pub libraries: HashmapE,
 }
 
 pub(crate) fn blockchain_config_from_boc(context: &ClientContext, b64: &str) -> ClientResult<BlockchainConfig> {
@@ -55,6 +330,17 @@ pub(crate) fn blockchain_config_from_boc(context: &ClientContext, b64: &str) ->
 }
 
 impl ResolvedExecutionOptions {
+    pub fn trace_callback(&self) -> Option<Arc<TraceCallback>> {
+        trace_callback(&self.trace, &self.profiler)
+    }
+
+    /// Drops the trace and the profiler for the executions that don't return them.
+    pub fn without_collectors(mut self) -> Self {
+        self.trace = None;
+        self.profiler = None;
+        self
+    }
+
     pub async fn from_options(
         context: &Arc<ClientContext>,
         options: Option<ExecutionOptions>,
@@ -64,6 +350,12 @@ impl ResolvedExecutionOptions {
         let params = resolve_network_params(
             context, options.blockchain_config, options.signature_id
         ).await?;
+        let blockchain_config = match &options.config_overrides {
+            Some(overrides) => {
+                Arc::new(apply_config_overrides(context, &params.blockchain_config, overrides)?)
+            }
+            None => params.blockchain_config,
+        };
 
         let block_lt = options
             .block_lt
@@ -72,19 +364,76 @@ impl ResolvedExecutionOptions {
         let block_time = options
             .block_time
             .unwrap_or_else(|| (context.env.now_ms() / 1000) as u32);
+        let modifiers = options.behavior_modifiers.unwrap_or_default();
+        // all the fields are listed, so the new TVM modifiers can not be missed
         let behavior_modifiers = BehaviorModifiers {
-            chksig_always_succeed: options.chksig_always_succeed.unwrap_or(false),
-            ..Default::default()
+            chksig_always_succeed: modifiers
+                .chksig_always_succeed
+                .or(options.chksig_always_succeed)
+                .unwrap_or(false),
+        };
+        let rand_seed = options
+            .rand_seed
+            .as_ref()
+            .map(|seed| {
+                UInt256::from_str(seed)
+                    .map_err(|err| crate::client::Error::invalid_hex(seed, err))
+            })
+            .transpose()?;
+        let account_address = options
+            .account_address
+            .as_ref()
+            .map(|address| account_decode(address))
+            .transpose()?;
+        let trace = if options.trace.unwrap_or_default() {
+            Some(TraceCollector::new(options.debug_map))
+        } else {
+            None
         };
         Ok(Self {
             block_lt,
             block_time,
-            blockchain_config: params.blockchain_config,
+            blockchain_config,
             signature_id: params.global_id,
             transaction_lt,
             behavior_modifiers,
+            trace,
+            profiler: if options.profile.unwrap_or_default() {
+                Some(GasProfiler::new())
+            } else {
+                None
+            },
+            rand_seed,
+            account_balance: options.account_balance,
+            account_address,
+            provided_libraries: options.libraries,
+            query_libraries: options.resolve_libraries.unwrap_or(true),
+            libraries: HashmapE::with_bit_len(256),
         })
     }
+
+    /// Resolves the libraries referenced by the cells to be executed.
+    ///
+    /// Unresolved libraries are not an error since the referenced code
+    /// can be unreachable, TVM fails only if such library is actually loaded.
+    pub async fn resolve_libraries(
+        &mut self,
+        context: &Arc<ClientContext>,
+        roots: &[Cell],
+    ) -> ClientResult<()> {
+        let resolved = resolve_library_cells(
+            context,
+            roots,
+            self.provided_libraries.as_ref(),
+            self.query_libraries,
+        )
+        .await?;
+        for hash in &resolved.missing {
+            log::warn!("Library {:x} can not be resolved", hash);
+        }
+        self.libraries = resolved.to_dict()?;
+        Ok(())
+    }
 }
 
 pub(crate) async fn resolve_network_params(
@@ -15,8 +15,12 @@ use crate::boc::internal::deserialize_object_from_boc;
 use crate::client::ClientContext;
 use crate::error::ClientResult;
 use crate::tvm::Error;
+use ever_block::{Account, AccountStatus, MsgAddressInt, StorageInfo};
+use ever_executor::BlockchainConfig;
 use std::sync::Arc;
 
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
 #[derive(Serialize, Deserialize, ApiType, Default, Debug)]
 pub struct ParamsOfCalcStorageFee {
     // Account BOC or BOC cache reference
@@ -38,18 +42,136 @@ pub async fn calc_storage_fee(
     params: ParamsOfCalcStorageFee,
 ) -> ClientResult<ResultOfCalcStorageFee> {
     let account =
-        deserialize_object_from_boc::<ever_block::Account>(&context, &params.account, "account")?
-            .object;
+        deserialize_object_from_boc::<Account>(&context, &params.account, "account")?.object;
+    let (storage, addr) = account_storage(&account)?;
+    let config = crate::net::network_params::get_default_params(&context)
+        .await?
+        .blockchain_config;
+
+    let fee = storage_fee_at(&config, storage, &addr, storage.last_paid() + params.period)?;
 
+    Ok(ResultOfCalcStorageFee {
+        fee: format!("{}", fee),
+    })
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug)]
+pub struct ParamsOfForecastStorageFee {
+    /// Account BOC or BOC cache reference
+    pub account: String,
+    /// Number of days the account must stay alive after the forecast time.
+    /// Used to calculate `top_up`. Default is 0.
+    pub keep_alive_days: Option<u32>,
+    /// Forecast time (unix time in seconds). Default is the current time.
+    pub now: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq)]
+pub struct ResultOfForecastStorageFee {
+    /// Account balance in nanotokens.
+    pub balance: String,
+    /// Storage fee accrued since the last payment until the forecast time
+    /// plus the due payment already recorded in the account, in nanotokens.
+    pub storage_fee: String,
+    /// Storage fee for one day in nanotokens at the current storage prices.
+    pub fee_per_day: String,
+    /// Debt limit after which the account is frozen, in nanotokens.
+    pub freeze_due_limit: String,
+    /// Debt limit after which the account is deleted, in nanotokens.
+    pub delete_due_limit: String,
+    /// Time (unix time in seconds) after which the next transaction on the
+    /// account will freeze it.
+    ///
+    /// Equals to the forecast time if the limit is already exceeded.
+    /// Is missing if the account is not active or the limit is not reached
+    /// within the `u32` unix time range.
+    pub freeze_at: Option<u32>,
+    /// Time (unix time in seconds) after which the next transaction on the
+    /// account will delete it.
+    ///
+    /// Equals to the forecast time if the limit is already exceeded.
+    /// Is missing if the limit is not reached within the `u32` unix time range.
+    pub delete_at: Option<u32>,
+    /// Amount in nanotokens that must be added to the account balance to pay
+    /// all of the storage fees for `keep_alive_days` days after the forecast time.
+    pub top_up: String,
+}
+
+/// Forecasts storage fee payments for an account.
+///
+/// Calculates the time when the account debt for storage will exceed the freeze
+/// and the delete limits of the blockchain config, taking into account the account
+/// balance, the due payment and the storage prices. Also calculates the top-up
+/// needed to keep the account without debt for the specified number of days.
+#[api_function]
+pub async fn forecast_storage_fee(
+    context: Arc<ClientContext>,
+    params: ParamsOfForecastStorageFee,
+) -> ClientResult<ResultOfForecastStorageFee> {
+    let account =
+        deserialize_object_from_boc::<Account>(&context, &params.account, "account")?.object;
+    let (storage, addr) = account_storage(&account)?;
+    let config = crate::net::network_params::get_default_params(&context)
+        .await?
+        .blockchain_config;
+
+    let now = params
+        .now
+        .unwrap_or_else(|| (context.env.now_ms() / 1000) as u32)
+        .max(storage.last_paid());
+    let balance = account
+        .balance()
+        .map(|balance| balance.grams.as_u128())
+        .unwrap_or_default();
+    let due_payment = storage
+        .due_payment()
+        .map(|due| due.as_u128())
+        .unwrap_or_default();
+    let gas_config = config.get_gas_config(addr.is_masterchain());
+    let freeze_due_limit = gas_config.freeze_due_limit as u128;
+    let delete_due_limit = gas_config.delete_due_limit as u128;
+
+    let total_fee = |time: u32| -> ClientResult<u128> {
+        Ok(storage_fee_at(&config, storage, &addr, time)? + due_payment)
+    };
+    let debt_exceeds = |time: u32, limit: u128| -> ClientResult<bool> {
+        Ok(total_fee(time)?.saturating_sub(balance) > limit)
+    };
+
+    let freeze_at = if account.status() == AccountStatus::AccStateActive {
+        find_first_time(now, |time| debt_exceeds(time, freeze_due_limit))?
+    } else {
+        None
+    };
+    let delete_at = find_first_time(now, |time| debt_exceeds(time, delete_due_limit))?;
+
+    let storage_fee = total_fee(now)?;
+    let day_end = now.saturating_add(SECONDS_PER_DAY);
+    let fee_per_day = total_fee(day_end)? - storage_fee;
+    let keep_alive_till =
+        now.saturating_add(params.keep_alive_days.unwrap_or(0).saturating_mul(SECONDS_PER_DAY));
+    let top_up = total_fee(keep_alive_till)?.saturating_sub(balance);
+
+    Ok(ResultOfForecastStorageFee {
+        balance: balance.to_string(),
+        storage_fee: storage_fee.to_string(),
+        fee_per_day: fee_per_day.to_string(),
+        freeze_due_limit: freeze_due_limit.to_string(),
+        delete_due_limit: delete_due_limit.to_string(),
+        freeze_at,
+        delete_at,
+        top_up: top_up.to_string(),
+    })
+}
+
+fn account_storage(account: &Account) -> ClientResult<(&StorageInfo, MsgAddressInt)> {
     let storage = account
         .storage_info()
         .ok_or(Error::invalid_account_boc("Account is None"))?;
     let addr = account
         .get_addr()
-        .ok_or(Error::invalid_account_boc("Account is None"))?;
-    let config = crate::net::network_params::get_default_params(&context)
-        .await?
-        .blockchain_config;
+        .ok_or(Error::invalid_account_boc("Account is None"))?
+        .clone();
 
     if storage.last_paid() == 0 {
         return Err(Error::invalid_account_boc(
@@ -57,17 +179,43 @@ pub async fn calc_storage_fee(
         ));
     }
 
-    let fee = config
-        .calc_storage_fee(
-            storage,
-            addr.is_masterchain(),
-            storage.last_paid() + params.period,
-        )
+    Ok((storage, addr))
+}
+
+fn storage_fee_at(
+    config: &BlockchainConfig,
+    storage: &StorageInfo,
+    addr: &MsgAddressInt,
+    time: u32,
+) -> ClientResult<u128> {
+    config
+        .calc_storage_fee(storage, addr.is_masterchain(), time)
+        .map(|fee| fee.as_u128())
         .map_err(|err| {
             Error::invalid_account_boc(format!("can not calculate storage fee: {}", err))
-        })?;
+        })
+}
 
-    Ok(ResultOfCalcStorageFee {
-        fee: format!("{}", fee.as_u128()),
-    })
+/// Finds the first time starting from `from` for which `predicate` holds.
+/// `predicate` must be monotonic: once it holds, it holds for all later times.
+fn find_first_time(
+    from: u32,
+    predicate: impl Fn(u32) -> ClientResult<bool>,
+) -> ClientResult<Option<u32>> {
+    if predicate(from)? {
+        return Ok(Some(from));
+    }
+    if !predicate(u32::MAX)? {
+        return Ok(None);
+    }
+    let (mut low, mut high) = (from, u32::MAX);
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if predicate(middle)? {
+            high = middle;
+        } else {
+            low = middle;
+        }
+    }
+    Ok(Some(high))
 }
@@ -15,19 +15,29 @@ use std::io::Cursor;
 
 use crate::error::ClientResult;
 
-/// Compresses data using Zstandard algorithm
-pub fn compress_zstd(uncompressed: &[u8], level: Option<i32>) -> ClientResult<Vec<u8>> {
-    let level =  match level {
-        None => 0,
+/// Default maximum dictionary size, the same as used by the `zstd` command line tool
+pub const DEFAULT_ZSTD_DICTIONARY_SIZE: usize = 112640;
+
+/// Maximum size of the item decompressed with a dictionary
+const MAX_DECOMPRESSED_ITEM_SIZE: usize = 16 * 1024 * 1024;
+
+fn check_level(level: Option<i32>) -> ClientResult<i32> {
+    match level {
+        None => Ok(0),
         Some(level) => {
             if !(1..=21).contains(&level) {
                 return Err(super::errors::Error::compression_error(
                     format!("Invalid compression level: {}", level)
                 ));
             }
-            level
+            Ok(level)
         }
-    };
+    }
+}
+
+/// Compresses data using Zstandard algorithm
+pub fn compress_zstd(uncompressed: &[u8], level: Option<i32>) -> ClientResult<Vec<u8>> {
+    let level = check_level(level)?;
 
     let mut compressed = Vec::new();
     zstd::stream::copy_encode(
@@ -47,3 +57,72 @@ pub fn decompress_zstd(compressed: &[u8]) -> ClientResult<Vec<u8>> {
 
     Ok(decompressed)
 }
+
+/// Trains Zstandard dictionary on the samples
+pub fn train_zstd_dictionary<S: AsRef<[u8]>>(
+    samples: &[S],
+    max_size: usize,
+) -> ClientResult<Vec<u8>> {
+    zstd::dict::from_samples(samples, max_size)
+        .map_err(|err| super::errors::Error::compression_error(
+            format!("Unable to train dictionary: {}", err)
+        ))
+}
+
+/// Compresses every item separately using Zstandard algorithm and optional dictionary
+///
+/// The dictionary is loaded once for the whole batch.
+pub fn compress_zstd_batch<S: AsRef<[u8]>>(
+    items: &[S],
+    level: Option<i32>,
+    dictionary: Option<&[u8]>,
+) -> ClientResult<Vec<Vec<u8>>> {
+    let dictionary = match dictionary {
+        Some(dictionary) => dictionary,
+        None => return items.iter().map(|item| compress_zstd(item.as_ref(), level)).collect(),
+    };
+    let level = check_level(level)?;
+
+    let mut compressor = zstd::bulk::Compressor::with_dictionary(level, dictionary)
+        .map_err(|err| super::errors::Error::compression_error(
+            format!("Unable to load dictionary: {}", err)
+        ))?;
+    items
+        .iter()
+        .map(|item| compressor
+            .compress(item.as_ref())
+            .map_err(|err| super::errors::Error::compression_error(err))
+        )
+        .collect()
+}
+
+/// Decompresses every item separately using Zstandard algorithm and optional dictionary
+///
+/// The dictionary is loaded once for the whole batch. Items decompressed with the dictionary
+/// are limited to 16 MB.
+pub fn decompress_zstd_batch<S: AsRef<[u8]>>(
+    items: &[S],
+    dictionary: Option<&[u8]>,
+) -> ClientResult<Vec<Vec<u8>>> {
+    let dictionary = match dictionary {
+        Some(dictionary) => dictionary,
+        None => return items.iter().map(|item| decompress_zstd(item.as_ref())).collect(),
+    };
+
+    let mut decompressor = zstd::bulk::Decompressor::with_dictionary(dictionary)
+        .map_err(|err| super::errors::Error::decompression_error(
+            format!("Unable to load dictionary: {}", err)
+        ))?;
+    items
+        .iter()
+        .map(|item| {
+            let item = item.as_ref();
+            // unknown and invalid content sizes are reported as values above the limit
+            let capacity = zstd::zstd_safe::get_frame_content_size(item)
+                .min(MAX_DECOMPRESSED_ITEM_SIZE as u64) as usize;
+            decompressor
+                .decompress(item, capacity)
+                .map_err(|err| super::errors::Error::decompression_error(err))
+        })
+        .collect()
+}
@@ -23,13 +23,17 @@ pub(crate) mod json;
 mod errors;
 
 pub use calc_storage_fee::{
-    calc_storage_fee, ParamsOfCalcStorageFee, ResultOfCalcStorageFee
+    calc_storage_fee, forecast_storage_fee, ParamsOfCalcStorageFee, ParamsOfForecastStorageFee,
+    ResultOfCalcStorageFee, ResultOfForecastStorageFee,
 };
 pub use conversion::{
     convert_address, AddressStringFormat, ParamsOfConvertAddress, ResultOfConvertAddress,
     get_address_type, ParamsOfGetAddressType, ResultOfGetAddressType,
 };
 #[cfg(feature = "include-zstd")]
-pub use compression::{compress_zstd, decompress_zstd};
+pub use compression::{
+    compress_zstd, compress_zstd_batch, decompress_zstd, decompress_zstd_batch,
+    train_zstd_dictionary,
+};
 pub use errors::{Error, ErrorCode};
 pub use crate::encoding::AccountAddressType;
@@ -3,8 +3,11 @@ use crate::json_interface::modules::UtilsModule;
 use crate::encoding::AccountAddressType;
 use crate::error::ClientResult;
 use crate::tests::TestClient;
+use ever_block::{Deserializable, Serializable};
 use crate::json_interface::utils::{
-    ParamsOfCompressZstd, ResultOfCompressZstd, ResultOfDecompressZstd, ParamsOfDecompressZstd
+    ParamsOfCompressZstd, ResultOfCompressZstd, ResultOfDecompressZstd, ParamsOfDecompressZstd,
+    ParamsOfTrainZstdDictionary, ResultOfTrainZstdDictionary, ParamsOfCompressZstdBatch,
+    ResultOfCompressZstdBatch, ParamsOfDecompressZstdBatch, ResultOfDecompressZstdBatch,
 };
 
 use super::*;
@@ -89,6 +92,57 @@ async fn test_calc_storage_fee() {
     assert_eq!(result.fee, "330");
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_forecast_storage_fee() {
+    let client = TestClient::new();
+
+    let mut account = ever_block::Account::construct_from_bytes(
+        include_bytes!("../boc/test_data/account.boc")
+    ).unwrap();
+    account.set_balance(ever_block::CurrencyCollection::with_grams(1000));
+    let last_paid = account.storage_info().unwrap().last_paid();
+    let account = base64::encode(&account.write_to_bytes().unwrap());
+
+    let calc_fee = |period: u32| {
+        let client = &client;
+        let account = account.clone();
+        async move {
+            let result: ResultOfCalcStorageFee = client.request_async(
+                "utils.calc_storage_fee",
+                ParamsOfCalcStorageFee { account, period },
+            ).await.unwrap();
+            result.fee.parse::<u128>().unwrap()
+        }
+    };
+
+    let result: ResultOfForecastStorageFee = client.request_async(
+        "utils.forecast_storage_fee",
+        ParamsOfForecastStorageFee {
+            account: account.clone(),
+            keep_alive_days: Some(1),
+            now: Some(last_paid),
+        }
+    ).await.unwrap();
+
+    let day_fee = calc_fee(24 * 60 * 60).await;
+    let due_payment = result.storage_fee.parse::<u128>().unwrap();
+    assert_eq!(result.balance, "1000");
+    assert_eq!(result.fee_per_day, day_fee.to_string());
+    assert_eq!(
+        result.top_up,
+        (day_fee + due_payment).saturating_sub(1000).to_string()
+    );
+
+    let freeze_due_limit = result.freeze_due_limit.parse::<u128>().unwrap();
+    let freeze_at = result.freeze_at.unwrap();
+    let debt = |fee: u128| (fee + due_payment).saturating_sub(1000);
+    assert!(debt(calc_fee(freeze_at - last_paid).await) > freeze_due_limit);
+    assert!(debt(calc_fee(freeze_at - last_paid - 1).await) <= freeze_due_limit);
+
+    let delete_at = result.delete_at.unwrap();
+    assert!(delete_at >= freeze_at);
+}
+
 #[test]
 fn test_compression() {
     let client = TestClient::new();
@@ -105,6 +159,7 @@ fn test_compression() {
         ParamsOfCompressZstd {
             uncompressed: base64::encode(uncompressed),
             level: Some(21),
+            dictionary: None,
         }
     ).unwrap();
 
@@ -113,7 +168,8 @@ fn test_compression() {
     let decompressed: ResultOfDecompressZstd = client.request(
         "utils.decompress_zstd",
         ParamsOfDecompressZstd {
-            compressed: compressed.compressed
+            compressed: compressed.compressed,
+            dictionary: None,
         }
     ).unwrap();
 
@@ -143,7 +199,8 @@ fn test_decompression() {
     let decompressed: ResultOfDecompressZstd = client.request(
         "utils.decompress_zstd",
         ParamsOfDecompressZstd {
-            compressed: compressed.to_string()
+            compressed: compressed.to_string(),
+            dictionary: None,
         }
     ).unwrap();
 
@@ -152,6 +209,90 @@ fn test_decompression() {
     assert_eq!(decompressed, uncompressed);
 }
 
+#[test]
+fn test_compression_with_dictionary() {
+    let client = TestClient::new();
+    let samples: Vec<Vec<u8>> = (0..1000u64)
+        .map(|i| format!(
+            "{{\"id\":\"0:{:064x}\",\"workchain_id\":0,\"acc_type\":1,\"balance\":\"{}\",\
+            \"last_paid\":{},\
+            \"code_hash\":\"4e92716de61d456e58f16e4e867e3e93a7548321eace86301b51c8b80ca6239b\"}}",
+            i.wrapping_mul(0x9e3779b97f4a7c15),
+            i * 1_000_000_007,
+            1_600_000_000 + i,
+        ).into_bytes())
+        .collect();
+
+    let dictionary: ResultOfTrainZstdDictionary = client.request(
+        "utils.train_zstd_dictionary",
+        ParamsOfTrainZstdDictionary {
+            samples: samples.iter().map(base64::encode).collect(),
+            max_size: Some(4096),
+        }
+    ).unwrap();
+    assert!(base64::decode(&dictionary.dictionary).unwrap().len() <= 4096);
+
+    let items: Vec<String> = samples[..10].iter().map(base64::encode).collect();
+    let plain: ResultOfCompressZstdBatch = client.request(
+        "utils.compress_zstd_batch",
+        ParamsOfCompressZstdBatch {
+            items: items.clone(),
+            level: Some(19),
+            dictionary: None,
+        }
+    ).unwrap();
+    let compressed: ResultOfCompressZstdBatch = client.request(
+        "utils.compress_zstd_batch",
+        ParamsOfCompressZstdBatch {
+            items: items.clone(),
+            level: Some(19),
+            dictionary: Some(dictionary.dictionary.clone()),
+        }
+    ).unwrap();
+    let total_size = |items: &[String]| -> usize {
+        items.iter().map(|item| base64::decode(item).unwrap().len()).sum()
+    };
+    assert_eq!(compressed.compressed.len(), items.len());
+    assert!(total_size(&compressed.compressed) < total_size(&plain.compressed));
+
+    let decompressed: ResultOfDecompressZstdBatch = client.request(
+        "utils.decompress_zstd_batch",
+        ParamsOfDecompressZstdBatch {
+            items: compressed.compressed.clone(),
+            dictionary: Some(dictionary.dictionary.clone()),
+        }
+    ).unwrap();
+    assert_eq!(decompressed.decompressed, items);
+
+    let single: ResultOfCompressZstd = client.request(
+        "utils.compress_zstd",
+        ParamsOfCompressZstd {
+            uncompressed: items[0].clone(),
+            level: Some(19),
+            dictionary: Some(dictionary.dictionary.clone()),
+        }
+    ).unwrap();
+    assert_eq!(single.compressed, compressed.compressed[0]);
+
+    let decompressed: ResultOfDecompressZstd = client.request(
+        "utils.decompress_zstd",
+        ParamsOfDecompressZstd {
+            compressed: single.compressed.clone(),
+            dictionary: Some(dictionary.dictionary),
+        }
+    ).unwrap();
+    assert_eq!(decompressed.decompressed, items[0]);
+
+    let error = client.request::<_, ResultOfDecompressZstd>(
+        "utils.decompress_zstd",
+        ParamsOfDecompressZstd {
+            compressed: single.compressed,
+            dictionary: None,
+        }
+    ).unwrap_err();
+    assert_eq!(error.code, ErrorCode::CompressionError as u32);
+}
+
 #[test]
 fn test_get_address_type() {
     let client = TestClient::new();